@@ -0,0 +1,42 @@
+extern crate blurz;
+
+use std::error::Error;
+use std::io::{Read, Write};
+
+use blurz::bluetooth_profile::{
+    BluetoothProfile as Profile, ProfileEvent, ProfileOptions, ProfileRole,
+};
+use blurz::bluetooth_session::BluetoothSession as Session;
+
+static SERIAL_PORT_UUID: &'static str = "00001101-0000-1000-8000-00805f9b34fb";
+
+fn test10() -> Result<(), Box<Error>> {
+    let session = &Session::create_session(None)?;
+    let options = ProfileOptions::new()
+        .name("Serial Port")
+        .role(ProfileRole::Server)
+        .channel(1)
+        .require_authentication(true);
+    let profile = Profile::register(session, "/org/blurz/serial", SERIAL_PORT_UUID, &options)?;
+
+    loop {
+        match profile.next_event(1000) {
+            Some(ProfileEvent::NewConnection(mut connection)) => {
+                println!("Connection from {}", connection.device);
+                let mut buf = [0u8; 256];
+                let n = connection.stream.read(&mut buf)?;
+                connection.stream.write_all(&buf[..n])?;
+            }
+            Some(ProfileEvent::Release) => return Ok(()),
+            Some(event) => println!("{:?}", event),
+            None => (),
+        }
+    }
+}
+
+fn main() {
+    match test10() {
+        Ok(_) => (),
+        Err(e) => println!("{:?}", e),
+    }
+}
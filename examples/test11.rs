@@ -0,0 +1,45 @@
+extern crate blurz;
+
+use std::error::Error;
+
+use blurz::bluetooth_adapter::BluetoothAdapter as Adapter;
+use blurz::bluetooth_device::BluetoothDevice as Device;
+use blurz::bluetooth_event::BluetoothEvent;
+use blurz::bluetooth_media_control::BluetoothMediaControl as MediaControl;
+use blurz::bluetooth_media_folder::MediaFilter;
+use blurz::bluetooth_session::BluetoothSession as Session;
+
+fn test11() -> Result<(), Box<Error>> {
+    let session = &Session::create_session(None)?;
+    let adapter: Adapter = Adapter::init(session)?;
+    let device: Device = adapter.get_first_device()?;
+
+    let control = MediaControl::new(session, device.get_id());
+    let player = control.get_player()?;
+    println!("Player: {} ({:?})", player.get_name()?, player.get_status()?);
+    println!("Track: {:?}", player.get_track()?);
+
+    if player.is_browsable()? {
+        let folder = player.get_folder();
+        for item in folder.list_items(&MediaFilter::new().start(0).end(9))? {
+            println!("{}: {}", item.get_id(), item.get_name()?);
+        }
+    }
+
+    player.play()?;
+    for event in session.incoming(10000).map(BluetoothEvent::from) {
+        match event {
+            Some(BluetoothEvent::Status { status, .. }) => println!("Status: {:?}", status),
+            Some(BluetoothEvent::Track { track, .. }) => println!("Track: {:?}", track),
+            _ => (),
+        }
+    }
+    player.pause()
+}
+
+fn main() {
+    match test11() {
+        Ok(_) => (),
+        Err(e) => println!("{:?}", e),
+    }
+}
@@ -47,12 +47,9 @@ fn test2() -> Result<(), Box<Error>> {
             if uuid == COLOR_PICKER_SERVICE_UUID || uuid == BATTERY_SERVICE_UUID {
                 println!("{:?} has a service!", device.get_alias());
                 println!("connect device...");
-                device.connect(10000).ok();
+                device.connect_and_resolve(15000).ok();
                 if try!(device.is_connected()) {
                     println!("checking gatt...");
-                    // We need to wait a bit after calling connect to safely
-                    // get the gatt services
-                    thread::sleep(Duration::from_millis(5000));
                     match device.get_gatt_services() {
                         Ok(_) => break 'device_loop,
                         Err(e) => println!("{:?}", e),
@@ -8,9 +8,8 @@ use std::path::Path;
 
 use blurz::bluetooth_adapter::BluetoothAdapter as Adapter;
 use blurz::bluetooth_device::BluetoothDevice as Device;
-use blurz::bluetooth_obex::{
-    BluetoothOBEXSession as OBEXSession, BluetoothOBEXTransfer as OBEXTransfer,
-};
+use blurz::bluetooth_obex::ObexSessionOptions;
+use blurz::bluetooth_obex_client::ObexClient;
 use blurz::bluetooth_session::BluetoothSession as Session;
 
 fn test_obex_file_transfer() -> Result<(), Box<Error>> {
@@ -29,17 +28,22 @@ fn test_obex_file_transfer() -> Result<(), Box<Error>> {
     let device_id: &str = &filtered_devices[0];
     let device = Device::new(session, device_id.to_string());
 
-    let session = OBEXSession::new(session, &device)?;
+    let client = ObexClient::new()?;
+    let object_push = client.object_push(&device, &ObexSessionOptions::new())?;
 
     let mut empty_file = File::create("./test.png")?;
     empty_file.write_all(b"1111")?;
 
     let file_path = Path::new("./test.png").canonicalize()?;
     let file_str = file_path.to_str().unwrap();
-    let transfer = OBEXTransfer::send_file(&session, file_str)?;
-    transfer.wait_until_transfer_completed()?;
+    let transfer = object_push.send_file(file_str)?;
+    transfer.wait_with_progress(30000, |progress| {
+        println!(
+            "{} bytes sent, {:.0} B/s",
+            progress.transferred, progress.bytes_per_second
+        )
+    })?;
 
-    session.remove_session()?;
     fs::remove_file(&file_path)?;
     Ok(())
 }
@@ -0,0 +1,36 @@
+extern crate blurz;
+
+use std::error::Error;
+use std::time::Duration;
+
+use blurz::bluetooth_event::{BluetoothEvent, BluetoothEventKind};
+use blurz::bluetooth_event_dispatcher::{BluetoothEventDispatcher as Dispatcher, EventFilter};
+
+fn test7() -> Result<(), Box<Error>> {
+    let dispatcher = Dispatcher::spawn(None)?;
+
+    dispatcher.add_handler(
+        EventFilter::new().kind(BluetoothEventKind::Powered),
+        |event: &BluetoothEvent| println!("Power changed: {:?}", event),
+    );
+
+    let (_, connections) = dispatcher.add_channel(
+        EventFilter::new()
+            .path_prefix("/org/bluez/hci0/")
+            .interface("org.bluez.Device1")
+            .kind(BluetoothEventKind::Connected),
+    );
+
+    loop {
+        if let Ok(event) = connections.recv_timeout(Duration::from_millis(1000)) {
+            println!("{:?}", event);
+        }
+    }
+}
+
+fn main() {
+    match test7() {
+        Ok(_) => (),
+        Err(e) => println!("{:?}", e),
+    }
+}
@@ -0,0 +1,32 @@
+extern crate blurz;
+
+use std::error::Error;
+
+use blurz::bluetooth_adapter::BluetoothAdapter as Adapter;
+use blurz::bluetooth_beacon::BeaconScanner;
+use blurz::bluetooth_session::BluetoothSession as Session;
+use blurz::formats::beacon::Beacon;
+
+fn test8() -> Result<(), Box<Error>> {
+    let session = &Session::create_session(None)?;
+    let adapter: Adapter = Adapter::init(session)?;
+    let scanner = BeaconScanner::new(session, adapter.get_id())?;
+    scanner.start()?;
+    for scanned in scanner.beacons(10000) {
+        match scanned.beacon {
+            Beacon::IBeacon(ref ibeacon) => println!(
+                "{} iBeacon {} {}/{}",
+                scanned.device, ibeacon.uuid, ibeacon.major, ibeacon.minor
+            ),
+            ref beacon => println!("{} {:?}", scanned.device, beacon),
+        }
+    }
+    scanner.stop()
+}
+
+fn main() {
+    match test8() {
+        Ok(_) => (),
+        Err(e) => println!("{:?}", e),
+    }
+}
@@ -0,0 +1,32 @@
+extern crate blurz;
+
+use std::error::Error;
+
+use blurz::bluetooth_adapter::BluetoothAdapter as Adapter;
+use blurz::bluetooth_battery_provider::BluetoothBatteryProvider as BatteryProvider;
+use blurz::bluetooth_device::BluetoothDevice as Device;
+use blurz::bluetooth_session::BluetoothSession as Session;
+
+fn test9() -> Result<(), Box<Error>> {
+    let session = &Session::create_session(None)?;
+    let adapter: Adapter = Adapter::init(session)?;
+    let device: Device = adapter.get_first_device()?;
+
+    let provider = BatteryProvider::register(session, adapter.get_id(), "/org/blurz/battery")?;
+    provider.add_battery(&device.get_id(), 100, Some("vendor protocol"))?;
+
+    // The provider objects are served while the session is polled.
+    for percentage in (0..11).rev().map(|p| p * 10) {
+        for _ in session.incoming(1000) {}
+        provider.set_percentage(&device.get_id(), percentage)?;
+        println!("{}: {}%", device.get_id(), percentage);
+    }
+    provider.unregister()
+}
+
+fn main() {
+    match test9() {
+        Ok(_) => (),
+        Err(e) => println!("{:?}", e),
+    }
+}
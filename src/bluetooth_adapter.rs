@@ -1,4 +1,5 @@
 use bluetooth_device::BluetoothDevice;
+use bluetooth_network_server::BluetoothNetworkServer;
 use bluetooth_session::BluetoothSession;
 use bluetooth_utils;
 use dbus::MessageItem;
@@ -65,6 +66,10 @@ impl<'a> BluetoothAdapter<'a> {
         bluetooth_utils::list_devices(self.session.get_connection(), &self.object_path)
     }
 
+    pub fn get_network_server(&self) -> BluetoothNetworkServer<'a> {
+        BluetoothNetworkServer::new(self.session, self.object_path.clone())
+    }
+
     fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
         bluetooth_utils::get_property(
             self.session.get_connection(),
@@ -148,6 +153,17 @@ impl<'a> BluetoothAdapter<'a> {
         self.set_property("Powered", value, 10000)
     }
 
+    pub fn wait_powered(&self, timeout_ms: u32) -> Result<(), Box<Error>> {
+        try!(self.session.wait_for_property(
+            &self.object_path,
+            ADAPTER_INTERFACE,
+            "Powered",
+            |value| value.inner::<bool>() == Ok(true),
+            timeout_ms
+        ));
+        Ok(())
+    }
+
     // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/adapter-api.txt#n156
     pub fn is_discoverable(&self) -> Result<bool, Box<Error>> {
         let discoverable = try!(self.get_property("Discoverable"));
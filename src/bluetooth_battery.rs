@@ -0,0 +1,65 @@
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::MessageItem;
+
+use std::error::Error;
+
+static BATTERY_INTERFACE: &'static str = "org.bluez.Battery1";
+
+// Battery1 lives on the device object, so `object_path` is the device path.
+#[derive(Clone, Debug)]
+pub struct BluetoothBattery<'a> {
+    object_path: String,
+    session: &'a BluetoothSession,
+}
+
+impl<'a> BluetoothBattery<'a> {
+    pub fn new(session: &'a BluetoothSession, object_path: String) -> BluetoothBattery {
+        BluetoothBattery {
+            object_path: object_path,
+            session: session,
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
+        bluetooth_utils::get_property(
+            self.session.get_connection(),
+            BATTERY_INTERFACE,
+            &self.object_path,
+            prop,
+        )
+    }
+
+    /*
+     * Properties
+     */
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/battery-api.txt#n12
+    pub fn get_percentage(&self) -> Result<u8, Box<Error>> {
+        let percentage = try!(self.get_property("Percentage"));
+        Ok(percentage.inner::<u8>().unwrap())
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/battery-api.txt#n16
+    pub fn get_source(&self) -> Result<String, Box<Error>> {
+        let source = try!(self.get_property("Source"));
+        Ok(String::from(source.inner::<&str>().unwrap()))
+    }
+
+    // Blocks until the percentage changes or `timeout_ms` elapses, returning the new value.
+    pub fn wait_percentage_change(&self, timeout_ms: u32) -> Result<u8, Box<Error>> {
+        let current = try!(self.get_percentage());
+        let value = try!(self.session.wait_for_property(
+            &self.object_path,
+            BATTERY_INTERFACE,
+            "Percentage",
+            |value| value.inner::<u8>().ok() != Some(current),
+            timeout_ms,
+        ));
+        Ok(value.inner::<u8>().unwrap())
+    }
+}
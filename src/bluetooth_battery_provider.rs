@@ -0,0 +1,362 @@
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::{
+    Message, MessageItem, MessageItemArray, MessageType, MsgHandler, MsgHandlerResult,
+    MsgHandlerType, Path, Signature,
+};
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::rc::Rc;
+
+static BATTERY_PROVIDER_MANAGER_INTERFACE: &'static str = "org.bluez.BatteryProviderManager1";
+static BATTERY_PROVIDER_INTERFACE: &'static str = "org.bluez.BatteryProvider1";
+static OBJECT_MANAGER_INTERFACE: &'static str = "org.freedesktop.DBus.ObjectManager";
+static PROPERTIES_INTERFACE: &'static str = "org.freedesktop.DBus.Properties";
+static INVALID_ARGS_ERROR: &'static str = "org.freedesktop.DBus.Error.InvalidArgs";
+
+#[derive(Clone, Debug)]
+struct ProvidedBattery {
+    device: String,
+    percentage: u8,
+    source: Option<String>,
+}
+
+impl ProvidedBattery {
+    fn properties(&self) -> MessageItem {
+        let mut properties = vec![
+            (
+                "Device",
+                MessageItem::ObjectPath(Path::from(self.device.clone())),
+            ),
+            ("Percentage", MessageItem::Byte(self.percentage)),
+        ];
+        if let Some(ref source) = self.source {
+            properties.push(("Source", MessageItem::Str(source.clone())));
+        }
+        bluetooth_utils::properties_dict(properties)
+    }
+
+    fn interfaces(&self) -> MessageItem {
+        MessageItem::Array(
+            MessageItemArray::new(
+                vec![MessageItem::DictEntry(
+                    Box::new(BATTERY_PROVIDER_INTERFACE.into()),
+                    Box::new(self.properties()),
+                )],
+                Signature::from("a{sa{sv}}"),
+            ).unwrap(),
+        )
+    }
+}
+
+// Battery object path -> battery
+type Batteries = Rc<RefCell<BTreeMap<String, ProvidedBattery>>>;
+
+// Exports BatteryProvider1 objects below `root` and registers them with the
+// BatteryProviderManager1 of an adapter, so BlueZ presents the levels as the
+// Battery1 interface of the devices.
+//
+// BlueZ queries the provider through the session's connection, so the session
+// must keep being polled (e.g. with `incoming`) for the batteries to show up.
+pub struct BluetoothBatteryProvider<'a> {
+    session: &'a BluetoothSession,
+    adapter: String,
+    root: String,
+    batteries: Batteries,
+    active: Rc<Cell<bool>>,
+    // Whether BlueZ knows the provider, so that dropping it unregisters it.
+    registered: bool,
+}
+
+impl<'a> BluetoothBatteryProvider<'a> {
+    pub fn register(
+        session: &'a BluetoothSession,
+        adapter: String,
+        root: &str,
+    ) -> Result<BluetoothBatteryProvider<'a>, Box<Error>> {
+        let batteries: Batteries = Rc::new(RefCell::new(BTreeMap::new()));
+        let active = Rc::new(Cell::new(true));
+        let connection = session.get_connection();
+        try!(connection.register_object_path(root));
+        connection.add_handler(BatteryProviderHandler {
+            root: String::from(root),
+            batteries: batteries.clone(),
+            active: active.clone(),
+        });
+
+        let mut provider = BluetoothBatteryProvider {
+            session: session,
+            adapter: adapter,
+            root: String::from(root),
+            batteries: batteries,
+            active: active,
+            registered: false,
+        };
+        try!(provider.call_manager("RegisterBatteryProvider"));
+        provider.registered = true;
+        Ok(provider)
+    }
+
+    pub fn get_root(&self) -> String {
+        self.root.clone()
+    }
+
+    fn call_manager(&self, method: &str) -> Result<(), Box<Error>> {
+        bluetooth_utils::call_method(
+            self.session.get_connection(),
+            BATTERY_PROVIDER_MANAGER_INTERFACE,
+            &self.adapter,
+            method,
+            Some(&[MessageItem::ObjectPath(Path::from(self.root.clone()))]),
+            1000,
+        )
+    }
+
+    // "/org/bluez/hci0/dev_XX" is exported as "<root>/hci0_dev_XX", keeping the
+    // adapter so that devices of different adapters don't collide.
+    fn battery_path(&self, device: &str) -> String {
+        format!(
+            "{}/{}",
+            self.root,
+            device
+                .trim_start_matches("/org/bluez/")
+                .trim_matches('/')
+                .replace('/', "_")
+        )
+    }
+
+    // Starts providing the battery level of `device`, returns the exported object path.
+    pub fn add_battery(
+        &self,
+        device: &str,
+        percentage: u8,
+        source: Option<&str>,
+    ) -> Result<String, Box<Error>> {
+        if percentage > 100 {
+            return Err(Box::from(format!(
+                "Invalid battery percentage {}",
+                percentage
+            )));
+        }
+        let path = self.battery_path(device);
+        if self.batteries.borrow().contains_key(&path) {
+            return Err(Box::from(format!(
+                "Battery for {} already provided",
+                device
+            )));
+        }
+        try!(self.session.get_connection().register_object_path(&path));
+        let battery = ProvidedBattery {
+            device: String::from(device),
+            percentage: percentage,
+            source: source.map(String::from),
+        };
+        let interfaces = battery.interfaces();
+        self.batteries.borrow_mut().insert(path.clone(), battery);
+        // Without the signal BlueZ never learns about the battery, so it is not kept.
+        if let Err(err) = self.interfaces_added(&path, interfaces) {
+            self.batteries.borrow_mut().remove(&path);
+            self.session.get_connection().unregister_object_path(&path);
+            return Err(err);
+        }
+        Ok(path)
+    }
+
+    fn interfaces_added(&self, path: &str, interfaces: MessageItem) -> Result<(), Box<Error>> {
+        let mut m = try!(Message::new_signal(
+            self.root.clone(),
+            OBJECT_MANAGER_INTERFACE,
+            "InterfacesAdded"
+        ));
+        m.append_items(&[
+            MessageItem::ObjectPath(Path::from(String::from(path))),
+            interfaces,
+        ]);
+        self.send(m)
+    }
+
+    pub fn set_percentage(&self, device: &str, percentage: u8) -> Result<(), Box<Error>> {
+        if percentage > 100 {
+            return Err(Box::from(format!(
+                "Invalid battery percentage {}",
+                percentage
+            )));
+        }
+        let path = self.battery_path(device);
+        match self.batteries.borrow_mut().get_mut(&path) {
+            Some(battery) => battery.percentage = percentage,
+            None => return Err(Box::from(format!("No battery provided for {}", device))),
+        }
+        let mut m = try!(Message::new_signal(
+            path,
+            PROPERTIES_INTERFACE,
+            "PropertiesChanged"
+        ));
+        m.append_items(&[
+            BATTERY_PROVIDER_INTERFACE.into(),
+            bluetooth_utils::properties_dict(vec![("Percentage", MessageItem::Byte(percentage))]),
+            MessageItem::Array(MessageItemArray::new(vec![], Signature::from("as")).unwrap()),
+        ]);
+        self.send(m)
+    }
+
+    pub fn remove_battery(&self, device: &str) -> Result<(), Box<Error>> {
+        let path = self.battery_path(device);
+        if self.batteries.borrow_mut().remove(&path).is_none() {
+            return Err(Box::from(format!("No battery provided for {}", device)));
+        }
+        self.session.get_connection().unregister_object_path(&path);
+        let mut m = try!(Message::new_signal(
+            self.root.clone(),
+            OBJECT_MANAGER_INTERFACE,
+            "InterfacesRemoved"
+        ));
+        m.append_items(&[
+            MessageItem::ObjectPath(Path::from(path)),
+            MessageItem::Array(
+                MessageItemArray::new(
+                    vec![BATTERY_PROVIDER_INTERFACE.into()],
+                    Signature::from("as"),
+                ).unwrap(),
+            ),
+        ]);
+        self.send(m)
+    }
+
+    fn send(&self, m: Message) -> Result<(), Box<Error>> {
+        match self.session.get_connection().send(m) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Box::from("Failed to send signal")),
+        }
+    }
+
+    // Unregisters the provider from BlueZ and removes the exported objects.
+    // Same as dropping the provider, but reports whether BlueZ accepted it.
+    pub fn unregister(mut self) -> Result<(), Box<Error>> {
+        self.registered = false;
+        self.call_manager("UnregisterBatteryProvider")
+    }
+}
+
+impl<'a> Drop for BluetoothBatteryProvider<'a> {
+    fn drop(&mut self) {
+        if self.registered {
+            let _ = self.call_manager("UnregisterBatteryProvider");
+        }
+        self.active.set(false);
+        let connection = self.session.get_connection();
+        for path in self.batteries.borrow().keys() {
+            connection.unregister_object_path(path);
+        }
+        connection.unregister_object_path(&self.root);
+    }
+}
+
+struct BatteryProviderHandler {
+    root: String,
+    batteries: Batteries,
+    active: Rc<Cell<bool>>,
+}
+
+impl BatteryProviderHandler {
+    fn get_managed_objects(&self, msg: &Message) -> Option<Message> {
+        let entries = self
+            .batteries
+            .borrow()
+            .iter()
+            .map(|(path, battery)| {
+                MessageItem::DictEntry(
+                    Box::new(MessageItem::ObjectPath(Path::from(path.clone()))),
+                    Box::new(battery.interfaces()),
+                )
+            })
+            .collect();
+        Message::new_method_return(msg).map(|m| {
+            m.append(MessageItem::Array(
+                MessageItemArray::new(entries, Signature::from("a{oa{sa{sv}}}")).unwrap(),
+            ))
+        })
+    }
+
+    fn get_properties(
+        &self,
+        msg: &Message,
+        member: &str,
+        battery: &ProvidedBattery,
+    ) -> Option<Message> {
+        let (interface, name) = match member {
+            "Get" => match msg.get2::<&str, &str>() {
+                (Some(interface), Some(name)) => (interface, Some(name)),
+                _ => return None,
+            },
+            "GetAll" => match msg.get1::<&str>() {
+                Some(interface) => (interface, None),
+                None => return None,
+            },
+            _ => return None,
+        };
+        if interface != BATTERY_PROVIDER_INTERFACE {
+            return Message::new_error(msg, INVALID_ARGS_ERROR, "Unknown interface");
+        }
+        let properties = battery.properties();
+        let name = match name {
+            Some(name) => name,
+            None => return Message::new_method_return(msg).map(|m| m.append(properties)),
+        };
+        let value = properties
+            .inner::<&[MessageItem]>()
+            .unwrap()
+            .iter()
+            .filter_map(|entry| entry.inner::<(&MessageItem, &MessageItem)>().ok())
+            .find(|&(key, _)| key.inner::<&str>().ok() == Some(name))
+            .map(|(_, value)| value.clone());
+        match value {
+            Some(value) => Message::new_method_return(msg).map(|m| m.append(value)),
+            None => Message::new_error(msg, INVALID_ARGS_ERROR, "Unknown property"),
+        }
+    }
+}
+
+impl MsgHandler for BatteryProviderHandler {
+    fn handler_type(&self) -> MsgHandlerType {
+        MsgHandlerType::MsgType(MessageType::MethodCall)
+    }
+
+    fn handle_msg(&mut self, msg: &Message) -> Option<MsgHandlerResult> {
+        if !self.active.get() {
+            return Some(MsgHandlerResult {
+                handled: false,
+                done: true,
+                reply: Vec::new(),
+            });
+        }
+        let path = match msg.path() {
+            Some(path) => path.to_string(),
+            None => return None,
+        };
+        let interface = msg.interface().map(|i| i.to_string()).unwrap_or_default();
+        let member = msg.member().map(|m| m.to_string()).unwrap_or_default();
+        let reply = if path == self.root {
+            if interface != OBJECT_MANAGER_INTERFACE || member != "GetManagedObjects" {
+                return None;
+            }
+            self.get_managed_objects(msg)
+        } else {
+            let battery = match self.batteries.borrow().get(&path) {
+                Some(battery) => battery.clone(),
+                None => return None,
+            };
+            if interface != PROPERTIES_INTERFACE {
+                return None;
+            }
+            self.get_properties(msg, &member, &battery)
+        };
+        Some(MsgHandlerResult {
+            handled: true,
+            done: false,
+            reply: reply.into_iter().collect(),
+        })
+    }
+}
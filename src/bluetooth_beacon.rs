@@ -0,0 +1,147 @@
+use bluetooth_discovery_session::BluetoothDiscoverySession;
+use bluetooth_event::BluetoothEvent;
+use bluetooth_session::{BluetoothSession, SessionMessages};
+use dbus::arg::{RefArg, Variant};
+use dbus::{Message, Path};
+use formats::beacon::Beacon;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+
+static DEVICE_INTERFACE: &'static str = "org.bluez.Device1";
+static OBJECT_MANAGER_INTERFACE: &'static str = "org.freedesktop.DBus.ObjectManager";
+
+impl Beacon {
+    // Beacons carried by a ManufacturerData or ServiceData event.
+    pub fn from_event(event: &BluetoothEvent) -> Vec<Beacon> {
+        match *event {
+            BluetoothEvent::ManufacturerData {
+                ref manufacturer_data,
+                ..
+            } => Beacon::from_advertisement(manufacturer_data, &HashMap::new()),
+            BluetoothEvent::ServiceData {
+                ref service_data, ..
+            } => Beacon::from_advertisement(&HashMap::new(), service_data),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScannedBeacon {
+    pub device: String,
+    // Signal strength reported with the advertisement, if it changed.
+    pub rssi: Option<i16>,
+    pub beacon: Beacon,
+}
+
+// Runs a discovery session on an adapter and decodes the beacons of the
+// advertisements reported while it is active.
+//
+// BlueZ only reports advertisement data when a device appears or its data
+// changes, so a beacon with constant payload is yielded once per scan.
+pub struct BeaconScanner<'a> {
+    session: &'a BluetoothSession,
+    discovery_session: BluetoothDiscoverySession<'a>,
+}
+
+impl<'a> BeaconScanner<'a> {
+    pub fn new(
+        session: &'a BluetoothSession,
+        adapter: String,
+    ) -> Result<BeaconScanner<'a>, Box<Error>> {
+        Ok(BeaconScanner {
+            session: session,
+            discovery_session: try!(BluetoothDiscoverySession::create_session(session, adapter)),
+        })
+    }
+
+    pub fn start(&self) -> Result<(), Box<Error>> {
+        self.discovery_session.start_discovery()
+    }
+
+    pub fn stop(&self) -> Result<(), Box<Error>> {
+        self.discovery_session.stop_discovery()
+    }
+
+    // Iterates over the decoded beacons, ending once no message arrives for `timeout_ms`.
+    pub fn beacons(&self, timeout_ms: u32) -> BeaconScan<'a> {
+        BeaconScan {
+            messages: self.session.incoming_queued(timeout_ms),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+pub struct BeaconScan<'a> {
+    messages: SessionMessages<'a>,
+    pending: VecDeque<ScannedBeacon>,
+}
+
+impl<'a> BeaconScan<'a> {
+    fn queue(&mut self, events: Vec<BluetoothEvent>) {
+        let rssi = events
+            .iter()
+            .filter_map(|e| match *e {
+                BluetoothEvent::RSSI { rssi, .. } => Some(rssi),
+                _ => None,
+            })
+            .next();
+        for event in events.iter() {
+            let device = match event.get_object_path() {
+                Some(path) => path.to_string(),
+                None => continue,
+            };
+            for beacon in Beacon::from_event(event) {
+                self.pending.push_back(ScannedBeacon {
+                    device: device.clone(),
+                    rssi: rssi,
+                    beacon: beacon,
+                });
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for BeaconScan<'a> {
+    type Item = ScannedBeacon;
+
+    fn next(&mut self) -> Option<ScannedBeacon> {
+        loop {
+            if let Some(beacon) = self.pending.pop_front() {
+                return Some(beacon);
+            }
+            let msg = match self.messages.next() {
+                Some(msg) => msg,
+                None => return None,
+            };
+            let events = match interfaces_added(&msg) {
+                Some(events) => events,
+                None => BluetoothEvent::all_from(&msg).unwrap_or_default(),
+            };
+            self.queue(events);
+        }
+    }
+}
+
+// Devices seen for the first time are announced with InterfacesAdded rather than
+// PropertiesChanged.
+fn interfaces_added(msg: &Message) -> Option<Vec<BluetoothEvent>> {
+    if msg.interface().as_ref().map(|i| &**i) != Some(OBJECT_MANAGER_INTERFACE)
+        || msg.member().as_ref().map(|m| &**m) != Some("InterfacesAdded")
+    {
+        return None;
+    }
+    let (path, interfaces) =
+        match msg.read2::<Path, HashMap<String, HashMap<String, Variant<Box<RefArg>>>>>() {
+            Ok(args) => args,
+            Err(_) => return None,
+        };
+    match interfaces.get(DEVICE_INTERFACE) {
+        Some(properties) => Some(BluetoothEvent::from_properties(
+            &path,
+            DEVICE_INTERFACE,
+            properties,
+        )),
+        None => Some(Vec::new()),
+    }
+}
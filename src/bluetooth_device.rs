@@ -1,9 +1,16 @@
+use bluetooth_gatt_characteristic::BluetoothGATTCharacteristic;
+use bluetooth_gatt_database::GattDatabase;
+use bluetooth_gatt_descriptor::BluetoothGATTDescriptor;
+use bluetooth_gatt_service::BluetoothGATTService;
+use bluetooth_input::BluetoothInput;
+use bluetooth_network::BluetoothNetwork;
 use bluetooth_session::BluetoothSession;
 use bluetooth_utils;
 use dbus::MessageItem;
 use hex::FromHex;
 use std::collections::HashMap;
 use std::error::Error;
+use std::time::Instant;
 
 static DEVICE_INTERFACE: &'static str = "org.bluez.Device1";
 
@@ -270,6 +277,93 @@ impl<'a> BluetoothDevice<'a> {
         bluetooth_utils::list_services(self.session.get_connection(), &self.object_path)
     }
 
+    pub fn get_gatt_database(&self) -> Result<GattDatabase, Box<Error>> {
+        GattDatabase::fetch(self.session, &self.object_path)
+    }
+
+    pub fn get_service_by_uuid(&self, uuid: &str) -> Result<BluetoothGATTService<'a>, Box<Error>> {
+        let database = try!(self.get_gatt_database());
+        match database.find_service(uuid) {
+            Some(service) => Ok(BluetoothGATTService::new(
+                self.session,
+                service.object_path.clone(),
+            )),
+            None => Err(Box::from(format!("GATT service {} not found", uuid))),
+        }
+    }
+
+    // Characteristics of the service `service_uuid` as (UUID, characteristic) pairs,
+    // resolved from a single GattDatabase snapshot.
+    pub fn get_service_characteristics(
+        &self,
+        service_uuid: &str,
+    ) -> Result<Vec<(String, BluetoothGATTCharacteristic<'a>)>, Box<Error>> {
+        let database = try!(self.get_gatt_database());
+        match database.find_service(service_uuid) {
+            Some(service) => Ok(service
+                .characteristics
+                .iter()
+                .map(|c| {
+                    (
+                        c.uuid.clone(),
+                        BluetoothGATTCharacteristic::new(self.session, c.object_path.clone()),
+                    )
+                })
+                .collect()),
+            None => Err(Box::from(format!(
+                "GATT service {} not found",
+                service_uuid
+            ))),
+        }
+    }
+
+    pub fn get_characteristic(
+        &self,
+        service_uuid: &str,
+        characteristic_uuid: &str,
+    ) -> Result<BluetoothGATTCharacteristic<'a>, Box<Error>> {
+        let database = try!(self.get_gatt_database());
+        match database.find_characteristic(service_uuid, characteristic_uuid) {
+            Some(characteristic) => Ok(BluetoothGATTCharacteristic::new(
+                self.session,
+                characteristic.object_path.clone(),
+            )),
+            None => Err(Box::from(format!(
+                "GATT characteristic {} not found in service {}",
+                characteristic_uuid, service_uuid
+            ))),
+        }
+    }
+
+    pub fn get_descriptor(
+        &self,
+        service_uuid: &str,
+        characteristic_uuid: &str,
+        descriptor_uuid: &str,
+    ) -> Result<BluetoothGATTDescriptor<'a>, Box<Error>> {
+        let database = try!(self.get_gatt_database());
+        match database.find_descriptor(service_uuid, characteristic_uuid, descriptor_uuid) {
+            Some(descriptor) => Ok(BluetoothGATTDescriptor::new(
+                self.session,
+                descriptor.object_path.clone(),
+            )),
+            None => Err(Box::from(format!(
+                "GATT descriptor {} not found in characteristic {}",
+                descriptor_uuid, characteristic_uuid
+            ))),
+        }
+    }
+
+    // Input1 of HID devices.
+    pub fn get_input(&self) -> BluetoothInput<'a> {
+        BluetoothInput::new(self.session, self.object_path.clone())
+    }
+
+    // Network1 of devices offering a PAN service.
+    pub fn get_network(&self) -> BluetoothNetwork<'a> {
+        BluetoothNetwork::new(self.session, self.object_path.clone())
+    }
+
     /*
      * Methods
      */
@@ -279,6 +373,29 @@ impl<'a> BluetoothDevice<'a> {
         self.call_method("Connect", None, timeout_ms)
     }
 
+    // Connects and blocks until the remote GATT services have been resolved,
+    // `timeout_ms` covers both steps.
+    pub fn connect_and_resolve(&self, timeout_ms: i32) -> Result<(), Box<Error>> {
+        let start = Instant::now();
+        try!(self.connect(timeout_ms));
+        let elapsed = start.elapsed();
+        let elapsed_ms =
+            elapsed.as_secs() as i32 * 1000 + (elapsed.subsec_nanos() / 1_000_000) as i32;
+        let remaining_ms = if timeout_ms > elapsed_ms {
+            (timeout_ms - elapsed_ms) as u32
+        } else {
+            0
+        };
+        try!(self.session.wait_for_property(
+            &self.object_path,
+            DEVICE_INTERFACE,
+            "ServicesResolved",
+            |value| value.inner::<bool>() == Ok(true),
+            remaining_ms
+        ));
+        Ok(())
+    }
+
     // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/device-api.txt#n29
     pub fn disconnect(&self) -> Result<(), Box<Error>> {
         self.call_method("Disconnect", None, 5000)
@@ -1,5 +1,11 @@
+use bluetooth_media_player::{self, MediaTrack, PlayerStatus, RepeatMode, ShuffleMode};
+use bluetooth_obex::TransferState;
 use dbus::{arg::cast, arg::RefArg, arg::TypeMismatchError, arg::Variant, Message};
 use std::collections::HashMap;
+use std::hash::Hash;
+
+static MEDIA_PLAYER_INTERFACE: &'static str = "org.bluez.MediaPlayer1";
+static TRANSFER_INTERFACE: &'static str = "org.bluez.obex.Transfer1";
 
 #[derive(Clone, Debug)]
 pub enum BluetoothEvent {
@@ -27,89 +33,334 @@ pub enum BluetoothEvent {
         object_path: String,
         rssi: i16,
     },
+    ManufacturerData {
+        object_path: String,
+        manufacturer_data: HashMap<u16, Vec<u8>>,
+    },
+    ServiceData {
+        object_path: String,
+        service_data: HashMap<String, Vec<u8>>,
+    },
+    Percentage {
+        object_path: String,
+        percentage: u8,
+    },
+    Status {
+        object_path: String,
+        status: PlayerStatus,
+    },
+    Position {
+        object_path: String,
+        position: u32,
+    },
+    Track {
+        object_path: String,
+        track: MediaTrack,
+    },
+    Repeat {
+        object_path: String,
+        repeat: RepeatMode,
+    },
+    Shuffle {
+        object_path: String,
+        shuffle: ShuffleMode,
+    },
+    Equalizer {
+        object_path: String,
+        equalizer: bool,
+    },
+    TransferStatus {
+        object_path: String,
+        status: TransferState,
+    },
+    Transferred {
+        object_path: String,
+        transferred: u64,
+    },
+    None,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BluetoothEventKind {
+    Powered,
+    Discovering,
+    Connected,
+    ServicesResolved,
+    Value,
+    RSSI,
+    ManufacturerData,
+    ServiceData,
+    Percentage,
+    Status,
+    Position,
+    Track,
+    Repeat,
+    Shuffle,
+    Equalizer,
+    TransferStatus,
+    Transferred,
     None,
 }
 
 impl BluetoothEvent {
     pub fn from(conn_msg: Message) -> Option<BluetoothEvent> {
+        match BluetoothEvent::all_from(&conn_msg) {
+            Some(events) => Some(
+                events
+                    .into_iter()
+                    .next()
+                    .unwrap_or(BluetoothEvent::None),
+            ),
+            None => None,
+        }
+    }
+
+    // Unlike `from`, this keeps every recognised property of a PropertiesChanged
+    // signal, so e.g. Connected and ServicesResolved arriving together are both reported.
+    pub fn all_from(conn_msg: &Message) -> Option<Vec<BluetoothEvent>> {
         let result: Result<
             (&str, HashMap<String, Variant<Box<RefArg>>>),
             TypeMismatchError,
         > = conn_msg.read2();
 
         match result {
-            Ok((_, properties)) => {
-                let object_path = conn_msg.path().unwrap().to_string();
-
-                if let Some(value) = properties.get("Powered") {
-                    if let Some(powered) = cast::<bool>(&value.0) {
-                        let event = BluetoothEvent::Powered {
-                            object_path: object_path.clone(),
-                            powered: *powered,
-                        };
-
-                        return Some(event);
-                    }
-                }
-
-                if let Some(value) = properties.get("Discovering") {
-                    if let Some(discovering) = cast::<bool>(&value.0) {
-                        let event = BluetoothEvent::Discovering {
-                            object_path: object_path.clone(),
-                            discovering: *discovering,
-                        };
-
-                        return Some(event);
-                    }
-                }
-
-                if let Some(value) = properties.get("Connected") {
-                    if let Some(connected) = cast::<bool>(&value.0) {
-                        let event = BluetoothEvent::Connected {
-                            object_path: object_path.clone(),
-                            connected: *connected,
-                        };
-
-                        return Some(event);
-                    }
-                }
-
-                if let Some(value) = properties.get("ServicesResolved") {
-                    if let Some(services_resolved) = cast::<bool>(&value.0) {
-                        let event = BluetoothEvent::ServicesResolved {
-                            object_path: object_path.clone(),
-                            services_resolved: *services_resolved,
-                        };
-
-                        return Some(event);
-                    }
-                }
-
-                if let Some(value) = properties.get("Value") {
-                    if let Some(value) = cast::<Vec<u8>>(&value.0) {
-                        let event = BluetoothEvent::Value {
-                            object_path: object_path.clone(),
-                            value: value.clone().into_boxed_slice(),
-                        };
-
-                        return Some(event);
-                    }
-                }
-
-                if let Some(value) = properties.get("RSSI") {
-                    if let Some(rssi) = cast::<i16>(&value.0) {
-                        let event = BluetoothEvent::RSSI {
-                            object_path: object_path.clone(),
-                            rssi: *rssi,
-                        };
-
-                        return Some(event);
-                    }
-                }
-
-                Some(BluetoothEvent::None)
-            }
+            Ok((interface, properties)) => Some(BluetoothEvent::from_properties(
+                &conn_msg.path().unwrap(),
+                interface,
+                &properties,
+            )),
             Err(_err) => None,
         }
     }
+
+    // Events for the recognised entries of a property map of `interface`, as carried
+    // by PropertiesChanged and InterfacesAdded signals.
+    pub fn from_properties(
+        object_path: &str,
+        interface: &str,
+        properties: &HashMap<String, Variant<Box<RefArg>>>,
+    ) -> Vec<BluetoothEvent> {
+        let object_path = object_path.to_string();
+        let mut events = Vec::new();
+
+        if let Some(value) = properties.get("Powered") {
+            if let Some(powered) = cast::<bool>(&value.0) {
+                events.push(BluetoothEvent::Powered {
+                    object_path: object_path.clone(),
+                    powered: *powered,
+                });
+            }
+        }
+
+        if let Some(value) = properties.get("Discovering") {
+            if let Some(discovering) = cast::<bool>(&value.0) {
+                events.push(BluetoothEvent::Discovering {
+                    object_path: object_path.clone(),
+                    discovering: *discovering,
+                });
+            }
+        }
+
+        if let Some(value) = properties.get("Connected") {
+            if let Some(connected) = cast::<bool>(&value.0) {
+                events.push(BluetoothEvent::Connected {
+                    object_path: object_path.clone(),
+                    connected: *connected,
+                });
+            }
+        }
+
+        if let Some(value) = properties.get("ServicesResolved") {
+            if let Some(services_resolved) = cast::<bool>(&value.0) {
+                events.push(BluetoothEvent::ServicesResolved {
+                    object_path: object_path.clone(),
+                    services_resolved: *services_resolved,
+                });
+            }
+        }
+
+        if let Some(value) = properties.get("Value") {
+            if let Some(value) = cast::<Vec<u8>>(&value.0) {
+                events.push(BluetoothEvent::Value {
+                    object_path: object_path.clone(),
+                    value: value.clone().into_boxed_slice(),
+                });
+            }
+        }
+
+        if let Some(value) = properties.get("RSSI") {
+            if let Some(rssi) = cast::<i16>(&value.0) {
+                events.push(BluetoothEvent::RSSI {
+                    object_path: object_path.clone(),
+                    rssi: *rssi,
+                });
+            }
+        }
+
+        if let Some(value) = properties.get("ManufacturerData") {
+            if let Some(manufacturer_data) =
+                byte_array_dict(&*value.0, |k| k.as_u64().map(|k| k as u16))
+            {
+                events.push(BluetoothEvent::ManufacturerData {
+                    object_path: object_path.clone(),
+                    manufacturer_data: manufacturer_data,
+                });
+            }
+        }
+
+        if let Some(value) = properties.get("ServiceData") {
+            if let Some(service_data) = byte_array_dict(&*value.0, |k| k.as_str().map(String::from))
+            {
+                events.push(BluetoothEvent::ServiceData {
+                    object_path: object_path.clone(),
+                    service_data: service_data,
+                });
+            }
+        }
+
+        if let Some(value) = properties.get("Percentage") {
+            if let Some(percentage) = cast::<u8>(&value.0) {
+                events.push(BluetoothEvent::Percentage {
+                    object_path: object_path.clone(),
+                    percentage: *percentage,
+                });
+            }
+        }
+
+        if interface == MEDIA_PLAYER_INTERFACE {
+            events.extend(bluetooth_media_player::player_events(
+                &object_path,
+                properties,
+            ));
+        }
+
+        if interface == TRANSFER_INTERFACE {
+            let status = properties.get("Status").and_then(|value| value.0.as_str());
+            if let Some(status) = status.and_then(TransferState::from_name) {
+                events.push(BluetoothEvent::TransferStatus {
+                    object_path: object_path.clone(),
+                    status: status,
+                });
+            }
+        }
+
+        if let Some(value) = properties.get("Transferred") {
+            if let Some(transferred) = cast::<u64>(&value.0) {
+                events.push(BluetoothEvent::Transferred {
+                    object_path: object_path.clone(),
+                    transferred: *transferred,
+                });
+            }
+        }
+
+        events
+    }
+
+    pub fn kind(&self) -> BluetoothEventKind {
+        match *self {
+            BluetoothEvent::Powered { .. } => BluetoothEventKind::Powered,
+            BluetoothEvent::Discovering { .. } => BluetoothEventKind::Discovering,
+            BluetoothEvent::Connected { .. } => BluetoothEventKind::Connected,
+            BluetoothEvent::ServicesResolved { .. } => BluetoothEventKind::ServicesResolved,
+            BluetoothEvent::Value { .. } => BluetoothEventKind::Value,
+            BluetoothEvent::RSSI { .. } => BluetoothEventKind::RSSI,
+            BluetoothEvent::ManufacturerData { .. } => BluetoothEventKind::ManufacturerData,
+            BluetoothEvent::ServiceData { .. } => BluetoothEventKind::ServiceData,
+            BluetoothEvent::Percentage { .. } => BluetoothEventKind::Percentage,
+            BluetoothEvent::Status { .. } => BluetoothEventKind::Status,
+            BluetoothEvent::Position { .. } => BluetoothEventKind::Position,
+            BluetoothEvent::Track { .. } => BluetoothEventKind::Track,
+            BluetoothEvent::Repeat { .. } => BluetoothEventKind::Repeat,
+            BluetoothEvent::Shuffle { .. } => BluetoothEventKind::Shuffle,
+            BluetoothEvent::Equalizer { .. } => BluetoothEventKind::Equalizer,
+            BluetoothEvent::TransferStatus { .. } => BluetoothEventKind::TransferStatus,
+            BluetoothEvent::Transferred { .. } => BluetoothEventKind::Transferred,
+            BluetoothEvent::None => BluetoothEventKind::None,
+        }
+    }
+
+    pub fn get_object_path(&self) -> Option<&str> {
+        match *self {
+            BluetoothEvent::Powered {
+                ref object_path, ..
+            }
+            | BluetoothEvent::Discovering {
+                ref object_path, ..
+            }
+            | BluetoothEvent::Connected {
+                ref object_path, ..
+            }
+            | BluetoothEvent::ServicesResolved {
+                ref object_path, ..
+            }
+            | BluetoothEvent::Value {
+                ref object_path, ..
+            }
+            | BluetoothEvent::RSSI {
+                ref object_path, ..
+            }
+            | BluetoothEvent::ManufacturerData {
+                ref object_path, ..
+            }
+            | BluetoothEvent::ServiceData {
+                ref object_path, ..
+            }
+            | BluetoothEvent::Percentage {
+                ref object_path, ..
+            }
+            | BluetoothEvent::Status {
+                ref object_path, ..
+            }
+            | BluetoothEvent::Position {
+                ref object_path, ..
+            }
+            | BluetoothEvent::Track {
+                ref object_path, ..
+            }
+            | BluetoothEvent::Repeat {
+                ref object_path, ..
+            }
+            | BluetoothEvent::Shuffle {
+                ref object_path, ..
+            }
+            | BluetoothEvent::Equalizer {
+                ref object_path, ..
+            }
+            | BluetoothEvent::TransferStatus {
+                ref object_path, ..
+            }
+            | BluetoothEvent::Transferred {
+                ref object_path, ..
+            } => Some(object_path),
+            BluetoothEvent::None => None,
+        }
+    }
+}
+
+// Decodes the a{qv} / a{sv} maps of byte arrays used by ManufacturerData and ServiceData.
+fn byte_array_dict<K, F>(dict: &RefArg, key: F) -> Option<HashMap<K, Vec<u8>>>
+where
+    K: Eq + Hash,
+    F: Fn(&RefArg) -> Option<K>,
+{
+    let mut items = match dict.as_iter() {
+        Some(items) => items,
+        None => return None,
+    };
+    let mut map = HashMap::new();
+    while let (Some(k), Some(v)) = (items.next(), items.next()) {
+        let key = match key(k) {
+            Some(key) => key,
+            None => return None,
+        };
+        let bytes = match v.as_iter().and_then(|mut variant| variant.next()) {
+            Some(array) => match array.as_iter() {
+                Some(bytes) => bytes.filter_map(|b| b.as_u64()).map(|b| b as u8).collect(),
+                None => return None,
+            },
+            None => return None,
+        };
+        map.insert(key, bytes);
+    }
+    Some(map)
 }
@@ -0,0 +1,305 @@
+use bluetooth_event::{BluetoothEvent, BluetoothEventKind};
+use bluetooth_session::BluetoothSession;
+use dbus::Message;
+
+use std::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+static PROPERTIES_INTERFACE: &'static str = "org.freedesktop.DBus.Properties";
+
+// How long the background thread blocks on the bus before checking the stop flag.
+const BACKGROUND_POLL_MS: u32 = 200;
+
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    path_prefix: Option<String>,
+    interface: Option<String>,
+    kind: Option<BluetoothEventKind>,
+}
+
+impl EventFilter {
+    pub fn new() -> EventFilter {
+        EventFilter::default()
+    }
+
+    pub fn path_prefix(mut self, path_prefix: &str) -> EventFilter {
+        self.path_prefix = Some(path_prefix.to_string());
+        self
+    }
+
+    pub fn interface(mut self, interface: &str) -> EventFilter {
+        self.interface = Some(interface.to_string());
+        self
+    }
+
+    pub fn kind(mut self, kind: BluetoothEventKind) -> EventFilter {
+        self.kind = Some(kind);
+        self
+    }
+
+    fn matches(&self, interface: Option<&str>, event: &BluetoothEvent) -> bool {
+        if let Some(ref prefix) = self.path_prefix {
+            match event.get_object_path() {
+                Some(path) if path.starts_with(prefix.as_str()) => (),
+                _ => return false,
+            }
+        }
+        if let Some(ref wanted) = self.interface {
+            if interface != Some(wanted.as_str()) {
+                return false;
+            }
+        }
+        if let Some(kind) = self.kind {
+            if event.kind() != kind {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HandlerId(usize);
+
+enum Handler {
+    Callback(Box<FnMut(&BluetoothEvent) + Send>),
+    Channel(Sender<BluetoothEvent>),
+}
+
+impl Handler {
+    // Returns false for channels whose receiver has been dropped.
+    fn deliver(&mut self, event: &BluetoothEvent) -> bool {
+        match *self {
+            Handler::Callback(ref mut callback) => {
+                callback(event);
+                true
+            }
+            Handler::Channel(ref sender) => sender.send(event.clone()).is_ok(),
+        }
+    }
+}
+
+struct Registration {
+    id: HandlerId,
+    filter: EventFilter,
+    handler: Arc<Mutex<Handler>>,
+}
+
+#[derive(Default)]
+struct HandlerList {
+    next_id: usize,
+    registrations: Vec<Registration>,
+}
+
+impl HandlerList {
+    fn add(&mut self, filter: EventFilter, handler: Handler) -> HandlerId {
+        let id = HandlerId(self.next_id);
+        self.next_id += 1;
+        self.registrations.push(Registration {
+            id: id,
+            filter: filter,
+            handler: Arc::new(Mutex::new(handler)),
+        });
+        id
+    }
+
+    fn remove(&mut self, ids: &[HandlerId]) -> bool {
+        let before = self.registrations.len();
+        self.registrations
+            .retain(|registration| !ids.contains(&registration.id));
+        self.registrations.len() != before
+    }
+
+    fn matching(
+        &self,
+        interface: Option<&str>,
+        event: &BluetoothEvent,
+    ) -> Vec<(HandlerId, Arc<Mutex<Handler>>)> {
+        self.registrations
+            .iter()
+            .filter(|registration| registration.filter.matches(interface, event))
+            .map(|registration| (registration.id, registration.handler.clone()))
+            .collect()
+    }
+}
+
+struct Worker {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+// Routes BluetoothEvents converted from bus messages to registered handlers.
+//
+// A dispatcher created with `new` is driven by the caller through `process` (or
+// `dispatch` for messages taken from an existing loop). One created with `spawn`
+// owns its own BluetoothSession on a background thread until it is stopped or dropped.
+pub struct BluetoothEventDispatcher {
+    handlers: Arc<Mutex<HandlerList>>,
+    worker: Option<Worker>,
+}
+
+impl BluetoothEventDispatcher {
+    pub fn new() -> BluetoothEventDispatcher {
+        BluetoothEventDispatcher {
+            handlers: Arc::new(Mutex::new(HandlerList::default())),
+            worker: None,
+        }
+    }
+
+    pub fn spawn(path: Option<&str>) -> Result<BluetoothEventDispatcher, Box<Error>> {
+        let mut dispatcher = BluetoothEventDispatcher::new();
+        let handlers = dispatcher.handlers.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let path = path.map(|p| p.to_string());
+        let (ready_tx, ready_rx) = channel::<Result<(), String>>();
+
+        let thread = thread::Builder::new()
+            .name("blurz-event-dispatcher".to_string())
+            .spawn(move || {
+                let session = match BluetoothSession::create_session(
+                    path.as_ref().map(|p| p.as_str()),
+                ) {
+                    Ok(session) => {
+                        let _ = ready_tx.send(Ok(()));
+                        session
+                    }
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e.to_string()));
+                        return;
+                    }
+                };
+                while !thread_stop.load(Ordering::SeqCst) {
+                    for msg in session.incoming(BACKGROUND_POLL_MS) {
+                        route(&handlers, &msg);
+                        if thread_stop.load(Ordering::SeqCst) {
+                            break;
+                        }
+                    }
+                }
+            })?;
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => (),
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                return Err(Box::from(e));
+            }
+            Err(_) => {
+                let _ = thread.join();
+                return Err(Box::from("Event dispatcher thread exited unexpectedly"));
+            }
+        }
+
+        dispatcher.worker = Some(Worker {
+            stop: stop,
+            thread: thread,
+        });
+        Ok(dispatcher)
+    }
+
+    pub fn add_handler<F>(&self, filter: EventFilter, handler: F) -> HandlerId
+    where
+        F: FnMut(&BluetoothEvent) + Send + 'static,
+    {
+        self.handlers
+            .lock()
+            .unwrap()
+            .add(filter, Handler::Callback(Box::new(handler)))
+    }
+
+    pub fn add_channel(&self, filter: EventFilter) -> (HandlerId, Receiver<BluetoothEvent>) {
+        let (sender, receiver) = channel();
+        let id = self
+            .handlers
+            .lock()
+            .unwrap()
+            .add(filter, Handler::Channel(sender));
+        (id, receiver)
+    }
+
+    // An event being delivered while the handler is removed may still reach it.
+    pub fn remove_handler(&self, id: HandlerId) -> bool {
+        self.handlers.lock().unwrap().remove(&[id])
+    }
+
+    // Routes a single message, returns the number of handler invocations.
+    pub fn dispatch(&self, msg: &Message) -> usize {
+        route(&self.handlers, msg)
+    }
+
+    // Drains the messages of `session` that arrive within `timeout_ms`, after the ones
+    // its waiting methods kept.
+    pub fn process(&self, session: &BluetoothSession, timeout_ms: u32) -> usize {
+        let mut delivered = 0;
+        for msg in session.incoming_queued(timeout_ms) {
+            delivered += self.dispatch(&msg);
+        }
+        delivered
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.worker.is_some()
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            worker.stop.store(true, Ordering::SeqCst);
+            let _ = worker.thread.join();
+        }
+    }
+}
+
+impl Default for BluetoothEventDispatcher {
+    fn default() -> BluetoothEventDispatcher {
+        BluetoothEventDispatcher::new()
+    }
+}
+
+impl Drop for BluetoothEventDispatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn route(handlers: &Mutex<HandlerList>, msg: &Message) -> usize {
+    let events = match BluetoothEvent::all_from(msg) {
+        Some(events) => events,
+        None => return 0,
+    };
+    if events.is_empty() {
+        return 0;
+    }
+
+    // For PropertiesChanged the interface that changed is the first argument,
+    // the message interface itself is always org.freedesktop.DBus.Properties.
+    let interface: Option<String> = match msg.interface() {
+        Some(ref i) if &**i == PROPERTIES_INTERFACE => msg.get1::<&str>().map(|i| i.to_string()),
+        Some(i) => Some(i.to_string()),
+        None => None,
+    };
+
+    let interface = interface.as_ref().map(|i| i.as_str());
+    let mut delivered = 0;
+    let mut closed = Vec::new();
+    for event in events.iter() {
+        // The list is only locked while picking the handlers, so that callbacks can
+        // add or remove handlers themselves.
+        let matching = handlers.lock().unwrap().matching(interface, event);
+        for (id, handler) in matching {
+            if handler.lock().unwrap().deliver(event) {
+                delivered += 1;
+            } else {
+                closed.push(id);
+            }
+        }
+    }
+    // Channels whose receiver has been dropped are unregistered.
+    if !closed.is_empty() {
+        handlers.lock().unwrap().remove(&closed);
+    }
+    delivered
+}
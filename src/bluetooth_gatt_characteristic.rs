@@ -1,7 +1,12 @@
-use bluetooth_session::BluetoothSession;
+use bluetooth_event::BluetoothEvent;
+use bluetooth_gatt_descriptor::BluetoothGATTDescriptor;
+use bluetooth_gatt_flags::CharacteristicFlags;
+use bluetooth_session::{BluetoothSession, SessionMessages};
 use bluetooth_utils;
 use dbus::{BusType, Connection, Message, MessageItem, MessageItemArray, OwnedFd, Signature};
+use formats::gatt_descriptors::{self, PresentationFormat};
 
+use std::cell::Cell;
 use std::error::Error;
 
 static SERVICE_NAME: &'static str = "org.bluez";
@@ -11,6 +16,8 @@ static GATT_CHARACTERISTIC_INTERFACE: &'static str = "org.bluez.GattCharacterist
 pub struct BluetoothGATTCharacteristic<'a> {
     object_path: String,
     session: &'a BluetoothSession,
+    // Filled by get_flags, the flags of an attribute don't change.
+    flags: Cell<Option<CharacteristicFlags>>,
 }
 
 impl<'a> BluetoothGATTCharacteristic<'a> {
@@ -18,6 +25,7 @@ impl<'a> BluetoothGATTCharacteristic<'a> {
         BluetoothGATTCharacteristic {
             object_path: object_path,
             session: session,
+            flags: Cell::new(None),
         }
     }
 
@@ -25,6 +33,10 @@ impl<'a> BluetoothGATTCharacteristic<'a> {
         self.object_path.clone()
     }
 
+    pub(crate) fn get_session(&self) -> &'a BluetoothSession {
+        self.session
+    }
+
     fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
         bluetooth_utils::get_property(
             self.session.get_connection(),
@@ -50,6 +62,26 @@ impl<'a> BluetoothGATTCharacteristic<'a> {
         )
     }
 
+    fn check_flags<F>(&self, supported: F, operation: &str) -> Result<(), Box<Error>>
+    where
+        F: Fn(&CharacteristicFlags) -> bool,
+    {
+        let flags = match self.flags.get() {
+            Some(flags) => flags,
+            None => try!(self.get_flags()),
+        };
+        if supported(&flags) {
+            Ok(())
+        } else {
+            Err(Box::from(format!(
+                "Characteristic {} does not support {} (flags: {:?})",
+                self.object_path,
+                operation,
+                flags.names()
+            )))
+        }
+    }
+
     /*
      * Properties
      */
@@ -84,14 +116,22 @@ impl<'a> BluetoothGATTCharacteristic<'a> {
     }
 
     // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n135
-    pub fn get_flags(&self) -> Result<Vec<String>, Box<Error>> {
+    pub fn get_flags(&self) -> Result<CharacteristicFlags, Box<Error>> {
         let flags = try!(self.get_property("Flags"));
         let z: &[MessageItem] = flags.inner().unwrap();
-        let mut v: Vec<String> = Vec::new();
+        let mut v: Vec<&str> = Vec::new();
         for y in z {
-            v.push(String::from(y.inner::<&str>().unwrap()));
+            v.push(y.inner::<&str>().unwrap());
         }
-        Ok(v)
+        let flags = CharacteristicFlags::from_strings(&v);
+        self.flags.set(Some(flags));
+        Ok(flags)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n144
+    pub fn get_handle(&self) -> Result<u16, Box<Error>> {
+        let handle = try!(self.get_property("Handle"));
+        Ok(handle.inner::<u16>().unwrap())
     }
 
     // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n156
@@ -99,12 +139,49 @@ impl<'a> BluetoothGATTCharacteristic<'a> {
         bluetooth_utils::list_descriptors(self.session.get_connection(), &self.object_path)
     }
 
+    pub fn find_descriptor(
+        &self,
+        uuid: &str,
+    ) -> Result<Option<BluetoothGATTDescriptor<'a>>, Box<Error>> {
+        let path = try!(bluetooth_utils::find_descriptor(
+            self.session.get_connection(),
+            &self.object_path,
+            uuid
+        ));
+        Ok(path.map(|path| BluetoothGATTDescriptor::new(self.session, path)))
+    }
+
+    // Human-readable name from the Characteristic User Description descriptor, if present.
+    pub fn get_user_description(&self) -> Result<Option<String>, Box<Error>> {
+        let uuid = gatt_descriptors::CHARACTERISTIC_USER_DESCRIPTION_UUID;
+        match try!(self.find_descriptor(uuid)) {
+            Some(descriptor) => {
+                let value = try!(descriptor.read_value(None));
+                Ok(Some(gatt_descriptors::parse_user_description(&value)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // Format, exponent and unit from the Characteristic Presentation Format descriptor, if present.
+    pub fn get_presentation_format(&self) -> Result<Option<PresentationFormat>, Box<Error>> {
+        let uuid = gatt_descriptors::CHARACTERISTIC_PRESENTATION_FORMAT_UUID;
+        match try!(self.find_descriptor(uuid)) {
+            Some(descriptor) => {
+                let value = try!(descriptor.read_value(None));
+                Ok(Some(try!(PresentationFormat::parse(&value))))
+            }
+            None => Ok(None),
+        }
+    }
+
     /*
      * Methods
      */
 
     // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n72
     pub fn read_value(&self, offset: Option<u16>) -> Result<Vec<u8>, Box<Error>> {
+        try!(self.check_flags(CharacteristicFlags::can_read, "read"));
         let c = try!(Connection::get_private(BusType::System));
         let mut m = try!(Message::new_method_call(
             SERVICE_NAME,
@@ -136,6 +213,7 @@ impl<'a> BluetoothGATTCharacteristic<'a> {
 
     // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n84
     pub fn write_value(&self, values: Vec<u8>, offset: Option<u16>) -> Result<(), Box<Error>> {
+        try!(self.check_flags(CharacteristicFlags::can_write, "write"));
         let values_msgs = {
             let mut res: Vec<MessageItem> = Vec::new();
             for v in values {
@@ -166,6 +244,10 @@ impl<'a> BluetoothGATTCharacteristic<'a> {
 
     // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n96
     pub fn start_notify(&self) -> Result<(), Box<Error>> {
+        try!(self.check_flags(
+            CharacteristicFlags::can_notify_or_indicate,
+            "notify or indicate"
+        ));
         self.call_method("StartNotify", None, 1000)
     }
 
@@ -175,6 +257,7 @@ impl<'a> BluetoothGATTCharacteristic<'a> {
     }
 
     pub fn acquire_notify(&self) -> Result<(OwnedFd, u16), Box<Error>> {
+        try!(self.check_flags(CharacteristicFlags::can_notify, "notify"));
         let mut m = Message::new_method_call(
             SERVICE_NAME,
             &self.object_path,
@@ -193,6 +276,10 @@ impl<'a> BluetoothGATTCharacteristic<'a> {
     }
 
     pub fn acquire_write(&self) -> Result<(OwnedFd, u16), Box<Error>> {
+        try!(self.check_flags(
+            CharacteristicFlags::can_write_without_response,
+            "write-without-response"
+        ));
         let mut m = Message::new_method_call(
             SERVICE_NAME,
             &self.object_path,
@@ -209,4 +296,46 @@ impl<'a> BluetoothGATTCharacteristic<'a> {
         let (opt_fd, opt_mtu) = reply.get2::<OwnedFd, u16>();
         Ok((opt_fd.unwrap(), opt_mtu.unwrap()))
     }
+
+    // Iterates over the values notified or indicated for this characteristic,
+    // ending once no message arrives for `timeout_ms`. Call `start_notify` first.
+    pub fn notifications(&self, timeout_ms: u32) -> BluetoothGATTNotifications<'a> {
+        BluetoothGATTNotifications {
+            object_path: self.object_path.clone(),
+            messages: self.session.incoming_queued(timeout_ms),
+        }
+    }
+}
+
+pub struct BluetoothGATTNotifications<'a> {
+    object_path: String,
+    messages: SessionMessages<'a>,
+}
+
+impl<'a> Iterator for BluetoothGATTNotifications<'a> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Vec<u8>> {
+        while let Some(msg) = self.messages.next() {
+            if let Some(value) = notified_value(&msg, &self.object_path) {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+// The value `msg` notifies or indicates for the characteristic at `object_path`.
+pub(crate) fn notified_value(msg: &Message, object_path: &str) -> Option<Vec<u8>> {
+    if msg.path().map_or(true, |path| &*path != object_path) {
+        return None;
+    }
+    BluetoothEvent::all_from(msg)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|event| match event {
+            BluetoothEvent::Value { value, .. } => Some(value.into_vec()),
+            _ => None,
+        })
+        .next()
 }
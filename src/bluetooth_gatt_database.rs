@@ -0,0 +1,268 @@
+use bluetooth_gatt_flags::{CharacteristicFlags, DescriptorFlags};
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::MessageItem;
+use std::collections::HashMap;
+use std::error::Error;
+
+static SERVICE_INTERFACE: &'static str = "org.bluez.GattService1";
+static CHARACTERISTIC_INTERFACE: &'static str = "org.bluez.GattCharacteristic1";
+static DESCRIPTOR_INTERFACE: &'static str = "org.bluez.GattDescriptor1";
+
+#[derive(Clone, Debug)]
+pub struct GattDescriptorInfo {
+    pub object_path: String,
+    pub uuid: String,
+    pub flags: DescriptorFlags,
+    pub handle: Option<u16>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GattCharacteristicInfo {
+    pub object_path: String,
+    pub uuid: String,
+    pub flags: CharacteristicFlags,
+    pub handle: Option<u16>,
+    pub descriptors: Vec<GattDescriptorInfo>,
+}
+
+impl GattCharacteristicInfo {
+    pub fn find_descriptor(&self, uuid: &str) -> Option<&GattDescriptorInfo> {
+        self.descriptors
+            .iter()
+            .find(|d| bluetooth_utils::uuid_matches(&d.uuid, uuid))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GattServiceInfo {
+    pub object_path: String,
+    pub uuid: String,
+    pub primary: bool,
+    pub handle: Option<u16>,
+    // Object paths of the included services.
+    pub includes: Vec<String>,
+    pub characteristics: Vec<GattCharacteristicInfo>,
+}
+
+impl GattServiceInfo {
+    pub fn find_characteristic(&self, uuid: &str) -> Option<&GattCharacteristicInfo> {
+        self.characteristics
+            .iter()
+            .find(|c| bluetooth_utils::uuid_matches(&c.uuid, uuid))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum GattAttribute<'a> {
+    Service(&'a GattServiceInfo),
+    Characteristic(&'a GattCharacteristicInfo),
+    Descriptor(&'a GattDescriptorInfo),
+}
+
+impl<'a> GattAttribute<'a> {
+    pub fn object_path(&self) -> &'a str {
+        match *self {
+            GattAttribute::Service(s) => &s.object_path,
+            GattAttribute::Characteristic(c) => &c.object_path,
+            GattAttribute::Descriptor(d) => &d.object_path,
+        }
+    }
+
+    pub fn uuid(&self) -> &'a str {
+        match *self {
+            GattAttribute::Service(s) => &s.uuid,
+            GattAttribute::Characteristic(c) => &c.uuid,
+            GattAttribute::Descriptor(d) => &d.uuid,
+        }
+    }
+
+    pub fn handle(&self) -> Option<u16> {
+        match *self {
+            GattAttribute::Service(s) => s.handle,
+            GattAttribute::Characteristic(c) => c.handle,
+            GattAttribute::Descriptor(d) => d.handle,
+        }
+    }
+}
+
+// Snapshot of the resolved GATT services of a device, fetched with a single
+// GetManagedObjects call. Services, characteristics and descriptors are ordered
+// by object path.
+#[derive(Clone, Debug)]
+pub struct GattDatabase {
+    pub device: String,
+    pub services: Vec<GattServiceInfo>,
+}
+
+impl GattDatabase {
+    pub fn fetch(
+        session: &BluetoothSession,
+        device_path: &str,
+    ) -> Result<GattDatabase, Box<Error>> {
+        let objects = try!(bluetooth_utils::get_managed_objects_properties(
+            session.get_connection()
+        ));
+
+        let mut services = Vec::new();
+        let mut characteristics: HashMap<String, Vec<GattCharacteristicInfo>> = HashMap::new();
+        let mut descriptors: HashMap<String, Vec<GattDescriptorInfo>> = HashMap::new();
+
+        for (path, interfaces) in objects.iter() {
+            if let Some(props) = interfaces.get(SERVICE_INTERFACE) {
+                if get_str(props, "Device").as_ref().map(|d| d.as_str()) != Some(device_path) {
+                    continue;
+                }
+                services.push(GattServiceInfo {
+                    object_path: path.clone(),
+                    uuid: get_str(props, "UUID").unwrap_or_default(),
+                    primary: props
+                        .get("Primary")
+                        .and_then(|p| p.inner::<bool>().ok())
+                        .unwrap_or(false),
+                    handle: get_handle(props),
+                    includes: get_str_vec(props, "Includes"),
+                    characteristics: Vec::new(),
+                });
+            } else if let Some(props) = interfaces.get(CHARACTERISTIC_INTERFACE) {
+                if let Some(service) = get_str(props, "Service") {
+                    characteristics
+                        .entry(service)
+                        .or_insert_with(Vec::new)
+                        .push(GattCharacteristicInfo {
+                            object_path: path.clone(),
+                            uuid: get_str(props, "UUID").unwrap_or_default(),
+                            flags: CharacteristicFlags::from_strings(&get_str_vec(props, "Flags")),
+                            handle: get_handle(props),
+                            descriptors: Vec::new(),
+                        });
+                }
+            } else if let Some(props) = interfaces.get(DESCRIPTOR_INTERFACE) {
+                if let Some(characteristic) = get_str(props, "Characteristic") {
+                    descriptors
+                        .entry(characteristic)
+                        .or_insert_with(Vec::new)
+                        .push(GattDescriptorInfo {
+                            object_path: path.clone(),
+                            uuid: get_str(props, "UUID").unwrap_or_default(),
+                            flags: DescriptorFlags::from_strings(&get_str_vec(props, "Flags")),
+                            handle: get_handle(props),
+                        });
+                }
+            }
+        }
+
+        services.sort_by(|a, b| a.object_path.cmp(&b.object_path));
+        for service in services.iter_mut() {
+            let mut chars = characteristics
+                .remove(&service.object_path)
+                .unwrap_or_default();
+            chars.sort_by(|a, b| a.object_path.cmp(&b.object_path));
+            for characteristic in chars.iter_mut() {
+                let mut descs = descriptors
+                    .remove(&characteristic.object_path)
+                    .unwrap_or_default();
+                descs.sort_by(|a, b| a.object_path.cmp(&b.object_path));
+                characteristic.descriptors = descs;
+            }
+            service.characteristics = chars;
+        }
+
+        Ok(GattDatabase {
+            device: String::from(device_path),
+            services: services,
+        })
+    }
+
+    pub fn get_service(&self, object_path: &str) -> Option<&GattServiceInfo> {
+        self.services.iter().find(|s| s.object_path == object_path)
+    }
+
+    // Services listed in the Includes property of `service` which are part of this database.
+    pub fn included_services(&self, service: &GattServiceInfo) -> Vec<&GattServiceInfo> {
+        service
+            .includes
+            .iter()
+            .filter_map(|path| self.get_service(path))
+            .collect()
+    }
+
+    // All services, characteristics and descriptors sorted by attribute handle.
+    // Attributes without a Handle property (older BlueZ) keep their object path
+    // order and come after the ones that have one.
+    pub fn attributes_by_handle(&self) -> Vec<GattAttribute> {
+        let mut attributes = Vec::new();
+        for service in self.services.iter() {
+            attributes.push(GattAttribute::Service(service));
+            for characteristic in service.characteristics.iter() {
+                attributes.push(GattAttribute::Characteristic(characteristic));
+                for descriptor in characteristic.descriptors.iter() {
+                    attributes.push(GattAttribute::Descriptor(descriptor));
+                }
+            }
+        }
+        attributes.sort_by_key(|a| match a.handle() {
+            Some(handle) => (0, handle),
+            None => (1, 0),
+        });
+        attributes
+    }
+
+    pub fn find_service(&self, uuid: &str) -> Option<&GattServiceInfo> {
+        self.services
+            .iter()
+            .find(|s| bluetooth_utils::uuid_matches(&s.uuid, uuid))
+    }
+
+    pub fn find_characteristic(
+        &self,
+        service_uuid: &str,
+        characteristic_uuid: &str,
+    ) -> Option<&GattCharacteristicInfo> {
+        self.services
+            .iter()
+            .filter(|s| bluetooth_utils::uuid_matches(&s.uuid, service_uuid))
+            .filter_map(|s| s.find_characteristic(characteristic_uuid))
+            .next()
+    }
+
+    pub fn find_descriptor(
+        &self,
+        service_uuid: &str,
+        characteristic_uuid: &str,
+        descriptor_uuid: &str,
+    ) -> Option<&GattDescriptorInfo> {
+        self.services
+            .iter()
+            .filter(|s| bluetooth_utils::uuid_matches(&s.uuid, service_uuid))
+            .flat_map(|s| s.characteristics.iter())
+            .filter(|c| bluetooth_utils::uuid_matches(&c.uuid, characteristic_uuid))
+            .filter_map(|c| c.find_descriptor(descriptor_uuid))
+            .next()
+    }
+}
+
+fn get_str(props: &HashMap<String, MessageItem>, name: &str) -> Option<String> {
+    props
+        .get(name)
+        .and_then(|p| p.inner::<&str>().ok())
+        .map(String::from)
+}
+
+fn get_str_vec(props: &HashMap<String, MessageItem>, name: &str) -> Vec<String> {
+    match props
+        .get(name)
+        .and_then(|p| p.inner::<&[MessageItem]>().ok())
+    {
+        Some(items) => items
+            .iter()
+            .filter_map(|i| i.inner::<&str>().ok())
+            .map(String::from)
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn get_handle(props: &HashMap<String, MessageItem>) -> Option<u16> {
+    props.get("Handle").and_then(|p| p.inner::<u16>().ok())
+}
@@ -1,7 +1,10 @@
+use bluetooth_gatt_flags::DescriptorFlags;
 use bluetooth_session::BluetoothSession;
 use bluetooth_utils;
 use dbus::{BusType, Connection, Message, MessageItem, MessageItemArray, Signature};
+use formats::gatt_descriptors::DescriptorValue;
 
+use std::cell::Cell;
 use std::error::Error;
 
 static SERVICE_NAME: &'static str = "org.bluez";
@@ -11,6 +14,8 @@ static GATT_DESCRIPTOR_INTERFACE: &'static str = "org.bluez.GattDescriptor1";
 pub struct BluetoothGATTDescriptor<'a> {
     object_path: String,
     session: &'a BluetoothSession,
+    // Filled by get_flags, the flags of an attribute don't change.
+    flags: Cell<Option<DescriptorFlags>>,
 }
 
 impl<'a> BluetoothGATTDescriptor<'a> {
@@ -18,6 +23,7 @@ impl<'a> BluetoothGATTDescriptor<'a> {
         BluetoothGATTDescriptor {
             object_path: object_path,
             session: session,
+            flags: Cell::new(None),
         }
     }
 
@@ -50,6 +56,26 @@ impl<'a> BluetoothGATTDescriptor<'a> {
         )
     }
 
+    fn check_flags<F>(&self, supported: F, operation: &str) -> Result<(), Box<Error>>
+    where
+        F: Fn(&DescriptorFlags) -> bool,
+    {
+        let flags = match self.flags.get() {
+            Some(flags) => flags,
+            None => try!(self.get_flags()),
+        };
+        if supported(&flags) {
+            Ok(())
+        } else {
+            Err(Box::from(format!(
+                "Descriptor {} does not support {} (flags: {:?})",
+                self.object_path,
+                operation,
+                flags.names()
+            )))
+        }
+    }
+
     /*
      * Properties
      */
@@ -78,14 +104,22 @@ impl<'a> BluetoothGATTDescriptor<'a> {
     }
 
     // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n213
-    pub fn get_flags(&self) -> Result<Vec<String>, Box<Error>> {
+    pub fn get_flags(&self) -> Result<DescriptorFlags, Box<Error>> {
         let flags = try!(self.get_property("Flags"));
         let z: &[MessageItem] = flags.inner().unwrap();
-        let mut v: Vec<String> = Vec::new();
+        let mut v: Vec<&str> = Vec::new();
         for y in z {
-            v.push(String::from(y.inner::<&str>().unwrap()));
+            v.push(y.inner::<&str>().unwrap());
         }
-        Ok(v)
+        let flags = DescriptorFlags::from_strings(&v);
+        self.flags.set(Some(flags));
+        Ok(flags)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n219
+    pub fn get_handle(&self) -> Result<u16, Box<Error>> {
+        let handle = try!(self.get_property("Handle"));
+        Ok(handle.inner::<u16>().unwrap())
     }
 
     /*
@@ -94,6 +128,7 @@ impl<'a> BluetoothGATTDescriptor<'a> {
 
     // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n174
     pub fn read_value(&self, offset: Option<u16>) -> Result<Vec<u8>, Box<Error>> {
+        try!(self.check_flags(DescriptorFlags::can_read, "read"));
         let c = try!(Connection::get_private(BusType::System));
         let mut m = try!(Message::new_method_call(
             SERVICE_NAME,
@@ -125,6 +160,7 @@ impl<'a> BluetoothGATTDescriptor<'a> {
 
     // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n186
     pub fn write_value(&self, values: Vec<u8>, offset: Option<u16>) -> Result<(), Box<Error>> {
+        try!(self.check_flags(DescriptorFlags::can_write, "write"));
         let args = {
             let mut res: Vec<MessageItem> = Vec::new();
             for v in values {
@@ -152,4 +188,11 @@ impl<'a> BluetoothGATTDescriptor<'a> {
             1000,
         )
     }
+
+    // Reads the value and decodes it according to the descriptor UUID.
+    pub fn read_decoded_value(&self) -> Result<DescriptorValue, Box<Error>> {
+        let uuid = try!(self.get_uuid());
+        let value = try!(self.read_value(None));
+        DescriptorValue::decode(&uuid, &value)
+    }
 }
@@ -0,0 +1,341 @@
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+
+// http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n135
+static CHARACTERISTIC_FLAG_NAMES: &'static [(&'static str, u32)] = &[
+    ("broadcast", CharacteristicFlags::BROADCAST.0),
+    ("read", CharacteristicFlags::READ.0),
+    (
+        "write-without-response",
+        CharacteristicFlags::WRITE_WITHOUT_RESPONSE.0,
+    ),
+    ("write", CharacteristicFlags::WRITE.0),
+    ("notify", CharacteristicFlags::NOTIFY.0),
+    ("indicate", CharacteristicFlags::INDICATE.0),
+    (
+        "authenticated-signed-writes",
+        CharacteristicFlags::AUTHENTICATED_SIGNED_WRITES.0,
+    ),
+    (
+        "extended-properties",
+        CharacteristicFlags::EXTENDED_PROPERTIES.0,
+    ),
+    ("reliable-write", CharacteristicFlags::RELIABLE_WRITE.0),
+    (
+        "writable-auxiliaries",
+        CharacteristicFlags::WRITABLE_AUXILIARIES.0,
+    ),
+    ("encrypt-read", CharacteristicFlags::ENCRYPT_READ.0),
+    ("encrypt-write", CharacteristicFlags::ENCRYPT_WRITE.0),
+    ("encrypt-notify", CharacteristicFlags::ENCRYPT_NOTIFY.0),
+    ("encrypt-indicate", CharacteristicFlags::ENCRYPT_INDICATE.0),
+    (
+        "encrypt-authenticated-read",
+        CharacteristicFlags::ENCRYPT_AUTHENTICATED_READ.0,
+    ),
+    (
+        "encrypt-authenticated-write",
+        CharacteristicFlags::ENCRYPT_AUTHENTICATED_WRITE.0,
+    ),
+    (
+        "encrypt-authenticated-notify",
+        CharacteristicFlags::ENCRYPT_AUTHENTICATED_NOTIFY.0,
+    ),
+    (
+        "encrypt-authenticated-indicate",
+        CharacteristicFlags::ENCRYPT_AUTHENTICATED_INDICATE.0,
+    ),
+    ("secure-read", CharacteristicFlags::SECURE_READ.0),
+    ("secure-write", CharacteristicFlags::SECURE_WRITE.0),
+    ("secure-notify", CharacteristicFlags::SECURE_NOTIFY.0),
+    ("secure-indicate", CharacteristicFlags::SECURE_INDICATE.0),
+    ("authorize", CharacteristicFlags::AUTHORIZE.0),
+];
+
+// http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n213
+static DESCRIPTOR_FLAG_NAMES: &'static [(&'static str, u32)] = &[
+    ("read", DescriptorFlags::READ.0),
+    ("write", DescriptorFlags::WRITE.0),
+    ("encrypt-read", DescriptorFlags::ENCRYPT_READ.0),
+    ("encrypt-write", DescriptorFlags::ENCRYPT_WRITE.0),
+    (
+        "encrypt-authenticated-read",
+        DescriptorFlags::ENCRYPT_AUTHENTICATED_READ.0,
+    ),
+    (
+        "encrypt-authenticated-write",
+        DescriptorFlags::ENCRYPT_AUTHENTICATED_WRITE.0,
+    ),
+    ("secure-read", DescriptorFlags::SECURE_READ.0),
+    ("secure-write", DescriptorFlags::SECURE_WRITE.0),
+    ("authorize", DescriptorFlags::AUTHORIZE.0),
+];
+
+fn parse_flags<S: AsRef<str>>(table: &[(&'static str, u32)], flags: &[S]) -> u32 {
+    let mut bits = 0;
+    for flag in flags {
+        if let Some(&(_, bit)) = table.iter().find(|&&(name, _)| name == flag.as_ref()) {
+            bits |= bit;
+        }
+    }
+    bits
+}
+
+fn flag_names(table: &[(&'static str, u32)], bits: u32) -> Vec<&'static str> {
+    table
+        .iter()
+        .filter(|&&(_, bit)| bits & bit != 0)
+        .map(|&(name, _)| name)
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CharacteristicFlags(u32);
+
+impl CharacteristicFlags {
+    pub const BROADCAST: CharacteristicFlags = CharacteristicFlags(1 << 0);
+    pub const READ: CharacteristicFlags = CharacteristicFlags(1 << 1);
+    pub const WRITE_WITHOUT_RESPONSE: CharacteristicFlags = CharacteristicFlags(1 << 2);
+    pub const WRITE: CharacteristicFlags = CharacteristicFlags(1 << 3);
+    pub const NOTIFY: CharacteristicFlags = CharacteristicFlags(1 << 4);
+    pub const INDICATE: CharacteristicFlags = CharacteristicFlags(1 << 5);
+    pub const AUTHENTICATED_SIGNED_WRITES: CharacteristicFlags = CharacteristicFlags(1 << 6);
+    pub const EXTENDED_PROPERTIES: CharacteristicFlags = CharacteristicFlags(1 << 7);
+    pub const RELIABLE_WRITE: CharacteristicFlags = CharacteristicFlags(1 << 8);
+    pub const WRITABLE_AUXILIARIES: CharacteristicFlags = CharacteristicFlags(1 << 9);
+    pub const ENCRYPT_READ: CharacteristicFlags = CharacteristicFlags(1 << 10);
+    pub const ENCRYPT_WRITE: CharacteristicFlags = CharacteristicFlags(1 << 11);
+    pub const ENCRYPT_NOTIFY: CharacteristicFlags = CharacteristicFlags(1 << 12);
+    pub const ENCRYPT_INDICATE: CharacteristicFlags = CharacteristicFlags(1 << 13);
+    pub const ENCRYPT_AUTHENTICATED_READ: CharacteristicFlags = CharacteristicFlags(1 << 14);
+    pub const ENCRYPT_AUTHENTICATED_WRITE: CharacteristicFlags = CharacteristicFlags(1 << 15);
+    pub const ENCRYPT_AUTHENTICATED_NOTIFY: CharacteristicFlags = CharacteristicFlags(1 << 16);
+    pub const ENCRYPT_AUTHENTICATED_INDICATE: CharacteristicFlags = CharacteristicFlags(1 << 17);
+    pub const SECURE_READ: CharacteristicFlags = CharacteristicFlags(1 << 18);
+    pub const SECURE_WRITE: CharacteristicFlags = CharacteristicFlags(1 << 19);
+    pub const SECURE_NOTIFY: CharacteristicFlags = CharacteristicFlags(1 << 20);
+    pub const SECURE_INDICATE: CharacteristicFlags = CharacteristicFlags(1 << 21);
+    pub const AUTHORIZE: CharacteristicFlags = CharacteristicFlags(1 << 22);
+
+    pub fn empty() -> CharacteristicFlags {
+        CharacteristicFlags(0)
+    }
+
+    pub fn from_bits(bits: u32) -> CharacteristicFlags {
+        CharacteristicFlags(bits)
+    }
+
+    // Unknown flag strings are ignored.
+    pub fn from_strings<S: AsRef<str>>(flags: &[S]) -> CharacteristicFlags {
+        CharacteristicFlags(parse_flags(CHARACTERISTIC_FLAG_NAMES, flags))
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(&self, other: CharacteristicFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn intersects(&self, other: CharacteristicFlags) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        flag_names(CHARACTERISTIC_FLAG_NAMES, self.0)
+    }
+
+    pub fn can_read(&self) -> bool {
+        self.intersects(
+            CharacteristicFlags::READ
+                | CharacteristicFlags::ENCRYPT_READ
+                | CharacteristicFlags::ENCRYPT_AUTHENTICATED_READ
+                | CharacteristicFlags::SECURE_READ,
+        )
+    }
+
+    pub fn can_write(&self) -> bool {
+        self.intersects(
+            CharacteristicFlags::WRITE
+                | CharacteristicFlags::WRITE_WITHOUT_RESPONSE
+                | CharacteristicFlags::AUTHENTICATED_SIGNED_WRITES
+                | CharacteristicFlags::RELIABLE_WRITE
+                | CharacteristicFlags::ENCRYPT_WRITE
+                | CharacteristicFlags::ENCRYPT_AUTHENTICATED_WRITE
+                | CharacteristicFlags::SECURE_WRITE,
+        )
+    }
+
+    pub fn can_write_without_response(&self) -> bool {
+        self.contains(CharacteristicFlags::WRITE_WITHOUT_RESPONSE)
+    }
+
+    pub fn can_notify(&self) -> bool {
+        self.intersects(
+            CharacteristicFlags::NOTIFY
+                | CharacteristicFlags::ENCRYPT_NOTIFY
+                | CharacteristicFlags::ENCRYPT_AUTHENTICATED_NOTIFY
+                | CharacteristicFlags::SECURE_NOTIFY,
+        )
+    }
+
+    pub fn can_indicate(&self) -> bool {
+        self.intersects(
+            CharacteristicFlags::INDICATE
+                | CharacteristicFlags::ENCRYPT_INDICATE
+                | CharacteristicFlags::ENCRYPT_AUTHENTICATED_INDICATE
+                | CharacteristicFlags::SECURE_INDICATE,
+        )
+    }
+
+    pub fn can_notify_or_indicate(&self) -> bool {
+        self.can_notify() || self.can_indicate()
+    }
+
+    pub fn requires_encryption(&self) -> bool {
+        self.intersects(
+            CharacteristicFlags::ENCRYPT_READ
+                | CharacteristicFlags::ENCRYPT_WRITE
+                | CharacteristicFlags::ENCRYPT_NOTIFY
+                | CharacteristicFlags::ENCRYPT_INDICATE
+                | CharacteristicFlags::ENCRYPT_AUTHENTICATED_READ
+                | CharacteristicFlags::ENCRYPT_AUTHENTICATED_WRITE
+                | CharacteristicFlags::ENCRYPT_AUTHENTICATED_NOTIFY
+                | CharacteristicFlags::ENCRYPT_AUTHENTICATED_INDICATE
+                | CharacteristicFlags::SECURE_READ
+                | CharacteristicFlags::SECURE_WRITE
+                | CharacteristicFlags::SECURE_NOTIFY
+                | CharacteristicFlags::SECURE_INDICATE,
+        )
+    }
+
+    pub fn requires_authorization(&self) -> bool {
+        self.contains(CharacteristicFlags::AUTHORIZE)
+    }
+}
+
+impl BitOr for CharacteristicFlags {
+    type Output = CharacteristicFlags;
+
+    fn bitor(self, other: CharacteristicFlags) -> CharacteristicFlags {
+        CharacteristicFlags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for CharacteristicFlags {
+    fn bitor_assign(&mut self, other: CharacteristicFlags) {
+        self.0 |= other.0;
+    }
+}
+
+impl fmt::Debug for CharacteristicFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CharacteristicFlags({:?})", self.names())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DescriptorFlags(u32);
+
+impl DescriptorFlags {
+    pub const READ: DescriptorFlags = DescriptorFlags(1 << 0);
+    pub const WRITE: DescriptorFlags = DescriptorFlags(1 << 1);
+    pub const ENCRYPT_READ: DescriptorFlags = DescriptorFlags(1 << 2);
+    pub const ENCRYPT_WRITE: DescriptorFlags = DescriptorFlags(1 << 3);
+    pub const ENCRYPT_AUTHENTICATED_READ: DescriptorFlags = DescriptorFlags(1 << 4);
+    pub const ENCRYPT_AUTHENTICATED_WRITE: DescriptorFlags = DescriptorFlags(1 << 5);
+    pub const SECURE_READ: DescriptorFlags = DescriptorFlags(1 << 6);
+    pub const SECURE_WRITE: DescriptorFlags = DescriptorFlags(1 << 7);
+    pub const AUTHORIZE: DescriptorFlags = DescriptorFlags(1 << 8);
+
+    pub fn empty() -> DescriptorFlags {
+        DescriptorFlags(0)
+    }
+
+    pub fn from_bits(bits: u32) -> DescriptorFlags {
+        DescriptorFlags(bits)
+    }
+
+    // Unknown flag strings are ignored.
+    pub fn from_strings<S: AsRef<str>>(flags: &[S]) -> DescriptorFlags {
+        DescriptorFlags(parse_flags(DESCRIPTOR_FLAG_NAMES, flags))
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(&self, other: DescriptorFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn intersects(&self, other: DescriptorFlags) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        flag_names(DESCRIPTOR_FLAG_NAMES, self.0)
+    }
+
+    pub fn can_read(&self) -> bool {
+        self.intersects(
+            DescriptorFlags::READ
+                | DescriptorFlags::ENCRYPT_READ
+                | DescriptorFlags::ENCRYPT_AUTHENTICATED_READ
+                | DescriptorFlags::SECURE_READ,
+        )
+    }
+
+    pub fn can_write(&self) -> bool {
+        self.intersects(
+            DescriptorFlags::WRITE
+                | DescriptorFlags::ENCRYPT_WRITE
+                | DescriptorFlags::ENCRYPT_AUTHENTICATED_WRITE
+                | DescriptorFlags::SECURE_WRITE,
+        )
+    }
+
+    pub fn requires_encryption(&self) -> bool {
+        self.intersects(
+            DescriptorFlags::ENCRYPT_READ
+                | DescriptorFlags::ENCRYPT_WRITE
+                | DescriptorFlags::ENCRYPT_AUTHENTICATED_READ
+                | DescriptorFlags::ENCRYPT_AUTHENTICATED_WRITE
+                | DescriptorFlags::SECURE_READ
+                | DescriptorFlags::SECURE_WRITE,
+        )
+    }
+
+    pub fn requires_authorization(&self) -> bool {
+        self.contains(DescriptorFlags::AUTHORIZE)
+    }
+}
+
+impl BitOr for DescriptorFlags {
+    type Output = DescriptorFlags;
+
+    fn bitor(self, other: DescriptorFlags) -> DescriptorFlags {
+        DescriptorFlags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for DescriptorFlags {
+    fn bitor_assign(&mut self, other: DescriptorFlags) {
+        self.0 |= other.0;
+    }
+}
+
+impl fmt::Debug for DescriptorFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DescriptorFlags({:?})", self.names())
+    }
+}
@@ -1,3 +1,4 @@
+use bluetooth_gatt_characteristic::BluetoothGATTCharacteristic;
 use bluetooth_session::BluetoothSession;
 use bluetooth_utils;
 use dbus::MessageItem;
@@ -56,11 +57,38 @@ impl<'a> BluetoothGATTService<'a> {
     }
 
     // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n48
-    pub fn get_includes(&self) -> Result<Vec<String>, Box<Error>> {
-        Err(Box::from("Not implemented"))
+    pub fn get_includes(&self) -> Result<Vec<BluetoothGATTService<'a>>, Box<Error>> {
+        let includes = try!(self.get_property("Includes"));
+        let z: &[MessageItem] = includes.inner().unwrap();
+        let mut v: Vec<BluetoothGATTService<'a>> = Vec::new();
+        for y in z {
+            v.push(BluetoothGATTService::new(
+                self.session,
+                String::from(y.inner::<&str>().unwrap()),
+            ));
+        }
+        Ok(v)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/gatt-api.txt#n52
+    pub fn get_handle(&self) -> Result<u16, Box<Error>> {
+        let handle = try!(self.get_property("Handle"));
+        Ok(handle.inner::<u16>().unwrap())
     }
 
     pub fn get_gatt_characteristics(&self) -> Result<Vec<String>, Box<Error>> {
         bluetooth_utils::list_characteristics(self.session.get_connection(), &self.object_path)
     }
+
+    pub fn find_characteristic(
+        &self,
+        uuid: &str,
+    ) -> Result<Option<BluetoothGATTCharacteristic<'a>>, Box<Error>> {
+        let path = try!(bluetooth_utils::find_characteristic(
+            self.session.get_connection(),
+            &self.object_path,
+            uuid
+        ));
+        Ok(path.map(|path| BluetoothGATTCharacteristic::new(self.session, path)))
+    }
 }
@@ -0,0 +1,80 @@
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::MessageItem;
+
+use std::error::Error;
+
+static INPUT_INTERFACE: &'static str = "org.bluez.Input1";
+
+// Which side re-establishes a dropped HID connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReconnectMode {
+    None,
+    Host,
+    Device,
+    Any,
+}
+
+impl ReconnectMode {
+    pub fn from_name(name: &str) -> Option<ReconnectMode> {
+        match name {
+            "none" => Some(ReconnectMode::None),
+            "host" => Some(ReconnectMode::Host),
+            "device" => Some(ReconnectMode::Device),
+            "any" => Some(ReconnectMode::Any),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            ReconnectMode::None => "none",
+            ReconnectMode::Host => "host",
+            ReconnectMode::Device => "device",
+            ReconnectMode::Any => "any",
+        }
+    }
+}
+
+// Input1 lives on the device object of HID devices, so `object_path` is the device path.
+#[derive(Clone, Debug)]
+pub struct BluetoothInput<'a> {
+    object_path: String,
+    session: &'a BluetoothSession,
+}
+
+impl<'a> BluetoothInput<'a> {
+    pub fn new(session: &'a BluetoothSession, object_path: String) -> BluetoothInput {
+        BluetoothInput {
+            object_path: object_path,
+            session: session,
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
+        bluetooth_utils::get_property(
+            self.session.get_connection(),
+            INPUT_INTERFACE,
+            &self.object_path,
+            prop,
+        )
+    }
+
+    /*
+     * Properties
+     */
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/input-api.txt#n12
+    pub fn get_reconnect_mode(&self) -> Result<ReconnectMode, Box<Error>> {
+        let mode = try!(self.get_property("ReconnectMode"));
+        let mode = mode.inner::<&str>().unwrap();
+        match ReconnectMode::from_name(mode) {
+            Some(mode) => Ok(mode),
+            None => Err(Box::from(format!("Unknown reconnect mode: {}", mode))),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use bluetooth_media_player::BluetoothMediaPlayer;
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::MessageItem;
+
+use std::error::Error;
+
+static MEDIA_CONTROL_INTERFACE: &'static str = "org.bluez.MediaControl1";
+
+// MediaControl1 lives on the device object, so `object_path` is the device path.
+// Most of its methods are deprecated in favour of MediaPlayer1, see `get_player`.
+#[derive(Clone, Debug)]
+pub struct BluetoothMediaControl<'a> {
+    object_path: String,
+    session: &'a BluetoothSession,
+}
+
+impl<'a> BluetoothMediaControl<'a> {
+    pub fn new(session: &'a BluetoothSession, object_path: String) -> BluetoothMediaControl {
+        BluetoothMediaControl {
+            object_path: object_path,
+            session: session,
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
+        bluetooth_utils::get_property(
+            self.session.get_connection(),
+            MEDIA_CONTROL_INTERFACE,
+            &self.object_path,
+            prop,
+        )
+    }
+
+    fn call_method(&self, method: &str) -> Result<(), Box<Error>> {
+        bluetooth_utils::call_method(
+            self.session.get_connection(),
+            MEDIA_CONTROL_INTERFACE,
+            &self.object_path,
+            method,
+            None,
+            1000,
+        )
+    }
+
+    /*
+     * Properties
+     */
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n57
+    pub fn is_connected(&self) -> Result<bool, Box<Error>> {
+        let connected = try!(self.get_property("Connected"));
+        Ok(connected.inner::<bool>().unwrap())
+    }
+
+    // Fails while the remote device has no addressed player.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n59
+    pub fn get_player(&self) -> Result<BluetoothMediaPlayer<'a>, Box<Error>> {
+        let player = try!(self.get_property("Player"));
+        Ok(BluetoothMediaPlayer::new(
+            self.session,
+            String::from(player.inner::<&str>().unwrap()),
+        ))
+    }
+
+    /*
+     * Methods
+     */
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n12
+    pub fn play(&self) -> Result<(), Box<Error>> {
+        self.call_method("Play")
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n17
+    pub fn pause(&self) -> Result<(), Box<Error>> {
+        self.call_method("Pause")
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n22
+    pub fn stop(&self) -> Result<(), Box<Error>> {
+        self.call_method("Stop")
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n27
+    pub fn next(&self) -> Result<(), Box<Error>> {
+        self.call_method("Next")
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n32
+    pub fn previous(&self) -> Result<(), Box<Error>> {
+        self.call_method("Previous")
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n37
+    pub fn volume_up(&self) -> Result<(), Box<Error>> {
+        self.call_method("VolumeUp")
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n42
+    pub fn volume_down(&self) -> Result<(), Box<Error>> {
+        self.call_method("VolumeDown")
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n47
+    pub fn fast_forward(&self) -> Result<(), Box<Error>> {
+        self.call_method("FastForward")
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n52
+    pub fn rewind(&self) -> Result<(), Box<Error>> {
+        self.call_method("Rewind")
+    }
+}
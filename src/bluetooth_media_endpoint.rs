@@ -0,0 +1,297 @@
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::{Message, MessageItem, MessageType, MsgHandler, MsgHandlerResult, MsgHandlerType, Path};
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::rc::Rc;
+
+static MEDIA_INTERFACE: &'static str = "org.bluez.Media1";
+static MEDIA_ENDPOINT_INTERFACE: &'static str = "org.bluez.MediaEndpoint1";
+static INVALID_ARGUMENTS_ERROR: &'static str = "org.bluez.Error.InvalidArguments";
+
+// Properties of RegisterEndpoint. Build the capabilities with the types of media_codecs.
+// http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n14
+#[derive(Clone, Debug)]
+pub struct EndpointOptions {
+    uuid: String,
+    codec: u8,
+    capabilities: Vec<u8>,
+    delay_reporting: Option<bool>,
+    vendor: Option<u32>,
+}
+
+impl EndpointOptions {
+    pub fn new(uuid: &str, codec: u8, capabilities: Vec<u8>) -> EndpointOptions {
+        EndpointOptions {
+            uuid: uuid.to_string(),
+            codec: codec,
+            capabilities: capabilities,
+            delay_reporting: None,
+            vendor: None,
+        }
+    }
+
+    pub fn delay_reporting(mut self, delay_reporting: bool) -> EndpointOptions {
+        self.delay_reporting = Some(delay_reporting);
+        self
+    }
+
+    // For formats::media_codecs::CODEC_VENDOR endpoints.
+    pub fn vendor(mut self, company_id: u16, codec_id: u16) -> EndpointOptions {
+        self.vendor = Some((company_id as u32) << 16 | codec_id as u32);
+        self
+    }
+
+    fn to_message_item(&self) -> MessageItem {
+        let mut options: Vec<(&str, MessageItem)> = vec![
+            ("UUID", self.uuid.as_str().into()),
+            ("Codec", self.codec.into()),
+            ("Capabilities", MessageItem::from(&self.capabilities[..])),
+        ];
+        if let Some(delay_reporting) = self.delay_reporting {
+            options.push(("DelayReporting", delay_reporting.into()));
+        }
+        if let Some(vendor) = self.vendor {
+            options.push(("Vendor", vendor.into()));
+        }
+        bluetooth_utils::properties_dict(options)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum EndpointEvent {
+    // A transport was configured, `properties` are those of its MediaTransport1
+    // object, e.g. "Configuration".
+    SetConfiguration {
+        transport: String,
+        properties: HashMap<String, MessageItem>,
+    },
+    ClearConfiguration {
+        transport: String,
+    },
+    // BlueZ unregistered the endpoint, no further events follow.
+    Release,
+}
+
+type EndpointEvents = Rc<RefCell<VecDeque<EndpointEvent>>>;
+type ConfigurationSelector = Box<FnMut(&[u8]) -> Option<Vec<u8>>>;
+
+// A MediaEndpoint1 object exported on the session's connection and registered
+// with the Media1 interface of an adapter. Method calls from BlueZ are answered
+// while the session is polled, `next_event` does so itself.
+pub struct BluetoothMediaEndpoint<'a> {
+    session: &'a BluetoothSession,
+    object_path: String,
+    adapter: String,
+    events: EndpointEvents,
+    active: Rc<Cell<bool>>,
+    registered: bool,
+}
+
+impl<'a> BluetoothMediaEndpoint<'a> {
+    // `select_configuration` answers SelectConfiguration, it receives the
+    // capabilities of the remote endpoint and returns the configuration to use,
+    // e.g. via SbcCapabilities::select_configuration. None rejects the request.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n14
+    pub fn register<F>(
+        session: &'a BluetoothSession,
+        adapter: &str,
+        object_path: &str,
+        options: &EndpointOptions,
+        select_configuration: F,
+    ) -> Result<BluetoothMediaEndpoint<'a>, Box<Error>>
+    where
+        F: FnMut(&[u8]) -> Option<Vec<u8>> + 'static,
+    {
+        let events: EndpointEvents = Rc::new(RefCell::new(VecDeque::new()));
+        let active = Rc::new(Cell::new(true));
+        let connection = session.get_connection();
+        try!(connection.register_object_path(object_path));
+        connection.add_handler(EndpointHandler {
+            object_path: String::from(object_path),
+            events: events.clone(),
+            active: active.clone(),
+            select_configuration: Box::new(select_configuration),
+        });
+
+        let mut endpoint = BluetoothMediaEndpoint {
+            session: session,
+            object_path: String::from(object_path),
+            adapter: String::from(adapter),
+            events: events,
+            active: active,
+            registered: false,
+        };
+        try!(endpoint.call_media(
+            "RegisterEndpoint",
+            &[
+                MessageItem::ObjectPath(Path::from(endpoint.object_path.clone())),
+                options.to_message_item(),
+            ],
+        ));
+        endpoint.registered = true;
+        Ok(endpoint)
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    pub fn get_adapter(&self) -> String {
+        self.adapter.clone()
+    }
+
+    fn call_media(&self, method: &str, param: &[MessageItem]) -> Result<(), Box<Error>> {
+        bluetooth_utils::call_method(
+            self.session.get_connection(),
+            MEDIA_INTERFACE,
+            &self.adapter,
+            method,
+            Some(param),
+            1000,
+        )
+    }
+
+    // Waits up to `timeout_ms` for the next call from BlueZ.
+    pub fn next_event(&self, timeout_ms: u32) -> Option<EndpointEvent> {
+        let events = self.events.clone();
+        self.session
+            .poll_until(timeout_ms, move || events.borrow_mut().pop_front())
+    }
+
+    // Same as dropping the endpoint, but reports whether BlueZ accepted it.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n60
+    pub fn unregister(mut self) -> Result<(), Box<Error>> {
+        self.registered = false;
+        self.call_unregister()
+    }
+
+    fn call_unregister(&self) -> Result<(), Box<Error>> {
+        self.call_media(
+            "UnregisterEndpoint",
+            &[MessageItem::ObjectPath(Path::from(
+                self.object_path.clone(),
+            ))],
+        )
+    }
+}
+
+impl<'a> Drop for BluetoothMediaEndpoint<'a> {
+    fn drop(&mut self) {
+        if self.registered {
+            let _ = self.call_unregister();
+        }
+        self.active.set(false);
+        self.session
+            .get_connection()
+            .unregister_object_path(&self.object_path);
+    }
+}
+
+struct EndpointHandler {
+    object_path: String,
+    events: EndpointEvents,
+    active: Rc<Cell<bool>>,
+    select_configuration: ConfigurationSelector,
+}
+
+impl EndpointHandler {
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n518
+    fn set_configuration(&self, msg: &Message) -> Option<EndpointEvent> {
+        let mut items = msg.get_items().into_iter();
+        let transport = match items.next() {
+            Some(MessageItem::ObjectPath(path)) => path.to_string(),
+            _ => return None,
+        };
+        let mut properties = HashMap::new();
+        if let Some(dict) = items.next() {
+            if let Ok(entries) = dict.inner::<&[MessageItem]>() {
+                for entry in entries {
+                    if let Ok((key, value)) = entry.inner::<(&MessageItem, &MessageItem)>() {
+                        if let (Ok(key), Ok(value)) =
+                            (key.inner::<&str>(), value.inner::<&MessageItem>())
+                        {
+                            properties.insert(String::from(key), value.clone());
+                        }
+                    }
+                }
+            }
+        }
+        Some(EndpointEvent::SetConfiguration {
+            transport: transport,
+            properties: properties,
+        })
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n525
+    fn select_configuration(&mut self, msg: &Message) -> Option<Message> {
+        let capabilities: Vec<u8> = match msg.get_items().get(0) {
+            Some(&MessageItem::Array(ref bytes)) => {
+                bytes.iter().filter_map(|b| b.inner::<u8>().ok()).collect()
+            }
+            _ => return None,
+        };
+        match (self.select_configuration)(&capabilities) {
+            Some(configuration) => Message::new_method_return(msg)
+                .map(|reply| reply.append(MessageItem::from(&configuration[..]))),
+            None => None,
+        }
+    }
+}
+
+impl MsgHandler for EndpointHandler {
+    fn handler_type(&self) -> MsgHandlerType {
+        MsgHandlerType::MsgType(MessageType::MethodCall)
+    }
+
+    fn handle_msg(&mut self, msg: &Message) -> Option<MsgHandlerResult> {
+        if !self.active.get() {
+            return Some(MsgHandlerResult {
+                handled: false,
+                done: true,
+                reply: Vec::new(),
+            });
+        }
+        let path = msg.path().map(|p| p.to_string()).unwrap_or_default();
+        let interface = msg.interface().map(|i| i.to_string()).unwrap_or_default();
+        if path != self.object_path || interface != MEDIA_ENDPOINT_INTERFACE {
+            return None;
+        }
+        let member = msg.member().map(|m| m.to_string()).unwrap_or_default();
+        let reply = if member == "SelectConfiguration" {
+            self.select_configuration(msg)
+        } else {
+            let event = match member.as_str() {
+                "SetConfiguration" => self.set_configuration(msg),
+                // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n538
+                "ClearConfiguration" => msg
+                    .get_items()
+                    .get(0)
+                    .and_then(|t| t.inner::<&str>().ok())
+                    .map(|transport| EndpointEvent::ClearConfiguration {
+                        transport: String::from(transport),
+                    }),
+                "Release" => Some(EndpointEvent::Release),
+                _ => return None,
+            };
+            match event {
+                Some(event) => {
+                    self.events.borrow_mut().push_back(event);
+                    Message::new_method_return(msg)
+                }
+                None => None,
+            }
+        };
+        let reply = match reply {
+            Some(reply) => reply,
+            None => Message::new_error(msg, INVALID_ARGUMENTS_ERROR, "Invalid arguments").unwrap(),
+        };
+        Some(MsgHandlerResult {
+            handled: true,
+            done: false,
+            reply: vec![reply],
+        })
+    }
+}
@@ -0,0 +1,176 @@
+use bluetooth_media_item::BluetoothMediaItem;
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::{Message, MessageItem, MessageItemArray, Path, Signature};
+
+use std::error::Error;
+
+static SERVICE_NAME: &'static str = "org.bluez";
+static MEDIA_FOLDER_INTERFACE: &'static str = "org.bluez.MediaFolder1";
+
+// Filter of ListItems and Search, unset entries are left to the player.
+// http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n395
+#[derive(Clone, Debug, Default)]
+pub struct MediaFilter {
+    start: Option<u32>,
+    end: Option<u32>,
+    attributes: Option<Vec<String>>,
+}
+
+impl MediaFilter {
+    pub fn new() -> MediaFilter {
+        MediaFilter::default()
+    }
+
+    // Offset of the first item.
+    pub fn start(mut self, start: u32) -> MediaFilter {
+        self.start = Some(start);
+        self
+    }
+
+    // Offset of the last item.
+    pub fn end(mut self, end: u32) -> MediaFilter {
+        self.end = Some(end);
+        self
+    }
+
+    // Metadata attributes to fetch, e.g. "title" or "artist".
+    pub fn attributes(mut self, attributes: Vec<String>) -> MediaFilter {
+        self.attributes = Some(attributes);
+        self
+    }
+
+    fn to_message_item(&self) -> MessageItem {
+        let mut filter: Vec<(&str, MessageItem)> = Vec::new();
+        if let Some(start) = self.start {
+            filter.push(("Start", start.into()));
+        }
+        if let Some(end) = self.end {
+            filter.push(("End", end.into()));
+        }
+        if let Some(ref attributes) = self.attributes {
+            let attributes = attributes
+                .iter()
+                .map(|a| MessageItem::from(a.as_str()))
+                .collect();
+            filter.push((
+                "Attributes",
+                MessageItem::Array(
+                    MessageItemArray::new(attributes, Signature::from("as")).unwrap(),
+                ),
+            ));
+        }
+        bluetooth_utils::properties_dict(filter)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BluetoothMediaFolder<'a> {
+    object_path: String,
+    session: &'a BluetoothSession,
+}
+
+impl<'a> BluetoothMediaFolder<'a> {
+    pub fn new(session: &'a BluetoothSession, object_path: String) -> BluetoothMediaFolder {
+        BluetoothMediaFolder {
+            object_path: object_path,
+            session: session,
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
+        bluetooth_utils::get_property(
+            self.session.get_connection(),
+            MEDIA_FOLDER_INTERFACE,
+            &self.object_path,
+            prop,
+        )
+    }
+
+    fn call_method_with_reply(
+        &self,
+        method: &str,
+        param: &[MessageItem],
+    ) -> Result<Message, Box<Error>> {
+        let mut m = try!(Message::new_method_call(
+            SERVICE_NAME,
+            &self.object_path,
+            MEDIA_FOLDER_INTERFACE,
+            method
+        ));
+        m.append_items(param);
+        // Browsing goes over the air, so allow more than the usual second.
+        Ok(try!(self
+            .session
+            .get_connection()
+            .send_with_reply_and_block(m, 10000)))
+    }
+
+    /*
+     * Properties
+     */
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n423
+    pub fn get_number_of_items(&self) -> Result<u32, Box<Error>> {
+        let number_of_items = try!(self.get_property("NumberOfItems"));
+        Ok(number_of_items.inner::<u32>().unwrap())
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n427
+    pub fn get_name(&self) -> Result<String, Box<Error>> {
+        let name = try!(self.get_property("Name"));
+        Ok(String::from(name.inner::<&str>().unwrap()))
+    }
+
+    /*
+     * Methods
+     */
+
+    // Returns the folder holding the results, only supported by searchable players.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n380
+    pub fn search(
+        &self,
+        value: &str,
+        filter: &MediaFilter,
+    ) -> Result<BluetoothMediaFolder<'a>, Box<Error>> {
+        let reply = try!(
+            self.call_method_with_reply("Search", &[value.into(), filter.to_message_item()])
+        );
+        let folder: Path = try!(reply.read1());
+        Ok(BluetoothMediaFolder::new(self.session, folder.to_string()))
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n391
+    pub fn list_items(
+        &self,
+        filter: &MediaFilter,
+    ) -> Result<Vec<BluetoothMediaItem<'a>>, Box<Error>> {
+        let reply = try!(self.call_method_with_reply("ListItems", &[filter.to_message_item()]));
+        let items: MessageItem = match reply.get1() {
+            Some(items) => items,
+            None => return Err(Box::from("Invalid ListItems reply")),
+        };
+        let entries: &[MessageItem] = items.inner().unwrap();
+        let mut v = Vec::new();
+        for entry in entries {
+            let (path, _) = entry.inner::<(&MessageItem, &MessageItem)>().unwrap();
+            let path: &str = path.inner().unwrap();
+            v.push(BluetoothMediaItem::new(self.session, String::from(path)));
+        }
+        Ok(v)
+    }
+
+    // `folder` is the object path of a folder or folder item below this one.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n411
+    pub fn change_folder(&self, folder: &str) -> Result<(), Box<Error>> {
+        try!(self.call_method_with_reply(
+            "ChangeFolder",
+            &[MessageItem::ObjectPath(Path::from(folder.to_string()))],
+        ));
+        Ok(())
+    }
+}
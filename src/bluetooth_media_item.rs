@@ -0,0 +1,103 @@
+use bluetooth_media_player::MediaTrack;
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::MessageItem;
+
+use std::error::Error;
+
+static MEDIA_ITEM_INTERFACE: &'static str = "org.bluez.MediaItem1";
+
+#[derive(Clone, Debug)]
+pub struct BluetoothMediaItem<'a> {
+    object_path: String,
+    session: &'a BluetoothSession,
+}
+
+impl<'a> BluetoothMediaItem<'a> {
+    pub fn new(session: &'a BluetoothSession, object_path: String) -> BluetoothMediaItem {
+        BluetoothMediaItem {
+            object_path: object_path,
+            session: session,
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
+        bluetooth_utils::get_property(
+            self.session.get_connection(),
+            MEDIA_ITEM_INTERFACE,
+            &self.object_path,
+            prop,
+        )
+    }
+
+    fn call_method(&self, method: &str) -> Result<(), Box<Error>> {
+        bluetooth_utils::call_method(
+            self.session.get_connection(),
+            MEDIA_ITEM_INTERFACE,
+            &self.object_path,
+            method,
+            None,
+            1000,
+        )
+    }
+
+    /*
+     * Properties
+     */
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n461
+    pub fn get_player(&self) -> Result<String, Box<Error>> {
+        let player = try!(self.get_property("Player"));
+        Ok(String::from(player.inner::<&str>().unwrap()))
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n465
+    pub fn get_name(&self) -> Result<String, Box<Error>> {
+        let name = try!(self.get_property("Name"));
+        Ok(String::from(name.inner::<&str>().unwrap()))
+    }
+
+    // "video", "audio" or "folder".
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n469
+    pub fn get_type(&self) -> Result<String, Box<Error>> {
+        let item_type = try!(self.get_property("Type"));
+        Ok(String::from(item_type.inner::<&str>().unwrap()))
+    }
+
+    // Only present on folder items.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n475
+    pub fn get_folder_type(&self) -> Result<String, Box<Error>> {
+        let folder_type = try!(self.get_property("FolderType"));
+        Ok(String::from(folder_type.inner::<&str>().unwrap()))
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n482
+    pub fn is_playable(&self) -> Result<bool, Box<Error>> {
+        let playable = try!(self.get_property("Playable"));
+        Ok(playable.inner::<bool>().unwrap())
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n486
+    pub fn get_metadata(&self) -> Result<MediaTrack, Box<Error>> {
+        let metadata = try!(self.get_property("Metadata"));
+        Ok(MediaTrack::from_message_item(&metadata))
+    }
+
+    /*
+     * Methods
+     */
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n444
+    pub fn play(&self) -> Result<(), Box<Error>> {
+        self.call_method("Play")
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n452
+    pub fn add_to_now_playing(&self) -> Result<(), Box<Error>> {
+        self.call_method("AddtoNowPlaying")
+    }
+}
@@ -0,0 +1,490 @@
+use bluetooth_event::BluetoothEvent;
+use bluetooth_media_folder::BluetoothMediaFolder;
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::arg::{cast, RefArg, Variant};
+use dbus::MessageItem;
+
+use std::collections::HashMap;
+use std::error::Error;
+
+static MEDIA_PLAYER_INTERFACE: &'static str = "org.bluez.MediaPlayer1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayerStatus {
+    Playing,
+    Stopped,
+    Paused,
+    ForwardSeek,
+    ReverseSeek,
+    Error,
+}
+
+impl PlayerStatus {
+    pub fn from_name(name: &str) -> Option<PlayerStatus> {
+        match name {
+            "playing" => Some(PlayerStatus::Playing),
+            "stopped" => Some(PlayerStatus::Stopped),
+            "paused" => Some(PlayerStatus::Paused),
+            "forward-seek" => Some(PlayerStatus::ForwardSeek),
+            "reverse-seek" => Some(PlayerStatus::ReverseSeek),
+            "error" => Some(PlayerStatus::Error),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            PlayerStatus::Playing => "playing",
+            PlayerStatus::Stopped => "stopped",
+            PlayerStatus::Paused => "paused",
+            PlayerStatus::ForwardSeek => "forward-seek",
+            PlayerStatus::ReverseSeek => "reverse-seek",
+            PlayerStatus::Error => "error",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RepeatMode {
+    Off,
+    SingleTrack,
+    AllTracks,
+    Group,
+}
+
+impl RepeatMode {
+    pub fn from_name(name: &str) -> Option<RepeatMode> {
+        match name {
+            "off" => Some(RepeatMode::Off),
+            "singletrack" => Some(RepeatMode::SingleTrack),
+            "alltracks" => Some(RepeatMode::AllTracks),
+            "group" => Some(RepeatMode::Group),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            RepeatMode::Off => "off",
+            RepeatMode::SingleTrack => "singletrack",
+            RepeatMode::AllTracks => "alltracks",
+            RepeatMode::Group => "group",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShuffleMode {
+    Off,
+    AllTracks,
+    Group,
+}
+
+impl ShuffleMode {
+    pub fn from_name(name: &str) -> Option<ShuffleMode> {
+        match name {
+            "off" => Some(ShuffleMode::Off),
+            "alltracks" => Some(ShuffleMode::AllTracks),
+            "group" => Some(ShuffleMode::Group),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            ShuffleMode::Off => "off",
+            ShuffleMode::AllTracks => "alltracks",
+            ShuffleMode::Group => "group",
+        }
+    }
+}
+
+// Track metadata as found in the Track property of MediaPlayer1 and the
+// Metadata property of MediaItem1. Players only report the attributes they know.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaTrack {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub genre: Option<String>,
+    pub number_of_tracks: Option<u32>,
+    pub track_number: Option<u32>,
+    // Milliseconds.
+    pub duration: Option<u32>,
+    // BIP handle of the cover art, see BluetoothOBEXImage.
+    pub image_handle: Option<String>,
+}
+
+impl MediaTrack {
+    pub fn from_message_item(dict: &MessageItem) -> MediaTrack {
+        let mut track = MediaTrack::default();
+        let entries = match dict.inner::<&Vec<MessageItem>>() {
+            Ok(entries) => entries,
+            Err(_) => return track,
+        };
+        for entry in entries {
+            let (key, value) = match entry.inner::<(&MessageItem, &MessageItem)>() {
+                Ok(entry) => entry,
+                Err(_) => continue,
+            };
+            let value = match value.inner::<&MessageItem>() {
+                Ok(value) => value,
+                Err(_) => continue,
+            };
+            let text = value.inner::<&str>().ok().map(String::from);
+            let number = value.inner::<u32>().ok();
+            match key.inner::<&str>().unwrap_or("") {
+                "Title" => track.title = text,
+                "Artist" => track.artist = text,
+                "Album" => track.album = text,
+                "Genre" => track.genre = text,
+                "NumberOfTracks" => track.number_of_tracks = number,
+                "TrackNumber" => track.track_number = number,
+                "Duration" => track.duration = number,
+                "ImgHandle" => track.image_handle = text,
+                _ => (),
+            }
+        }
+        track
+    }
+
+    // Same as from_message_item for the a{sv} of a signal.
+    fn from_ref_arg(dict: &RefArg) -> Option<MediaTrack> {
+        let mut items = match dict.as_iter() {
+            Some(items) => items,
+            None => return None,
+        };
+        let mut track = MediaTrack::default();
+        while let (Some(k), Some(v)) = (items.next(), items.next()) {
+            let value = match v.as_iter().and_then(|mut variant| variant.next()) {
+                Some(value) => value,
+                None => continue,
+            };
+            let text = value.as_str().map(String::from);
+            let number = value.as_u64().map(|n| n as u32);
+            match k.as_str().unwrap_or("") {
+                "Title" => track.title = text,
+                "Artist" => track.artist = text,
+                "Album" => track.album = text,
+                "Genre" => track.genre = text,
+                "NumberOfTracks" => track.number_of_tracks = number,
+                "TrackNumber" => track.track_number = number,
+                "Duration" => track.duration = number,
+                "ImgHandle" => track.image_handle = text,
+                _ => (),
+            }
+        }
+        Some(track)
+    }
+}
+
+// Events for the MediaPlayer1 entries of a property map, see
+// BluetoothEvent::from_properties. Values the player doesn't document are skipped.
+pub fn player_events(
+    object_path: &str,
+    properties: &HashMap<String, Variant<Box<RefArg>>>,
+) -> Vec<BluetoothEvent> {
+    let object_path = object_path.to_string();
+    let mut events = Vec::new();
+    let text = |name: &str| properties.get(name).and_then(|value| value.0.as_str());
+
+    if let Some(status) = text("Status").and_then(PlayerStatus::from_name) {
+        events.push(BluetoothEvent::Status {
+            object_path: object_path.clone(),
+            status: status,
+        });
+    }
+
+    if let Some(value) = properties.get("Position") {
+        if let Some(position) = cast::<u32>(&value.0) {
+            events.push(BluetoothEvent::Position {
+                object_path: object_path.clone(),
+                position: *position,
+            });
+        }
+    }
+
+    if let Some(value) = properties.get("Track") {
+        if let Some(track) = MediaTrack::from_ref_arg(&*value.0) {
+            events.push(BluetoothEvent::Track {
+                object_path: object_path.clone(),
+                track: track,
+            });
+        }
+    }
+
+    if let Some(repeat) = text("Repeat").and_then(RepeatMode::from_name) {
+        events.push(BluetoothEvent::Repeat {
+            object_path: object_path.clone(),
+            repeat: repeat,
+        });
+    }
+
+    if let Some(shuffle) = text("Shuffle").and_then(ShuffleMode::from_name) {
+        events.push(BluetoothEvent::Shuffle {
+            object_path: object_path.clone(),
+            shuffle: shuffle,
+        });
+    }
+
+    let equalizer = match text("Equalizer") {
+        Some("on") => Some(true),
+        Some("off") => Some(false),
+        _ => None,
+    };
+    if let Some(equalizer) = equalizer {
+        events.push(BluetoothEvent::Equalizer {
+            object_path: object_path.clone(),
+            equalizer: equalizer,
+        });
+    }
+
+    events
+}
+
+// MediaPlayer1 objects are children of the device, e.g. /org/bluez/hci0/dev_XX/player0.
+#[derive(Clone, Debug)]
+pub struct BluetoothMediaPlayer<'a> {
+    object_path: String,
+    session: &'a BluetoothSession,
+}
+
+impl<'a> BluetoothMediaPlayer<'a> {
+    pub fn new(session: &'a BluetoothSession, object_path: String) -> BluetoothMediaPlayer {
+        BluetoothMediaPlayer {
+            object_path: object_path,
+            session: session,
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
+        bluetooth_utils::get_property(
+            self.session.get_connection(),
+            MEDIA_PLAYER_INTERFACE,
+            &self.object_path,
+            prop,
+        )
+    }
+
+    fn set_property<T>(&self, prop: &str, value: T, timeout_ms: i32) -> Result<(), Box<Error>>
+    where
+        T: Into<MessageItem>,
+    {
+        bluetooth_utils::set_property(
+            self.session.get_connection(),
+            MEDIA_PLAYER_INTERFACE,
+            &self.object_path,
+            prop,
+            value,
+            timeout_ms,
+        )
+    }
+
+    fn call_method(&self, method: &str, param: Option<&[MessageItem]>) -> Result<(), Box<Error>> {
+        bluetooth_utils::call_method(
+            self.session.get_connection(),
+            MEDIA_PLAYER_INTERFACE,
+            &self.object_path,
+            method,
+            param,
+            1000,
+        )
+    }
+
+    // Browsable players also implement MediaFolder1 on their own object,
+    // operating on the current folder.
+    pub fn get_folder(&self) -> BluetoothMediaFolder<'a> {
+        BluetoothMediaFolder::new(self.session, self.object_path.clone())
+    }
+
+    /*
+     * Properties
+     */
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n263
+    pub fn get_equalizer(&self) -> Result<bool, Box<Error>> {
+        let equalizer = try!(self.get_property("Equalizer"));
+        Ok(equalizer.inner::<&str>().unwrap() == "on")
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n263
+    pub fn set_equalizer(&self, value: bool) -> Result<(), Box<Error>> {
+        self.set_property("Equalizer", if value { "on" } else { "off" }, 1000)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n268
+    pub fn get_repeat(&self) -> Result<RepeatMode, Box<Error>> {
+        let repeat = try!(self.get_property("Repeat"));
+        let repeat = repeat.inner::<&str>().unwrap();
+        match RepeatMode::from_name(repeat) {
+            Some(mode) => Ok(mode),
+            None => Err(Box::from(format!("Unknown repeat mode: {}", repeat))),
+        }
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n268
+    pub fn set_repeat(&self, value: RepeatMode) -> Result<(), Box<Error>> {
+        self.set_property("Repeat", value.name(), 1000)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n274
+    pub fn get_shuffle(&self) -> Result<ShuffleMode, Box<Error>> {
+        let shuffle = try!(self.get_property("Shuffle"));
+        let shuffle = shuffle.inner::<&str>().unwrap();
+        match ShuffleMode::from_name(shuffle) {
+            Some(mode) => Ok(mode),
+            None => Err(Box::from(format!("Unknown shuffle mode: {}", shuffle))),
+        }
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n274
+    pub fn set_shuffle(&self, value: ShuffleMode) -> Result<(), Box<Error>> {
+        self.set_property("Shuffle", value.name(), 1000)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n287
+    pub fn get_status(&self) -> Result<PlayerStatus, Box<Error>> {
+        let status = try!(self.get_property("Status"));
+        let status = status.inner::<&str>().unwrap();
+        match PlayerStatus::from_name(status) {
+            Some(status) => Ok(status),
+            None => Err(Box::from(format!("Unknown player status: {}", status))),
+        }
+    }
+
+    // Playback position in milliseconds.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n294
+    pub fn get_position(&self) -> Result<u32, Box<Error>> {
+        let position = try!(self.get_property("Position"));
+        Ok(position.inner::<u32>().unwrap())
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n299
+    pub fn get_track(&self) -> Result<MediaTrack, Box<Error>> {
+        let track = try!(self.get_property("Track"));
+        Ok(MediaTrack::from_message_item(&track))
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n329
+    pub fn get_device(&self) -> Result<String, Box<Error>> {
+        let device = try!(self.get_property("Device"));
+        Ok(String::from(device.inner::<&str>().unwrap()))
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n333
+    pub fn get_name(&self) -> Result<String, Box<Error>> {
+        let name = try!(self.get_property("Name"));
+        Ok(String::from(name.inner::<&str>().unwrap()))
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n337
+    pub fn get_type(&self) -> Result<String, Box<Error>> {
+        let player_type = try!(self.get_property("Type"));
+        Ok(String::from(player_type.inner::<&str>().unwrap()))
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n343
+    pub fn get_subtype(&self) -> Result<String, Box<Error>> {
+        let subtype = try!(self.get_property("Subtype"));
+        Ok(String::from(subtype.inner::<&str>().unwrap()))
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n349
+    pub fn is_browsable(&self) -> Result<bool, Box<Error>> {
+        let browsable = try!(self.get_property("Browsable"));
+        Ok(browsable.inner::<bool>().unwrap())
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n355
+    pub fn is_searchable(&self) -> Result<bool, Box<Error>> {
+        let searchable = try!(self.get_property("Searchable"));
+        Ok(searchable.inner::<bool>().unwrap())
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n361
+    pub fn get_playlist(&self) -> Result<String, Box<Error>> {
+        let playlist = try!(self.get_property("Playlist"));
+        Ok(String::from(playlist.inner::<&str>().unwrap()))
+    }
+
+    // L2CAP PSM of the cover art server, to pass to ObexSessionOptions::psm when
+    // creating a BluetoothOBEXImage session.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n366
+    pub fn get_obex_port(&self) -> Result<u16, Box<Error>> {
+        let port = try!(self.get_property("ObexPort"));
+        Ok(port.inner::<u16>().unwrap())
+    }
+
+    // Blocks until the player reports `status` or `timeout_ms` elapses.
+    pub fn wait_for_status(&self, status: PlayerStatus, timeout_ms: u32) -> Result<(), Box<Error>> {
+        try!(self.session.wait_for_property(
+            &self.object_path,
+            MEDIA_PLAYER_INTERFACE,
+            "Status",
+            |value| value.inner::<&str>().ok() == Some(status.name()),
+            timeout_ms,
+        ));
+        Ok(())
+    }
+
+    /*
+     * Methods
+     */
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n189
+    pub fn play(&self) -> Result<(), Box<Error>> {
+        self.call_method("Play", None)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n196
+    pub fn pause(&self) -> Result<(), Box<Error>> {
+        self.call_method("Pause", None)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n203
+    pub fn stop(&self) -> Result<(), Box<Error>> {
+        self.call_method("Stop", None)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n210
+    pub fn next(&self) -> Result<(), Box<Error>> {
+        self.call_method("Next", None)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n217
+    pub fn previous(&self) -> Result<(), Box<Error>> {
+        self.call_method("Previous", None)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n224
+    pub fn fast_forward(&self) -> Result<(), Box<Error>> {
+        self.call_method("FastForward", None)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n231
+    pub fn rewind(&self) -> Result<(), Box<Error>> {
+        self.call_method("Rewind", None)
+    }
+
+    // Sends a single press and release of an AVC passthrough key.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n238
+    pub fn press(&self, avc_key: u8) -> Result<(), Box<Error>> {
+        self.call_method("Press", Some(&[avc_key.into()]))
+    }
+
+    // Keeps an AVC passthrough key pressed until `release` is called.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n246
+    pub fn hold(&self, avc_key: u8) -> Result<(), Box<Error>> {
+        self.call_method("Hold", Some(&[avc_key.into()]))
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n255
+    pub fn release(&self) -> Result<(), Box<Error>> {
+        self.call_method("Release", None)
+    }
+}
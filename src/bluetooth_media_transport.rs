@@ -0,0 +1,209 @@
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::{Message, MessageItem, OwnedFd};
+
+use std::error::Error;
+
+static SERVICE_NAME: &'static str = "org.bluez";
+static MEDIA_TRANSPORT_INTERFACE: &'static str = "org.bluez.MediaTransport1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransportState {
+    // Not streaming.
+    Idle,
+    // Streaming but not acquired, TryAcquire will succeed.
+    Pending,
+    // Streaming and acquired.
+    Active,
+}
+
+impl TransportState {
+    pub fn from_name(name: &str) -> Option<TransportState> {
+        match name {
+            "idle" => Some(TransportState::Idle),
+            "pending" => Some(TransportState::Pending),
+            "active" => Some(TransportState::Active),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            TransportState::Idle => "idle",
+            TransportState::Pending => "pending",
+            TransportState::Active => "active",
+        }
+    }
+}
+
+// A MediaTransport1 object, created by BlueZ once a MediaEndpoint1 has been
+// configured, see BluetoothMediaEndpoint.
+#[derive(Clone, Debug)]
+pub struct BluetoothMediaTransport<'a> {
+    object_path: String,
+    session: &'a BluetoothSession,
+}
+
+impl<'a> BluetoothMediaTransport<'a> {
+    pub fn new(session: &'a BluetoothSession, object_path: String) -> BluetoothMediaTransport {
+        BluetoothMediaTransport {
+            object_path: object_path,
+            session: session,
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
+        bluetooth_utils::get_property(
+            self.session.get_connection(),
+            MEDIA_TRANSPORT_INTERFACE,
+            &self.object_path,
+            prop,
+        )
+    }
+
+    fn set_property<T>(&self, prop: &str, value: T, timeout_ms: i32) -> Result<(), Box<Error>>
+    where
+        T: Into<MessageItem>,
+    {
+        bluetooth_utils::set_property(
+            self.session.get_connection(),
+            MEDIA_TRANSPORT_INTERFACE,
+            &self.object_path,
+            prop,
+            value,
+            timeout_ms,
+        )
+    }
+
+    // Returns the stream fd with its read and write MTUs.
+    fn acquire_method(&self, method: &str) -> Result<(OwnedFd, u16, u16), Box<Error>> {
+        let m = try!(Message::new_method_call(
+            SERVICE_NAME,
+            &self.object_path,
+            MEDIA_TRANSPORT_INTERFACE,
+            method
+        ));
+        let reply = try!(self
+            .session
+            .get_connection()
+            .send_with_reply_and_block(m, 5000));
+        match reply.get3::<OwnedFd, u16, u16>() {
+            (Some(fd), Some(read_mtu), Some(write_mtu)) => Ok((fd, read_mtu, write_mtu)),
+            _ => Err(Box::from(format!("Invalid {} reply", method))),
+        }
+    }
+
+    /*
+     * Properties
+     */
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n586
+    pub fn get_device(&self) -> Result<String, Box<Error>> {
+        let device = try!(self.get_property("Device"));
+        Ok(String::from(device.inner::<&str>().unwrap()))
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n590
+    pub fn get_uuid(&self) -> Result<String, Box<Error>> {
+        let uuid = try!(self.get_property("UUID"));
+        Ok(String::from(uuid.inner::<&str>().unwrap()))
+    }
+
+    // One of the formats::media_codecs::CODEC_* values.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n594
+    pub fn get_codec(&self) -> Result<u8, Box<Error>> {
+        let codec = try!(self.get_property("Codec"));
+        Ok(codec.inner::<u8>().unwrap())
+    }
+
+    // Codec configuration, decode it with the matching type of media_codecs.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n599
+    pub fn get_configuration(&self) -> Result<Vec<u8>, Box<Error>> {
+        let configuration = try!(self.get_property("Configuration"));
+        let z: &[MessageItem] = configuration.inner().unwrap();
+        Ok(z.iter().map(|b| b.inner::<u8>().unwrap_or(0)).collect())
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n603
+    pub fn get_state(&self) -> Result<TransportState, Box<Error>> {
+        let state = try!(self.get_property("State"));
+        let state = state.inner::<&str>().unwrap();
+        match TransportState::from_name(state) {
+            Some(state) => Ok(state),
+            None => Err(Box::from(format!("Unknown transport state: {}", state))),
+        }
+    }
+
+    // Transport delay in 1/10 of milliseconds, only present with delay reporting.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n614
+    pub fn get_delay(&self) -> Result<u16, Box<Error>> {
+        let delay = try!(self.get_property("Delay"));
+        Ok(delay.inner::<u16>().unwrap())
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n614
+    pub fn set_delay(&self, value: u16) -> Result<(), Box<Error>> {
+        self.set_property("Delay", value, 1000)
+    }
+
+    // Volume in the range 0 to 127, only present with AVRCP absolute volume.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n619
+    pub fn get_volume(&self) -> Result<u16, Box<Error>> {
+        let volume = try!(self.get_property("Volume"));
+        Ok(volume.inner::<u16>().unwrap())
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n619
+    pub fn set_volume(&self, value: u16) -> Result<(), Box<Error>> {
+        self.set_property("Volume", value, 1000)
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n625
+    pub fn get_endpoint(&self) -> Result<String, Box<Error>> {
+        let endpoint = try!(self.get_property("Endpoint"));
+        Ok(String::from(endpoint.inner::<&str>().unwrap()))
+    }
+
+    // Blocks until the transport reaches `state` or `timeout_ms` elapses.
+    pub fn wait_for_state(&self, state: TransportState, timeout_ms: u32) -> Result<(), Box<Error>> {
+        try!(self.session.wait_for_property(
+            &self.object_path,
+            MEDIA_TRANSPORT_INTERFACE,
+            "State",
+            |value| value.inner::<&str>().ok() == Some(state.name()),
+            timeout_ms,
+        ));
+        Ok(())
+    }
+
+    /*
+     * Methods
+     */
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n561
+    pub fn acquire(&self) -> Result<(OwnedFd, u16, u16), Box<Error>> {
+        self.acquire_method("Acquire")
+    }
+
+    // Fails unless the transport is pending, i.e. the remote started streaming.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n568
+    pub fn try_acquire(&self) -> Result<(OwnedFd, u16, u16), Box<Error>> {
+        self.acquire_method("TryAcquire")
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/media-api.txt#n579
+    pub fn release(&self) -> Result<(), Box<Error>> {
+        bluetooth_utils::call_method(
+            self.session.get_connection(),
+            MEDIA_TRANSPORT_INTERFACE,
+            &self.object_path,
+            "Release",
+            None,
+            1000,
+        )
+    }
+}
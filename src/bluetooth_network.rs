@@ -0,0 +1,126 @@
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::{Message, MessageItem};
+
+use std::error::Error;
+
+static SERVICE_NAME: &'static str = "org.bluez";
+static NETWORK_INTERFACE: &'static str = "org.bluez.Network1";
+
+// PAN roles, accepted by Network1 and NetworkServer1 in place of the full UUIDs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NetworkRole {
+    // PAN User.
+    Panu,
+    // Network Access Point.
+    Nap,
+    // Group ad-hoc Network.
+    Gn,
+}
+
+impl NetworkRole {
+    pub fn from_name(name: &str) -> Option<NetworkRole> {
+        match name.to_lowercase().as_str() {
+            "panu" | "00001115-0000-1000-8000-00805f9b34fb" => Some(NetworkRole::Panu),
+            "nap" | "00001116-0000-1000-8000-00805f9b34fb" => Some(NetworkRole::Nap),
+            "gn" | "00001117-0000-1000-8000-00805f9b34fb" => Some(NetworkRole::Gn),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            NetworkRole::Panu => "panu",
+            NetworkRole::Nap => "nap",
+            NetworkRole::Gn => "gn",
+        }
+    }
+}
+
+// Network1 lives on the device object, so `object_path` is the device path.
+#[derive(Clone, Debug)]
+pub struct BluetoothNetwork<'a> {
+    object_path: String,
+    session: &'a BluetoothSession,
+}
+
+impl<'a> BluetoothNetwork<'a> {
+    pub fn new(session: &'a BluetoothSession, object_path: String) -> BluetoothNetwork {
+        BluetoothNetwork {
+            object_path: object_path,
+            session: session,
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
+        bluetooth_utils::get_property(
+            self.session.get_connection(),
+            NETWORK_INTERFACE,
+            &self.object_path,
+            prop,
+        )
+    }
+
+    /*
+     * Properties
+     */
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/network-api.txt#n36
+    pub fn is_connected(&self) -> Result<bool, Box<Error>> {
+        let connected = try!(self.get_property("Connected"));
+        Ok(connected.inner::<bool>().unwrap())
+    }
+
+    // Name of the network interface, only set while connected.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/network-api.txt#n40
+    pub fn get_interface(&self) -> Result<String, Box<Error>> {
+        let interface = try!(self.get_property("Interface"));
+        Ok(String::from(interface.inner::<&str>().unwrap()))
+    }
+
+    // Role of the remote side of the connection, only set while connected.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/network-api.txt#n44
+    pub fn get_uuid(&self) -> Result<String, Box<Error>> {
+        let uuid = try!(self.get_property("UUID"));
+        Ok(String::from(uuid.inner::<&str>().unwrap()))
+    }
+
+    /*
+     * Methods
+     */
+
+    // Connects to the `role` service of the device and returns the name of the
+    // created network interface, e.g. "bnep0".
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/network-api.txt#n12
+    pub fn connect(&self, role: NetworkRole) -> Result<String, Box<Error>> {
+        let mut m = try!(Message::new_method_call(
+            SERVICE_NAME,
+            &self.object_path,
+            NETWORK_INTERFACE,
+            "Connect"
+        ));
+        m.append_items(&[role.name().into()]);
+        let reply = try!(self
+            .session
+            .get_connection()
+            .send_with_reply_and_block(m, 30000));
+        let interface: &str = try!(reply.read1());
+        Ok(String::from(interface))
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/network-api.txt#n27
+    pub fn disconnect(&self) -> Result<(), Box<Error>> {
+        bluetooth_utils::call_method(
+            self.session.get_connection(),
+            NETWORK_INTERFACE,
+            &self.object_path,
+            "Disconnect",
+            None,
+            5000,
+        )
+    }
+}
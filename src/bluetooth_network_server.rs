@@ -0,0 +1,54 @@
+use bluetooth_network::NetworkRole;
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::MessageItem;
+
+use std::error::Error;
+
+static NETWORK_SERVER_INTERFACE: &'static str = "org.bluez.NetworkServer1";
+
+// NetworkServer1 lives on the adapter object, so `object_path` is the adapter path.
+#[derive(Clone, Debug)]
+pub struct BluetoothNetworkServer<'a> {
+    object_path: String,
+    session: &'a BluetoothSession,
+}
+
+impl<'a> BluetoothNetworkServer<'a> {
+    pub fn new(session: &'a BluetoothSession, object_path: String) -> BluetoothNetworkServer {
+        BluetoothNetworkServer {
+            object_path: object_path,
+            session: session,
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    fn call_method(&self, method: &str, param: &[MessageItem]) -> Result<(), Box<Error>> {
+        bluetooth_utils::call_method(
+            self.session.get_connection(),
+            NETWORK_SERVER_INTERFACE,
+            &self.object_path,
+            method,
+            Some(param),
+            1000,
+        )
+    }
+
+    /*
+     * Methods
+     */
+
+    // Serves `role` and adds incoming connections to the existing `bridge` interface.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/network-api.txt#n57
+    pub fn register(&self, role: NetworkRole, bridge: &str) -> Result<(), Box<Error>> {
+        self.call_method("Register", &[role.name().into(), bridge.into()])
+    }
+
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/network-api.txt#n67
+    pub fn unregister(&self, role: NetworkRole) -> Result<(), Box<Error>> {
+        self.call_method("Unregister", &[role.name().into()])
+    }
+}
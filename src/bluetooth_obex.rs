@@ -1,28 +1,69 @@
 extern crate dbus;
-use self::dbus::arg::{Dict, Variant};
 use self::dbus::Path as ObjectPath;
 use self::dbus::{BusType, Connection, Message, MessageItem, Props};
-use std::collections::HashMap;
+use std::cell::Cell;
 use std::error::Error;
-use std::path::Path;
-use std::thread::sleep;
-use std::time::Duration;
+use std::fmt;
+use std::time::Instant;
 
 use bluetooth_device::BluetoothDevice;
-use bluetooth_session::BluetoothSession;
+use bluetooth_event::BluetoothEvent;
+use bluetooth_obex_client::ObexClient;
+use bluetooth_utils;
 
 const OBEX_BUS: &str = "org.bluez.obex";
 const OBEX_PATH: &str = "/org/bluez/obex";
 const OBJECT_PUSH_INTERFACE: &str = "org.bluez.obex.ObjectPush1";
 const CLIENT_INTERFACE: &str = "org.bluez.obex.Client1";
+const SESSION_INTERFACE: &str = "org.bluez.obex.Session1";
 const TRANSFER_INTERFACE: &str = "org.bluez.obex.Transfer1";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
 
+// How long a removed transfer's final Status signal is looked for. obexd emits it
+// before removing the object, so it is at most still unread on the connection.
+const REMOVED_STATUS_MS: u32 = 100;
+
+// Declares a typed session such as BluetoothOBEXFileTransfer, owning a session of
+// `$target`. The methods of the profile are implemented next to the invocation.
+macro_rules! obex_session {
+    ($name:ident, $target:expr) => {
+        pub struct $name<'a> {
+            session: $crate::bluetooth_obex::BluetoothOBEXSession<'a>,
+        }
+
+        impl<'a> $name<'a> {
+            pub fn new(
+                client: &'a $crate::bluetooth_obex_client::ObexClient,
+                device: &$crate::bluetooth_device::BluetoothDevice,
+                options: &$crate::bluetooth_obex::ObexSessionOptions,
+            ) -> Result<$name<'a>, Box<::std::error::Error>> {
+                Ok($name {
+                    session: $crate::bluetooth_obex::BluetoothOBEXSession::create(
+                        client, device, $target, options,
+                    )?,
+                })
+            }
+
+            pub fn get_id(&self) -> String {
+                self.session.get_id()
+            }
+
+            pub fn remove_session(&self) -> Result<(), Box<::std::error::Error>> {
+                self.session.remove_session()
+            }
+        }
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SessionTarget {
     Ftp,
     Map,
     Opp,
     Pbap,
     Sync_,
+    // Cover art of AVRCP players, see BluetoothMediaPlayer::get_obex_port.
+    BipAvrcp,
 }
 
 impl SessionTarget {
@@ -33,10 +74,12 @@ impl SessionTarget {
             SessionTarget::Opp => "opp",
             SessionTarget::Pbap => "pbap",
             SessionTarget::Sync_ => "sync",
+            SessionTarget::BipAvrcp => "bip-avrcp",
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TransferState {
     Queued,
     Active,
@@ -46,7 +89,18 @@ pub enum TransferState {
 }
 
 impl TransferState {
-    fn as_str(&self) -> &str {
+    pub fn from_name(name: &str) -> Option<TransferState> {
+        match name {
+            "queued" => Some(TransferState::Queued),
+            "active" => Some(TransferState::Active),
+            "complete" => Some(TransferState::Complete),
+            "suspended" => Some(TransferState::Suspended),
+            "error" => Some(TransferState::Error),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
         match self {
             TransferState::Queued => "queued",
             TransferState::Active => "active",
@@ -57,124 +111,734 @@ impl TransferState {
     }
 }
 
+// Why a transfer did not complete, returned boxed by the waits of
+// BluetoothOBEXTransfer and recoverable with `downcast_ref`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferError {
+    // The transfer ended in the error state, e.g. rejected by the remote.
+    Failed,
+    // The transfer was stopped with `cancel`.
+    Cancelled,
+    TimedOut,
+    // obexd removed the transfer before its final status was seen.
+    Unknown,
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TransferError::Failed => write!(f, "OBEX transfer failed"),
+            TransferError::Cancelled => write!(f, "OBEX transfer cancelled"),
+            TransferError::TimedOut => write!(f, "Timed out waiting for OBEX transfer"),
+            TransferError::Unknown => write!(f, "OBEX transfer removed with unknown outcome"),
+        }
+    }
+}
+
+impl Error for TransferError {}
+
+// Passed to the progress callback of BluetoothOBEXTransfer::wait_with_progress.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransferProgress {
+    pub transferred: u64,
+    // Size of the object, if announced.
+    pub size: Option<u64>,
+    // Average rate in bytes per second since the wait started.
+    pub bytes_per_second: f64,
+}
+
+// Optional arguments of CreateSession, by default obexd looks the channel up over SDP.
+// https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n12
+#[derive(Clone, Debug, Default)]
+pub struct ObexSessionOptions {
+    source: Option<String>,
+    channel: Option<u8>,
+    psm: Option<u16>,
+    descriptor: Option<Vec<u8>>,
+}
+
+impl ObexSessionOptions {
+    pub fn new() -> ObexSessionOptions {
+        ObexSessionOptions::default()
+    }
+
+    // Address of the local adapter to connect from.
+    pub fn source(mut self, source: &str) -> ObexSessionOptions {
+        self.source = Some(source.to_string());
+        self
+    }
+
+    // RFCOMM channel to connect to.
+    pub fn channel(mut self, channel: u8) -> ObexSessionOptions {
+        self.channel = Some(channel);
+        self
+    }
+
+    // L2CAP PSM to connect to.
+    pub fn psm(mut self, psm: u16) -> ObexSessionOptions {
+        self.psm = Some(psm);
+        self
+    }
+
+    pub fn descriptor(mut self, descriptor: Vec<u8>) -> ObexSessionOptions {
+        self.descriptor = Some(descriptor);
+        self
+    }
+
+    fn to_message_item(&self, target: SessionTarget) -> MessageItem {
+        let mut args: Vec<(&str, MessageItem)> = vec![("Target", target.as_str().into())];
+        if let Some(ref source) = self.source {
+            args.push(("Source", source.as_str().into()));
+        }
+        if let Some(channel) = self.channel {
+            args.push(("Channel", channel.into()));
+        }
+        if let Some(psm) = self.psm {
+            args.push(("PSM", psm.into()));
+        }
+        if let Some(ref descriptor) = self.descriptor {
+            args.push(("Descriptor", MessageItem::from(&descriptor[..])));
+        }
+        bluetooth_utils::properties_dict(args)
+    }
+}
+
+// OBEX timestamps, "YYYYMMDDTHHMMSS" in local time, with a trailing "Z" in UTC or
+// with a trailing offset such as "+0100" as sent in MAP message listings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ObexTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub utc: bool,
+    // Minutes east of UTC.
+    pub utc_offset: Option<i16>,
+}
+
+impl ObexTime {
+    pub fn parse(value: &str) -> Option<ObexTime> {
+        let value = value.trim();
+        let (value, utc) = if value.ends_with('Z') {
+            (&value[..value.len() - 1], true)
+        } else {
+            (value, false)
+        };
+        let (value, utc_offset) = match parse_utc_offset(value) {
+            Some(offset) if !utc => (&value[..value.len() - 5], Some(offset)),
+            _ => (value, None),
+        };
+        // Only ASCII passes, so the fields can be sliced by byte offsets.
+        let valid = value.len() == 15
+            && value.bytes().enumerate().all(|(i, b)| match i {
+                8 => b == b'T',
+                _ => b.is_ascii_digit(),
+            });
+        if !valid {
+            return None;
+        }
+        let field = |start: usize, end: usize| value[start..end].parse::<u32>().ok();
+        Some(ObexTime {
+            year: field(0, 4)? as u16,
+            month: field(4, 6)? as u8,
+            day: field(6, 8)? as u8,
+            hours: field(9, 11)? as u8,
+            minutes: field(11, 13)? as u8,
+            seconds: field(13, 15)? as u8,
+            utc,
+            utc_offset,
+        })
+    }
+}
+
+// "+hhmm" or "-hhmm" at the end of `value`, in minutes.
+fn parse_utc_offset(value: &str) -> Option<i16> {
+    let bytes = value.as_bytes();
+    if bytes.len() < 5 {
+        return None;
+    }
+    let offset = &bytes[bytes.len() - 5..];
+    let sign = match offset[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    if !offset[1..].iter().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let digit = |i: usize| (offset[i] - b'0') as i16;
+    let (hours, minutes) = (digit(1) * 10 + digit(2), digit(3) * 10 + digit(4));
+    if minutes >= 60 {
+        return None;
+    }
+    Some(sign * (hours * 60 + minutes))
+}
+
+impl fmt::Display for ObexTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:04}{:02}{:02}T{:02}{:02}{:02}{}",
+            self.year,
+            self.month,
+            self.day,
+            self.hours,
+            self.minutes,
+            self.seconds,
+            if self.utc { "Z" } else { "" }
+        )?;
+        match self.utc_offset {
+            Some(offset) => write!(
+                f,
+                "{}{:02}{:02}",
+                if offset < 0 { '-' } else { '+' },
+                offset.abs() / 60,
+                offset.abs() % 60
+            ),
+            None => Ok(()),
+        }
+    }
+}
+
 pub fn open_bus_connection() -> Result<Connection, Box<Error>> {
     let c = Connection::get_private(BusType::Session)?;
     Ok(c)
 }
 
+// A session of obexd, removed when dropped unless `remove_session` was called.
 pub struct BluetoothOBEXSession<'a> {
-    session: &'a BluetoothSession,
+    client: &'a ObexClient,
     object_path: String,
+    // Cleared by remove_session.
+    remove_on_drop: Cell<bool>,
 }
 
 impl<'a> BluetoothOBEXSession<'a> {
-    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n12
+    // Creates an Object Push session, see ObexClient::create_session for the other
+    // targets.
     pub fn new(
-        session: &'a BluetoothSession,
+        client: &'a ObexClient,
         device: &BluetoothDevice,
+    ) -> Result<BluetoothOBEXSession<'a>, Box<Error>> {
+        BluetoothOBEXSession::create(
+            client,
+            device,
+            SessionTarget::Opp,
+            &ObexSessionOptions::new(),
+        )
+    }
+
+    // The typed sessions, e.g. BluetoothOBEXFileTransfer, create theirs through this.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n12
+    pub(crate) fn create(
+        client: &'a ObexClient,
+        device: &BluetoothDevice,
+        target: SessionTarget,
+        options: &ObexSessionOptions,
     ) -> Result<BluetoothOBEXSession<'a>, Box<Error>> {
         let device_address: String = device.get_address()?;
-        let mut map = HashMap::new();
-        map.insert("Target", Variant(SessionTarget::Opp.as_str()));
-        let args: Dict<&str, Variant<&str>, _> = Dict::new(map);
-        let m = Message::new_method_call(OBEX_BUS, OBEX_PATH, CLIENT_INTERFACE, "CreateSession")?
-            .append2(device_address, args);
+        let mut m =
+            Message::new_method_call(OBEX_BUS, OBEX_PATH, CLIENT_INTERFACE, "CreateSession")?;
+        m.append_items(&[device_address.into(), options.to_message_item(target)]);
 
-        let r = session
-            .get_connection()
-            .send_with_reply_and_block(m, 1000)?;
+        let r = client.get_connection().send_with_reply_and_block(m, 1000)?;
         let session_path: ObjectPath = r.read1()?;
         let session_str: String = session_path.parse()?;
+        client.add_session(&session_str);
         let obex_session = BluetoothOBEXSession {
-            session,
+            client,
             object_path: session_str,
+            remove_on_drop: Cell::new(true),
         };
         Ok(obex_session)
     }
 
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    pub fn get_connection(&self) -> &Connection {
+        self.client.get_connection()
+    }
+
+    fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
+        let p = Props::new(
+            self.get_connection(),
+            OBEX_BUS,
+            &self.object_path,
+            SESSION_INTERFACE,
+            1000,
+        );
+        Ok(p.get(prop)?)
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n58
+    pub fn get_source(&self) -> Result<String, Box<Error>> {
+        let source = self.get_property("Source")?;
+        Ok(String::from(source.inner::<&str>().unwrap()))
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n62
+    pub fn get_destination(&self) -> Result<String, Box<Error>> {
+        let destination = self.get_property("Destination")?;
+        Ok(String::from(destination.inner::<&str>().unwrap()))
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n66
+    pub fn get_channel(&self) -> Result<u8, Box<Error>> {
+        let channel = self.get_property("Channel")?;
+        Ok(channel.inner::<u8>().unwrap())
+    }
+
+    // Target UUID of the session.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n70
+    pub fn get_target(&self) -> Result<String, Box<Error>> {
+        let target = self.get_property("Target")?;
+        Ok(String::from(target.inner::<&str>().unwrap()))
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n74
+    pub fn get_root(&self) -> Result<String, Box<Error>> {
+        let root = self.get_property("Root")?;
+        Ok(String::from(root.inner::<&str>().unwrap()))
+    }
+
+    // Capabilities object of the remote device, in XML.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n50
+    pub fn get_capabilities(&self) -> Result<String, Box<Error>> {
+        let r = self.call_method(SESSION_INTERFACE, "GetCapabilities", &[], 5000)?;
+        let capabilities: &str = r.read1()?;
+        Ok(capabilities.to_string())
+    }
+
+    // Calls `method` of `interface` on the session object, as done by the typed sessions.
+    pub(crate) fn call_method(
+        &self,
+        interface: &str,
+        method: &str,
+        args: &[MessageItem],
+        timeout_ms: i32,
+    ) -> Result<Message, Box<Error>> {
+        self.call_object_method(&self.object_path, interface, method, args, timeout_ms)
+    }
+
+    // Same as call_method, for objects below the session such as MAP messages.
+    pub(crate) fn call_object_method(
+        &self,
+        object_path: &str,
+        interface: &str,
+        method: &str,
+        args: &[MessageItem],
+        timeout_ms: i32,
+    ) -> Result<Message, Box<Error>> {
+        let mut m = Message::new_method_call(OBEX_BUS, object_path, interface, method)?;
+        m.append_items(args);
+        Ok(self
+            .get_connection()
+            .send_with_reply_and_block(m, timeout_ms)?)
+    }
+
     // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n35
     pub fn remove_session(&self) -> Result<(), Box<Error>> {
         let object_path = ObjectPath::new(self.object_path.as_bytes())?;
         let m = Message::new_method_call(OBEX_BUS, OBEX_PATH, CLIENT_INTERFACE, "RemoveSession")?
             .append1(object_path);
-        let _r = self
-            .session
-            .get_connection()
-            .send_with_reply_and_block(m, 1000)?;
+        let _r = self.get_connection().send_with_reply_and_block(m, 1000)?;
+        self.remove_on_drop.set(false);
+        self.client.forget_session(&self.object_path);
         Ok(())
     }
 }
 
+impl<'a> Drop for BluetoothOBEXSession<'a> {
+    fn drop(&mut self) {
+        if self.remove_on_drop.get() {
+            let _ = self.remove_session();
+        }
+        self.client.forget_session(&self.object_path);
+    }
+}
+
 pub struct BluetoothOBEXTransfer<'a> {
     session: &'a BluetoothOBEXSession<'a>,
     object_path: String,
-    _name: String,
+    // Set by `cancel`, see `wait`.
+    cancelled: Cell<bool>,
+    // Last status seen by `wait`, tells how a transfer obexd removed ended.
+    last_status: Cell<Option<TransferState>>,
 }
 
 impl<'a> BluetoothOBEXTransfer<'a> {
+    // Calls a session method replying with the (object transfer, dict properties) pair.
+    pub(crate) fn start(
+        session: &'a BluetoothOBEXSession,
+        interface: &str,
+        method: &str,
+        args: &[MessageItem],
+    ) -> Result<BluetoothOBEXTransfer<'a>, Box<Error>> {
+        let r = session.call_method(interface, method, args, 1000)?;
+        BluetoothOBEXTransfer::from_reply(session, &r)
+    }
+
+    // Wraps the transfer of a (object transfer, dict properties) reply.
+    pub(crate) fn from_reply(
+        session: &'a BluetoothOBEXSession,
+        reply: &Message,
+    ) -> Result<BluetoothOBEXTransfer<'a>, Box<Error>> {
+        let transfer_path: ObjectPath = reply.read1()?;
+        Ok(BluetoothOBEXTransfer {
+            session,
+            object_path: transfer_path.parse()?,
+            cancelled: Cell::new(false),
+            last_status: Cell::new(None),
+        })
+    }
+
     // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n169
     pub fn send_file(
         session: &'a BluetoothOBEXSession,
         file_path: &str,
     ) -> Result<BluetoothOBEXTransfer<'a>, Box<Error>> {
-        let session_path: String = session.object_path.clone();
-        let m =
-            Message::new_method_call(OBEX_BUS, session_path, OBJECT_PUSH_INTERFACE, "SendFile")?
-                .append1(file_path);
-        let r = session
-            .session
-            .get_connection()
-            .send_with_reply_and_block(m, 1000)?;
-        let transfer_path: ObjectPath = r.read1()?;
-        let transfer_str: String = transfer_path.parse()?;
-
-        let file_name: String = match Path::new(file_path).file_name() {
-            Some(value) => value.to_string_lossy().to_string(),
-            None => file_path.to_string(),
-        };
-
-        let obex_transfer = BluetoothOBEXTransfer {
+        BluetoothOBEXTransfer::start(
             session,
-            object_path: transfer_str,
-            _name: file_name,
-        };
-        Ok(obex_transfer)
+            OBJECT_PUSH_INTERFACE,
+            "SendFile",
+            &[file_path.into()],
+        )
     }
 
-    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n115
-    pub fn status(&self) -> Result<String, Box<Error>> {
-        let transfer_path = self.object_path.clone();
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
         let p = Props::new(
-            &self.session.session.get_connection(),
+            self.session.get_connection(),
             OBEX_BUS,
-            transfer_path,
+            &self.object_path,
             TRANSFER_INTERFACE,
             1000,
         );
-        let status: MessageItem = p.get("Status")?;
+        Ok(p.get(prop)?)
+    }
+
+    fn call_method(&self, method: &str) -> Result<(), Box<Error>> {
+        self.session.call_object_method(
+            &self.object_path,
+            TRANSFER_INTERFACE,
+            method,
+            &[],
+            1000,
+        )?;
+        Ok(())
+    }
+
+    // Adds a match rule for the PropertiesChanged signals of the transfer, which
+    // BluetoothEvent turns into TransferStatus and Transferred events.
+    pub fn subscribe(&self) -> Result<(), Box<Error>> {
+        self.session
+            .get_connection()
+            .add_match(&self.match_rule())?;
+        Ok(())
+    }
+
+    // Removes the match rule added by `subscribe`.
+    pub fn unsubscribe(&self) -> Result<(), Box<Error>> {
+        self.session
+            .get_connection()
+            .remove_match(&self.match_rule())?;
+        Ok(())
+    }
+
+    fn match_rule(&self) -> String {
+        format!(
+            "type='signal',sender='{}',path='{}',interface='{}',member='PropertiesChanged'",
+            OBEX_BUS, self.object_path, PROPERTIES_INTERFACE
+        )
+    }
+
+    /*
+     * Properties
+     */
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n115
+    pub fn status(&self) -> Result<String, Box<Error>> {
+        let status: MessageItem = self.get_property("Status")?;
         match status.inner::<&str>() {
             Ok(value) => Ok(value.to_string()),
             Err(_) => Err("Failed to get status.".into()),
         }
     }
 
+    pub fn get_status(&self) -> Result<TransferState, Box<Error>> {
+        let status = self.status()?;
+        match TransferState::from_name(&status) {
+            Some(state) => Ok(state),
+            None => Err(Box::from(format!("Unknown transfer status: {}", status))),
+        }
+    }
+
+    // Object path of the session the transfer belongs to.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n122
+    pub fn get_session(&self) -> Result<String, Box<Error>> {
+        let session = self.get_property("Session")?;
+        let session: &ObjectPath = session.inner().unwrap();
+        Ok(session.to_string())
+    }
+
+    // Name of the transferred object, e.g. the remote file name.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n126
+    pub fn get_name(&self) -> Result<String, Box<Error>> {
+        let name = self.get_property("Name")?;
+        Ok(String::from(name.inner::<&str>().unwrap()))
+    }
+
+    // MIME type of the object, if known.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n131
+    pub fn get_type(&self) -> Result<String, Box<Error>> {
+        let transfer_type = self.get_property("Type")?;
+        Ok(String::from(transfer_type.inner::<&str>().unwrap()))
+    }
+
+    // Seconds since the epoch of the object's timestamp, if known.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n135
+    pub fn get_time(&self) -> Result<u64, Box<Error>> {
+        let time = self.get_property("Time")?;
+        Ok(time.inner::<u64>().unwrap())
+    }
+
+    // Size in bytes, if known.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n139
+    pub fn get_size(&self) -> Result<u64, Box<Error>> {
+        let size = self.get_property("Size")?;
+        Ok(size.inner::<u64>().unwrap())
+    }
+
+    // Bytes transferred so far, only available while active.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n143
+    pub fn get_transferred(&self) -> Result<u64, Box<Error>> {
+        let transferred = self.get_property("Transferred")?;
+        Ok(transferred.inner::<u64>().unwrap())
+    }
+
+    // Complete name of the local file.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n148
+    pub fn get_filename(&self) -> Result<String, Box<Error>> {
+        let filename = self.get_property("Filename")?;
+        Ok(String::from(filename.inner::<&str>().unwrap()))
+    }
+
+    /*
+     * Methods
+     */
+
+    // Stops the transfer, obexd removes partially received files.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n94
+    pub fn cancel(&self) -> Result<(), Box<Error>> {
+        self.cancelled.set(true);
+        self.call_method("Cancel")
+    }
+
+    // Pauses the transfer, only allowed while it is queued or active.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n101
+    pub fn suspend(&self) -> Result<(), Box<Error>> {
+        self.call_method("Suspend")
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n108
+    pub fn resume(&self) -> Result<(), Box<Error>> {
+        self.call_method("Resume")
+    }
+
+    // Waits without a timeout, see wait.
     pub fn wait_until_transfer_completed(&self) -> Result<(), Box<Error>> {
-        sleep(Duration::from_millis(500));
-        let mut transfer_status: String = self.status()?;
-
-        while transfer_status != TransferState::Complete.as_str() {
-            sleep(Duration::from_millis(500));
-            transfer_status = match self.status() {
-                Ok(value) => {
-                    if value == TransferState::Error.as_str() {
-                        break;
-                    } else {
-                        value
+        self.wait(u32::max_value())
+    }
+
+    // Blocks until the transfer completes. Fails with a TransferError when it
+    // ends in the error state, is cancelled, `timeout_ms` elapses or obexd removed
+    // it before its final status could be seen. Only `cancel` calls on this object,
+    // also from within a progress callback, are reported as TransferError::Cancelled,
+    // other cancels end in TransferError::Failed.
+    pub fn wait(&self, timeout_ms: u32) -> Result<(), Box<Error>> {
+        self.wait_with_progress(timeout_ms, |_| {})
+    }
+
+    // Same as wait, calling `progress` whenever the Transferred property changes.
+    // Driven by the PropertiesChanged signals of the transfer, messages read while
+    // waiting are kept for `incoming_queued` except for the one ending the transfer.
+    pub fn wait_with_progress<F>(&self, timeout_ms: u32, progress: F) -> Result<(), Box<Error>>
+    where
+        F: FnMut(&TransferProgress),
+    {
+        self.subscribe()?;
+        let result = self.wait_for_end(timeout_ms, progress);
+        let _ = self.unsubscribe();
+        result
+    }
+
+    fn wait_for_end<F>(&self, timeout_ms: u32, mut progress: F) -> Result<(), Box<Error>>
+    where
+        F: FnMut(&TransferProgress),
+    {
+        let started = Instant::now();
+        // obexd removes the transfer object once it completes or fails.
+        let status = match self.get_status() {
+            Ok(status) => status,
+            Err(ref err) if is_unknown_object(&**err) => return self.removed_result(),
+            Err(err) => return Err(err),
+        };
+        if let Some(result) = self.finished(status) {
+            return result;
+        }
+        let size = self.get_size().ok();
+        let initial = self.get_transferred().unwrap_or(0);
+
+        let session = self.session.client.get_session();
+        let result = session.wait_for_message(timeout_ms, |msg| {
+            if msg.path().map_or(true, |path| &*path != self.object_path) {
+                return None;
+            }
+            for event in BluetoothEvent::all_from(msg).unwrap_or_default() {
+                match event {
+                    BluetoothEvent::TransferStatus { status, .. } => {
+                        if let Some(result) = self.finished(status) {
+                            return Some(result);
+                        }
+                    }
+                    BluetoothEvent::Transferred { transferred, .. } => {
+                        let elapsed = started.elapsed();
+                        let seconds = elapsed.as_secs() as f64
+                            + elapsed.subsec_nanos() as f64 / 1_000_000_000.0;
+                        let bytes_per_second = if seconds > 0.0 {
+                            transferred.saturating_sub(initial) as f64 / seconds
+                        } else {
+                            0.0
+                        };
+                        progress(&TransferProgress {
+                            transferred,
+                            size,
+                            bytes_per_second,
+                        });
                     }
+                    _ => {}
                 }
-                Err(_) => break,
             }
+            None
+        });
+        result.unwrap_or_else(|| Err(Box::new(TransferError::TimedOut)))
+    }
+
+    // Outcome of a transfer obexd has removed, from the status seen last or the
+    // Status signal sent before the removal.
+    fn removed_result(&self) -> Result<(), Box<Error>> {
+        if let Some(result) = self.last_status.get().and_then(|s| self.finished(s)) {
+            return result;
         }
-        Ok(())
+        let session = self.session.client.get_session();
+        let result = session.wait_for_message(REMOVED_STATUS_MS, |msg| {
+            if msg.path().map_or(true, |path| &*path != self.object_path) {
+                return None;
+            }
+            BluetoothEvent::all_from(msg)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|event| match event {
+                    BluetoothEvent::TransferStatus { status, .. } => self.finished(status),
+                    _ => None,
+                })
+                .next()
+        });
+        result.unwrap_or_else(|| {
+            if self.cancelled.get() {
+                Err(Box::new(TransferError::Cancelled))
+            } else {
+                Err(Box::new(TransferError::Unknown))
+            }
+        })
+    }
+
+    fn finished(&self, status: TransferState) -> Option<Result<(), Box<Error>>> {
+        self.last_status.set(Some(status));
+        match status {
+            TransferState::Complete => Some(Ok(())),
+            TransferState::Error if self.cancelled.get() => {
+                Some(Err(Box::new(TransferError::Cancelled)))
+            }
+            TransferState::Error => Some(Err(Box::new(TransferError::Failed))),
+            _ => None,
+        }
+    }
+}
+
+// Error of calls on objects that obexd has removed.
+fn is_unknown_object(err: &(Error + 'static)) -> bool {
+    match err.downcast_ref::<dbus::Error>().and_then(|e| e.name()) {
+        Some("org.freedesktop.DBus.Error.UnknownObject")
+        | Some("org.freedesktop.DBus.Error.UnknownMethod") => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ObexTime;
+
+    #[test]
+    fn parse_obex_time() {
+        let time = ObexTime::parse("20200131T235958Z").unwrap();
+        assert_eq!(
+            time,
+            ObexTime {
+                year: 2020,
+                month: 1,
+                day: 31,
+                hours: 23,
+                minutes: 59,
+                seconds: 58,
+                utc: true,
+                utc_offset: None,
+            }
+        );
+        assert_eq!(time.to_string(), "20200131T235958Z");
+
+        let time = ObexTime::parse("19991231T000000").unwrap();
+        assert!(!time.utc);
+        assert_eq!(time.utc_offset, None);
+        assert_eq!(time.to_string(), "19991231T000000");
+    }
+
+    #[test]
+    fn parse_obex_time_with_offset() {
+        let time = ObexTime::parse("20200131T235958+0130").unwrap();
+        assert_eq!((time.hours, time.minutes, time.seconds), (23, 59, 58));
+        assert!(!time.utc);
+        assert_eq!(time.utc_offset, Some(90));
+        assert_eq!(time.to_string(), "20200131T235958+0130");
+
+        let time = ObexTime::parse("20200131T235958-0500").unwrap();
+        assert_eq!(time.utc_offset, Some(-300));
+        assert_eq!(time.to_string(), "20200131T235958-0500");
+
+        assert_eq!(ObexTime::parse("20200131T235958+01"), None);
+        assert_eq!(ObexTime::parse("20200131T235958+0160"), None);
+        assert_eq!(ObexTime::parse("20200131T235958+0100Z"), None);
+        assert_eq!(ObexTime::parse("20200131T235958é0100"), None);
+    }
+
+    #[test]
+    fn parse_malformed_obex_time() {
+        assert_eq!(ObexTime::parse(""), None);
+        assert_eq!(ObexTime::parse("Z"), None);
+        assert_eq!(ObexTime::parse("20200101"), None);
+        assert_eq!(ObexTime::parse("20200101 123456"), None);
+        assert_eq!(ObexTime::parse("2020+101T123456"), None);
+        assert_eq!(ObexTime::parse("20200101T12345"), None);
+        // Multi-byte characters around the separator must not panic.
+        assert_eq!(ObexTime::parse("20200101é12345"), None);
+        assert_eq!(ObexTime::parse("2020010éT12345"), None);
+        assert_eq!(ObexTime::parse("20200101T1234é"), None);
     }
 }
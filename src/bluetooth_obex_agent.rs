@@ -0,0 +1,354 @@
+use bluetooth_obex_client::ObexClient;
+use dbus::{
+    Message, MessageItem, MessageType, MsgHandler, MsgHandlerResult, MsgHandlerType, Path, Props,
+};
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+static OBEX_BUS: &'static str = "org.bluez.obex";
+static AGENT_MANAGER_PATH: &'static str = "/org/bluez/obex";
+static AGENT_MANAGER_INTERFACE: &'static str = "org.bluez.obex.AgentManager1";
+static AGENT_INTERFACE: &'static str = "org.bluez.obex.Agent1";
+static TRANSFER_INTERFACE: &'static str = "org.bluez.obex.Transfer1";
+static SESSION_INTERFACE: &'static str = "org.bluez.obex.Session1";
+static REJECTED_ERROR: &'static str = "org.bluez.obex.Error.Rejected";
+
+// An incoming Object Push, as passed to the PushPolicy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PushRequest {
+    // Object path of the Transfer1 object, see BluetoothOBEXTransfer.
+    pub transfer: String,
+    // File name proposed by the sender.
+    pub name: String,
+    pub size: Option<u64>,
+    pub mime_type: Option<String>,
+    // Bluetooth address of the sender.
+    pub sender: Option<String>,
+    // Where obexd would store the object by default.
+    pub filename: Option<String>,
+}
+
+// Decides on incoming pushes, returning the absolute path to store the object
+// at, or None to reject it.
+pub trait PushPolicy {
+    fn authorize_push(&mut self, request: &PushRequest) -> Option<String>;
+}
+
+impl<F> PushPolicy for F
+where
+    F: FnMut(&PushRequest) -> Option<String>,
+{
+    fn authorize_push(&mut self, request: &PushRequest) -> Option<String> {
+        self(request)
+    }
+}
+
+// Accepts every push into `directory`, keeping only the file name of the sender's
+// proposal and adding a "-N" suffix instead of overwriting existing files. The
+// name is reserved by creating the file empty, obexd then writes the object to it.
+#[derive(Clone, Debug)]
+pub struct AcceptIntoDirectory {
+    directory: PathBuf,
+    max_size: Option<u64>,
+}
+
+impl AcceptIntoDirectory {
+    pub fn new(directory: &str) -> AcceptIntoDirectory {
+        AcceptIntoDirectory {
+            directory: PathBuf::from(directory),
+            max_size: None,
+        }
+    }
+
+    // Rejects objects announced larger than `max_size` bytes, and those whose
+    // size isn't announced.
+    pub fn max_size(mut self, max_size: u64) -> AcceptIntoDirectory {
+        self.max_size = Some(max_size);
+        self
+    }
+}
+
+impl PushPolicy for AcceptIntoDirectory {
+    fn authorize_push(&mut self, request: &PushRequest) -> Option<String> {
+        if let Some(max_size) = self.max_size {
+            match request.size {
+                Some(size) if size <= max_size => (),
+                _ => return None,
+            }
+        }
+        let name = PathBuf::from(&request.name);
+        let mut path = self.directory.join(name.file_name()?);
+        let mut n = 1;
+        loop {
+            // create_new fails if the file exists, unlike checking for it first.
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => break,
+                Err(ref err) if err.kind() == ErrorKind::AlreadyExists => (),
+                Err(_) => return None,
+            }
+            let mut unique = name.file_stem()?.to_os_string();
+            unique.push(format!("-{}", n));
+            if let Some(extension) = name.extension() {
+                unique.push(".");
+                unique.push(extension);
+            }
+            path = self.directory.join(unique);
+            n += 1;
+        }
+        path.to_str().map(String::from)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AgentEvent {
+    // `path` is where obexd stores the object, see BluetoothOBEXTransfer to follow it.
+    PushAccepted { request: PushRequest, path: String },
+    PushRejected { request: PushRequest },
+    // obexd cancelled the pending request, e.g. because the sender aborted.
+    Cancel,
+    // obexd unregistered the agent, no further events follow.
+    Release,
+}
+
+enum AgentCall {
+    // The replies are prepared by the handler, since the call itself can't be kept.
+    AuthorizePush {
+        transfer: String,
+        accept: Message,
+        reject: Message,
+    },
+    Cancel,
+    Release,
+}
+
+type AgentCalls = Rc<RefCell<VecDeque<AgentCall>>>;
+
+// An Agent1 object exported on the client's connection and registered with the
+// AgentManager1 of obexd. Pushes are authorized from `next_event`, so the client
+// must be polled through it.
+pub struct BluetoothOBEXAgent<'a> {
+    client: &'a ObexClient,
+    object_path: String,
+    calls: AgentCalls,
+    active: Rc<Cell<bool>>,
+    policy: Box<PushPolicy>,
+    registered: bool,
+}
+
+impl<'a> BluetoothOBEXAgent<'a> {
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-agent-api.txt#n13
+    pub fn register<P>(
+        client: &'a ObexClient,
+        object_path: &str,
+        policy: P,
+    ) -> Result<BluetoothOBEXAgent<'a>, Box<Error>>
+    where
+        P: PushPolicy + 'static,
+    {
+        let calls: AgentCalls = Rc::new(RefCell::new(VecDeque::new()));
+        let active = Rc::new(Cell::new(true));
+        let connection = client.get_connection();
+        try!(connection.register_object_path(object_path));
+        connection.add_handler(AgentHandler {
+            object_path: String::from(object_path),
+            calls: calls.clone(),
+            active: active.clone(),
+        });
+
+        let mut agent = BluetoothOBEXAgent {
+            client: client,
+            object_path: String::from(object_path),
+            calls: calls,
+            active: active,
+            policy: Box::new(policy),
+            registered: false,
+        };
+        try!(agent.call_manager("RegisterAgent"));
+        agent.registered = true;
+        Ok(agent)
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    fn call_manager(&self, method: &str) -> Result<(), Box<Error>> {
+        let mut m = try!(Message::new_method_call(
+            OBEX_BUS,
+            AGENT_MANAGER_PATH,
+            AGENT_MANAGER_INTERFACE,
+            method
+        ));
+        m.append_items(&[MessageItem::ObjectPath(Path::from(
+            self.object_path.clone(),
+        ))]);
+        try!(self
+            .client
+            .get_connection()
+            .send_with_reply_and_block(m, 1000));
+        Ok(())
+    }
+
+    fn push_request(&self, transfer: String) -> Result<PushRequest, Box<Error>> {
+        let connection = self.client.get_connection();
+        let properties =
+            try!(Props::new(connection, OBEX_BUS, &transfer, TRANSFER_INTERFACE, 1000).get_all());
+        let text = |key: &str| {
+            properties
+                .get(key)
+                .and_then(|v| v.inner::<&str>().ok())
+                .map(String::from)
+        };
+        let sender = properties
+            .get("Session")
+            .and_then(|s| s.inner::<&Path>().ok())
+            .and_then(|s| {
+                Props::new(connection, OBEX_BUS, &**s, SESSION_INTERFACE, 1000)
+                    .get("Destination")
+                    .ok()
+            })
+            .and_then(|d| d.inner::<&str>().ok().map(String::from));
+        Ok(PushRequest {
+            name: text("Name").unwrap_or_default(),
+            size: properties.get("Size").and_then(|s| s.inner::<u64>().ok()),
+            mime_type: text("Type"),
+            sender: sender,
+            filename: text("Filename"),
+            transfer: transfer,
+        })
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-agent-api.txt#n45
+    fn authorize_push(
+        &mut self,
+        transfer: String,
+        accept: Message,
+        reject: Message,
+    ) -> Option<AgentEvent> {
+        let connection = self.client.get_connection();
+        let request = match self.push_request(transfer) {
+            Ok(request) => request,
+            Err(_) => {
+                let _ = connection.send(reject);
+                return None;
+            }
+        };
+        match self.policy.authorize_push(&request) {
+            Some(path) => {
+                let _ = connection.send(accept.append1(path.as_str()));
+                Some(AgentEvent::PushAccepted {
+                    request: request,
+                    path: path,
+                })
+            }
+            None => {
+                let _ = connection.send(reject);
+                Some(AgentEvent::PushRejected { request: request })
+            }
+        }
+    }
+
+    // Waits up to `timeout_ms` for the next call from obexd, answering pushes with
+    // the policy.
+    pub fn next_event(&mut self, timeout_ms: u32) -> Option<AgentEvent> {
+        let calls = self.calls.clone();
+        let call = self
+            .client
+            .get_session()
+            .poll_until(timeout_ms, move || calls.borrow_mut().pop_front());
+        match call {
+            Some(AgentCall::AuthorizePush {
+                transfer,
+                accept,
+                reject,
+            }) => self.authorize_push(transfer, accept, reject),
+            Some(AgentCall::Cancel) => Some(AgentEvent::Cancel),
+            Some(AgentCall::Release) => Some(AgentEvent::Release),
+            None => None,
+        }
+    }
+
+    // Same as dropping the agent, but reports whether obexd accepted it.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-agent-api.txt#n21
+    pub fn unregister(mut self) -> Result<(), Box<Error>> {
+        self.registered = false;
+        self.call_manager("UnregisterAgent")
+    }
+}
+
+impl<'a> Drop for BluetoothOBEXAgent<'a> {
+    fn drop(&mut self) {
+        if self.registered {
+            let _ = self.call_manager("UnregisterAgent");
+        }
+        self.active.set(false);
+        self.client
+            .get_connection()
+            .unregister_object_path(&self.object_path);
+    }
+}
+
+struct AgentHandler {
+    object_path: String,
+    calls: AgentCalls,
+    active: Rc<Cell<bool>>,
+}
+
+impl MsgHandler for AgentHandler {
+    fn handler_type(&self) -> MsgHandlerType {
+        MsgHandlerType::MsgType(MessageType::MethodCall)
+    }
+
+    fn handle_msg(&mut self, msg: &Message) -> Option<MsgHandlerResult> {
+        if !self.active.get() {
+            return Some(MsgHandlerResult {
+                handled: false,
+                done: true,
+                reply: Vec::new(),
+            });
+        }
+        let path = msg.path().map(|p| p.to_string()).unwrap_or_default();
+        let interface = msg.interface().map(|i| i.to_string()).unwrap_or_default();
+        if path != self.object_path || interface != AGENT_INTERFACE {
+            return None;
+        }
+        let member = msg.member().map(|m| m.to_string()).unwrap_or_default();
+        // AuthorizePush is answered later by next_event, the others right away.
+        let (call, reply) = match member.as_str() {
+            "AuthorizePush" => {
+                let transfer = match msg.get_items().into_iter().next() {
+                    Some(MessageItem::ObjectPath(path)) => path.to_string(),
+                    _ => return None,
+                };
+                let accept = Message::new_method_return(msg);
+                let reject = Message::new_error(msg, REJECTED_ERROR, "Push rejected");
+                match (accept, reject) {
+                    (Some(accept), Some(reject)) => (
+                        AgentCall::AuthorizePush {
+                            transfer: transfer,
+                            accept: accept,
+                            reject: reject,
+                        },
+                        None,
+                    ),
+                    _ => return None,
+                }
+            }
+            "Cancel" => (AgentCall::Cancel, Message::new_method_return(msg)),
+            "Release" => (AgentCall::Release, Message::new_method_return(msg)),
+            _ => return None,
+        };
+        self.calls.borrow_mut().push_back(call);
+        Some(MsgHandlerResult {
+            handled: true,
+            done: false,
+            reply: reply.into_iter().collect(),
+        })
+    }
+}
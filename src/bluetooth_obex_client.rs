@@ -0,0 +1,142 @@
+use bluetooth_device::BluetoothDevice;
+use bluetooth_obex::{
+    open_bus_connection, BluetoothOBEXSession, ObexSessionOptions, SessionTarget,
+};
+use bluetooth_obex_file_transfer::BluetoothOBEXFileTransfer;
+use bluetooth_obex_image::BluetoothOBEXImage;
+use bluetooth_obex_message::BluetoothOBEXMessageAccess;
+use bluetooth_obex_object_push::BluetoothOBEXObjectPush;
+use bluetooth_obex_phonebook::BluetoothOBEXPhonebookAccess;
+use bluetooth_obex_sync::BluetoothOBEXSynchronization;
+use bluetooth_session::{BluetoothSession, SessionMessages};
+use dbus::{ConnMsgs, Connection};
+
+use std::cell::RefCell;
+use std::error::Error;
+
+static OBEX_MATCH: &'static str = "type='signal',sender='org.bluez.obex'";
+
+// Entry point for OBEX, owning its own connection to obexd which runs on the session
+// bus, unlike bluetoothd. The BluetoothSession of the system bus is still needed for
+// the BluetoothDevice passed in.
+//
+// Sessions, plain or typed such as BluetoothOBEXFileTransfer, are created by the
+// client, which keeps track of them until they are removed. They are removed from
+// obexd when dropped, so they can't outlive the client.
+pub struct ObexClient {
+    session: BluetoothSession,
+    sessions: RefCell<Vec<String>>,
+}
+
+impl ObexClient {
+    pub fn new() -> Result<ObexClient, Box<Error>> {
+        ObexClient::from_connection(open_bus_connection()?)
+    }
+
+    // Connects to obexd on the bus at `address`, e.g. "unix:path=/run/user/1000/bus"
+    // when the process has no session bus of its own.
+    pub fn with_address(address: &str) -> Result<ObexClient, Box<Error>> {
+        let c = Connection::open_private(address)?;
+        c.register()?;
+        ObexClient::from_connection(c)
+    }
+
+    fn from_connection(connection: Connection) -> Result<ObexClient, Box<Error>> {
+        connection.add_match(OBEX_MATCH)?;
+        Ok(ObexClient {
+            session: BluetoothSession::new(connection),
+            sessions: RefCell::new(Vec::new()),
+        })
+    }
+
+    // Waiting for signals goes through the session, so other messages are kept for
+    // `incoming`.
+    pub(crate) fn get_session(&self) -> &BluetoothSession {
+        &self.session
+    }
+
+    pub fn get_connection(&self) -> &Connection {
+        self.session.get_connection()
+    }
+
+    // Signals of obexd, e.g. transfer progress, and calls to exported agents.
+    pub fn incoming(&self, timeout_ms: u32) -> ConnMsgs<&Connection> {
+        self.session.incoming(timeout_ms)
+    }
+
+    // Same as `incoming`, after the messages kept while waiting for transfers.
+    pub fn incoming_queued(&self, timeout_ms: u32) -> SessionMessages {
+        self.session.incoming_queued(timeout_ms)
+    }
+
+    // Object paths of the sessions created by this client and not removed yet.
+    pub fn get_sessions(&self) -> Vec<String> {
+        self.sessions.borrow().clone()
+    }
+
+    pub(crate) fn add_session(&self, object_path: &str) {
+        self.sessions.borrow_mut().push(String::from(object_path));
+    }
+
+    pub(crate) fn forget_session(&self, object_path: &str) {
+        self.sessions.borrow_mut().retain(|s| s != object_path);
+    }
+
+    pub fn create_session(
+        &self,
+        device: &BluetoothDevice,
+        target: SessionTarget,
+        options: &ObexSessionOptions,
+    ) -> Result<BluetoothOBEXSession, Box<Error>> {
+        BluetoothOBEXSession::create(self, device, target, options)
+    }
+
+    pub fn file_transfer(
+        &self,
+        device: &BluetoothDevice,
+        options: &ObexSessionOptions,
+    ) -> Result<BluetoothOBEXFileTransfer, Box<Error>> {
+        BluetoothOBEXFileTransfer::new(self, device, options)
+    }
+
+    pub fn object_push(
+        &self,
+        device: &BluetoothDevice,
+        options: &ObexSessionOptions,
+    ) -> Result<BluetoothOBEXObjectPush, Box<Error>> {
+        BluetoothOBEXObjectPush::new(self, device, options)
+    }
+
+    pub fn phonebook_access(
+        &self,
+        device: &BluetoothDevice,
+        options: &ObexSessionOptions,
+    ) -> Result<BluetoothOBEXPhonebookAccess, Box<Error>> {
+        BluetoothOBEXPhonebookAccess::new(self, device, options)
+    }
+
+    pub fn message_access(
+        &self,
+        device: &BluetoothDevice,
+        options: &ObexSessionOptions,
+    ) -> Result<BluetoothOBEXMessageAccess, Box<Error>> {
+        BluetoothOBEXMessageAccess::new(self, device, options)
+    }
+
+    pub fn synchronization(
+        &self,
+        device: &BluetoothDevice,
+        options: &ObexSessionOptions,
+    ) -> Result<BluetoothOBEXSynchronization, Box<Error>> {
+        BluetoothOBEXSynchronization::new(self, device, options)
+    }
+
+    // Cover art of AVRCP players, see BluetoothOBEXImage.
+    pub fn image(
+        &self,
+        device: &BluetoothDevice,
+        options: &ObexSessionOptions,
+    ) -> Result<BluetoothOBEXImage, Box<Error>> {
+        BluetoothOBEXImage::new(self, device, options)
+    }
+}
@@ -0,0 +1,144 @@
+use bluetooth_obex::{BluetoothOBEXTransfer, ObexTime, SessionTarget};
+use bluetooth_utils;
+use dbus::MessageItem;
+
+use std::collections::HashMap;
+use std::error::Error;
+
+static FILE_TRANSFER_INTERFACE: &'static str = "org.bluez.obex.FileTransfer1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FolderEntryType {
+    Folder,
+    File,
+}
+
+// An entry of ListFolder, built from the folder listing object of the remote.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FolderEntry {
+    pub name: String,
+    pub entry_type: FolderEntryType,
+    // Bytes for files, number of entries for folders.
+    pub size: Option<u64>,
+    // Permission letters ("R", "W", "D") for the user, group and others.
+    pub user_permissions: Option<String>,
+    pub group_permissions: Option<String>,
+    pub other_permissions: Option<String>,
+    pub modified: Option<ObexTime>,
+    pub accessed: Option<ObexTime>,
+    pub created: Option<ObexTime>,
+}
+
+impl FolderEntry {
+    fn from_dict(dict: &HashMap<String, MessageItem>) -> Option<FolderEntry> {
+        let text = |key: &str| {
+            dict.get(key)
+                .and_then(|v| v.inner::<&str>().ok())
+                .map(String::from)
+        };
+        let time = |key: &str| text(key).and_then(|t| ObexTime::parse(&t));
+        let entry_type = match text("Type").as_ref().map(|t| t.as_str()) {
+            Some("folder") => FolderEntryType::Folder,
+            Some("file") => FolderEntryType::File,
+            _ => return None,
+        };
+        Some(FolderEntry {
+            name: text("Name")?,
+            entry_type: entry_type,
+            size: dict.get("Size").and_then(|v| v.inner::<u64>().ok()),
+            user_permissions: text("User-perm"),
+            group_permissions: text("Group-perm"),
+            other_permissions: text("Other-perm"),
+            modified: time("Modified"),
+            accessed: time("Accessed"),
+            created: time("Created"),
+        })
+    }
+}
+
+// A File Transfer (FTP) session, exposing org.bluez.obex.FileTransfer1.
+obex_session!(BluetoothOBEXFileTransfer, SessionTarget::Ftp);
+
+impl<'a> BluetoothOBEXFileTransfer<'a> {
+    fn call_method(&self, method: &str, args: &[MessageItem]) -> Result<(), Box<Error>> {
+        self.session
+            .call_method(FILE_TRANSFER_INTERFACE, method, args, 5000)?;
+        Ok(())
+    }
+
+    // `folder` is relative to the current folder, ".." goes up and "" to the root.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n264
+    pub fn change_folder(&self, folder: &str) -> Result<(), Box<Error>> {
+        self.call_method("ChangeFolder", &[folder.into()])
+    }
+
+    // Creates `folder` in the current folder and changes into it.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n271
+    pub fn create_folder(&self, folder: &str) -> Result<(), Box<Error>> {
+        self.call_method("CreateFolder", &[folder.into()])
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n278
+    pub fn list_folder(&self) -> Result<Vec<FolderEntry>, Box<Error>> {
+        let r = self
+            .session
+            .call_method(FILE_TRANSFER_INTERFACE, "ListFolder", &[], 30000)?;
+        let listing: MessageItem = match r.get1() {
+            Some(listing) => listing,
+            None => return Err(Box::from("Invalid ListFolder reply")),
+        };
+        let dicts: &[MessageItem] = listing.inner().unwrap();
+        Ok(dicts
+            .iter()
+            .filter_map(|d| FolderEntry::from_dict(&bluetooth_utils::variant_dict(d)))
+            .collect())
+    }
+
+    // Downloads `source_file` of the current folder to the local `target_file`,
+    // an empty target lets obexd pick a temporary file.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n298
+    pub fn get_file(
+        &self,
+        target_file: &str,
+        source_file: &str,
+    ) -> Result<BluetoothOBEXTransfer, Box<Error>> {
+        BluetoothOBEXTransfer::start(
+            &self.session,
+            FILE_TRANSFER_INTERFACE,
+            "GetFile",
+            &[target_file.into(), source_file.into()],
+        )
+    }
+
+    // Uploads the local `source_file` as `target_file` into the current folder.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n311
+    pub fn put_file(
+        &self,
+        source_file: &str,
+        target_file: &str,
+    ) -> Result<BluetoothOBEXTransfer, Box<Error>> {
+        BluetoothOBEXTransfer::start(
+            &self.session,
+            FILE_TRANSFER_INTERFACE,
+            "PutFile",
+            &[source_file.into(), target_file.into()],
+        )
+    }
+
+    // Copy, move and delete are executed by the remote, so unlike get_file and
+    // put_file they complete with the call and create no transfer.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n322
+    pub fn copy_file(&self, source_file: &str, target_file: &str) -> Result<(), Box<Error>> {
+        self.call_method("CopyFile", &[source_file.into(), target_file.into()])
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n330
+    pub fn move_file(&self, source_file: &str, target_file: &str) -> Result<(), Box<Error>> {
+        self.call_method("MoveFile", &[source_file.into(), target_file.into()])
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n338
+    pub fn delete(&self, file: &str) -> Result<(), Box<Error>> {
+        self.call_method("Delete", &[file.into()])
+    }
+}
@@ -0,0 +1,189 @@
+use bluetooth_obex::{BluetoothOBEXTransfer, SessionTarget};
+use bluetooth_utils;
+use dbus::MessageItem;
+
+use std::collections::HashMap;
+use std::error::Error;
+
+static IMAGE_INTERFACE: &'static str = "org.bluez.obex.Image1";
+
+// Selects the variant of an image to get, an empty description gets the native one.
+// https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n905
+#[derive(Clone, Debug, Default)]
+pub struct ImageDescription {
+    encoding: Option<String>,
+    pixel: Option<String>,
+    transform: Option<String>,
+}
+
+impl ImageDescription {
+    pub fn new() -> ImageDescription {
+        ImageDescription::default()
+    }
+
+    // e.g. "JPEG" or "PNG".
+    pub fn encoding(mut self, encoding: &str) -> ImageDescription {
+        self.encoding = Some(String::from(encoding));
+        self
+    }
+
+    // Size as "<width>*<height>", e.g. "200*200".
+    pub fn pixel(mut self, pixel: &str) -> ImageDescription {
+        self.pixel = Some(String::from(pixel));
+        self
+    }
+
+    // One of the transformations listed by the format, e.g. "stretch" or "crop".
+    pub fn transform(mut self, transform: &str) -> ImageDescription {
+        self.transform = Some(String::from(transform));
+        self
+    }
+
+    fn to_message_item(&self) -> MessageItem {
+        let mut description: Vec<(&str, MessageItem)> = Vec::new();
+        if let Some(ref encoding) = self.encoding {
+            description.push(("encoding", encoding.as_str().into()));
+        }
+        if let Some(ref pixel) = self.pixel {
+            description.push(("pixel", pixel.as_str().into()));
+        }
+        if let Some(ref transform) = self.transform {
+            description.push(("transform", transform.as_str().into()));
+        }
+        bluetooth_utils::properties_dict(description)
+    }
+}
+
+// The native format of an image or one of the variants the remote can convert to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageFormat {
+    pub native: bool,
+    pub encoding: Option<String>,
+    pub pixel: Option<String>,
+    // Bytes of the native image.
+    pub size: Option<u64>,
+    // Upper bound of the bytes of a variant.
+    pub max_size: Option<u64>,
+    // Supported transformations, space separated.
+    pub transform: Option<String>,
+}
+
+impl ImageFormat {
+    // Description getting the image in this format.
+    pub fn description(&self) -> ImageDescription {
+        ImageDescription {
+            encoding: self.encoding.clone(),
+            pixel: self.pixel.clone(),
+            transform: self
+                .transform
+                .as_ref()
+                .and_then(|t| t.split_whitespace().next())
+                .map(String::from),
+        }
+    }
+
+    fn from_dict(dict: &HashMap<String, MessageItem>) -> ImageFormat {
+        let text = |key: &str| {
+            dict.get(key)
+                .and_then(|v| v.inner::<&str>().ok())
+                .map(String::from)
+        };
+        // Sizes are numbers or decimal strings depending on the obexd version.
+        let number = |key: &str| {
+            dict.get(key).and_then(|v| {
+                v.inner::<u64>()
+                    .ok()
+                    .or_else(|| v.inner::<u32>().ok().map(u64::from))
+                    .or_else(|| v.inner::<&str>().ok().and_then(|s| s.parse().ok()))
+            })
+        };
+        ImageFormat {
+            native: text("type").map_or(false, |t| t == "native"),
+            encoding: text("encoding"),
+            pixel: text("pixel"),
+            size: number("size"),
+            max_size: number("maxsize"),
+            transform: text("transform"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageProperties {
+    pub handle: String,
+    pub name: Option<String>,
+    // The native format first, followed by the variants.
+    pub formats: Vec<ImageFormat>,
+}
+
+// A Basic Imaging Profile session, exposing org.bluez.obex.Image1. For the cover
+// art of AVRCP players `options` needs the PSM of BluetoothMediaPlayer::get_obex_port
+// and images are addressed by MediaTrack::image_handle.
+obex_session!(BluetoothOBEXImage, SessionTarget::BipAvrcp);
+
+impl<'a> BluetoothOBEXImage<'a> {
+    // Downloads the image `handle` in the format of `description` to the local
+    // `target_file`.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n905
+    pub fn get(
+        &self,
+        target_file: &str,
+        handle: &str,
+        description: &ImageDescription,
+    ) -> Result<BluetoothOBEXTransfer, Box<Error>> {
+        BluetoothOBEXTransfer::start(
+            &self.session,
+            IMAGE_INTERFACE,
+            "Get",
+            &[
+                target_file.into(),
+                handle.into(),
+                description.to_message_item(),
+            ],
+        )
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n920
+    pub fn get_properties(&self, handle: &str) -> Result<ImageProperties, Box<Error>> {
+        let r = self
+            .session
+            .call_method(IMAGE_INTERFACE, "Properties", &[handle.into()], 10000)?;
+        let dicts: MessageItem = match r.get1() {
+            Some(dicts) => dicts,
+            None => return Err(Box::from("Invalid Properties reply")),
+        };
+        let dicts: &[MessageItem] = dicts.inner().map_err(|_| "Invalid Properties reply")?;
+        let mut dicts = dicts.iter().map(bluetooth_utils::variant_dict);
+        // The first dict names the image, the others describe its formats.
+        let image = match dicts.next() {
+            Some(image) => image,
+            None => return Err(Box::from("Invalid Properties reply")),
+        };
+        let text = |key: &str| {
+            image
+                .get(key)
+                .and_then(|v| v.inner::<&str>().ok())
+                .map(String::from)
+        };
+        Ok(ImageProperties {
+            handle: text("handle").unwrap_or_else(|| String::from(handle)),
+            name: text("name"),
+            formats: dicts.map(|d| ImageFormat::from_dict(&d)).collect(),
+        })
+    }
+
+    // Downloads the 200x200 JPEG thumbnail of the image `handle`.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n934
+    pub fn get_thumbnail(
+        &self,
+        target_file: &str,
+        handle: &str,
+    ) -> Result<BluetoothOBEXTransfer, Box<Error>> {
+        BluetoothOBEXTransfer::start(
+            &self.session,
+            IMAGE_INTERFACE,
+            "GetThumbnail",
+            &[target_file.into(), handle.into()],
+        )
+    }
+}
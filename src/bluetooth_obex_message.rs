@@ -0,0 +1,485 @@
+use bluetooth_obex::{BluetoothOBEXSession, BluetoothOBEXTransfer, ObexTime, SessionTarget};
+use bluetooth_utils;
+use dbus::{MessageItem, MessageItemArray, Props, Signature};
+
+// Shared with the bMessage format, re-exported as part of the MAP client.
+pub use formats::bmessage::MessageType;
+
+use std::error::Error;
+
+static OBEX_BUS: &'static str = "org.bluez.obex";
+static MESSAGE_ACCESS_INTERFACE: &'static str = "org.bluez.obex.MessageAccess1";
+static MESSAGE_INTERFACE: &'static str = "org.bluez.obex.Message1";
+
+// Reception status, only complete messages carry their whole body.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageStatus {
+    Complete,
+    Fractioned,
+    Notification,
+}
+
+impl MessageStatus {
+    pub fn from_name(name: &str) -> Option<MessageStatus> {
+        match name {
+            "complete" => Some(MessageStatus::Complete),
+            "fractioned" => Some(MessageStatus::Fractioned),
+            "notification" => Some(MessageStatus::Notification),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            MessageStatus::Complete => "complete",
+            MessageStatus::Fractioned => "fractioned",
+            MessageStatus::Notification => "notification",
+        }
+    }
+}
+
+// Filter of ListFolders and ListMessages, unset entries are left to the remote.
+// Only the offset and maximum count apply to ListFolders.
+// https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n657
+#[derive(Clone, Debug, Default)]
+pub struct MessageFilter {
+    offset: Option<u16>,
+    max_count: Option<u16>,
+    subject_length: Option<u8>,
+    fields: Option<Vec<String>>,
+    types: Option<Vec<String>>,
+    period_begin: Option<ObexTime>,
+    period_end: Option<ObexTime>,
+    read: Option<bool>,
+    recipient: Option<String>,
+    sender: Option<String>,
+    priority: Option<bool>,
+}
+
+impl MessageFilter {
+    pub fn new() -> MessageFilter {
+        MessageFilter::default()
+    }
+
+    pub fn offset(mut self, offset: u16) -> MessageFilter {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub fn max_count(mut self, max_count: u16) -> MessageFilter {
+        self.max_count = Some(max_count);
+        self
+    }
+
+    // Number of characters of the subject to list.
+    pub fn subject_length(mut self, subject_length: u8) -> MessageFilter {
+        self.subject_length = Some(subject_length);
+        self
+    }
+
+    // Message properties to list, as returned by list_filter_fields.
+    pub fn fields(mut self, fields: Vec<String>) -> MessageFilter {
+        self.fields = Some(fields);
+        self
+    }
+
+    // Message types to list: "sms", "email" or "mms".
+    pub fn types(mut self, types: Vec<String>) -> MessageFilter {
+        self.types = Some(types);
+        self
+    }
+
+    pub fn period_begin(mut self, period_begin: ObexTime) -> MessageFilter {
+        self.period_begin = Some(period_begin);
+        self
+    }
+
+    pub fn period_end(mut self, period_end: ObexTime) -> MessageFilter {
+        self.period_end = Some(period_end);
+        self
+    }
+
+    pub fn read(mut self, read: bool) -> MessageFilter {
+        self.read = Some(read);
+        self
+    }
+
+    pub fn recipient(mut self, recipient: &str) -> MessageFilter {
+        self.recipient = Some(String::from(recipient));
+        self
+    }
+
+    pub fn sender(mut self, sender: &str) -> MessageFilter {
+        self.sender = Some(String::from(sender));
+        self
+    }
+
+    pub fn priority(mut self, priority: bool) -> MessageFilter {
+        self.priority = Some(priority);
+        self
+    }
+
+    fn to_message_item(&self) -> MessageItem {
+        let strings = |values: &Vec<String>| {
+            let values = values
+                .iter()
+                .map(|v| MessageItem::from(v.as_str()))
+                .collect();
+            MessageItem::Array(MessageItemArray::new(values, Signature::from("as")).unwrap())
+        };
+        let mut filter: Vec<(&str, MessageItem)> = Vec::new();
+        if let Some(offset) = self.offset {
+            filter.push(("Offset", offset.into()));
+        }
+        if let Some(max_count) = self.max_count {
+            filter.push(("MaxCount", max_count.into()));
+        }
+        if let Some(subject_length) = self.subject_length {
+            filter.push(("SubjectLength", subject_length.into()));
+        }
+        if let Some(ref fields) = self.fields {
+            filter.push(("Fields", strings(fields)));
+        }
+        if let Some(ref types) = self.types {
+            filter.push(("Types", strings(types)));
+        }
+        if let Some(period_begin) = self.period_begin {
+            filter.push(("PeriodBegin", period_begin.to_string().into()));
+        }
+        if let Some(period_end) = self.period_end {
+            filter.push(("PeriodEnd", period_end.to_string().into()));
+        }
+        if let Some(read) = self.read {
+            filter.push(("Read", read.into()));
+        }
+        if let Some(ref recipient) = self.recipient {
+            filter.push(("Recipient", recipient.as_str().into()));
+        }
+        if let Some(ref sender) = self.sender {
+            filter.push(("Sender", sender.as_str().into()));
+        }
+        if let Some(priority) = self.priority {
+            filter.push(("Priority", priority.into()));
+        }
+        bluetooth_utils::properties_dict(filter)
+    }
+}
+
+// Options of PushMessage.
+// https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n760
+#[derive(Clone, Debug, Default)]
+pub struct PushMessageOptions {
+    transparent: Option<bool>,
+    retry: Option<bool>,
+    native_charset: Option<bool>,
+}
+
+impl PushMessageOptions {
+    pub fn new() -> PushMessageOptions {
+        PushMessageOptions::default()
+    }
+
+    // Don't keep a copy in the sent folder of the remote.
+    pub fn transparent(mut self, transparent: bool) -> PushMessageOptions {
+        self.transparent = Some(transparent);
+        self
+    }
+
+    // Let the remote retry sending if the network is unavailable.
+    pub fn retry(mut self, retry: bool) -> PushMessageOptions {
+        self.retry = Some(retry);
+        self
+    }
+
+    // The body is in the native format of the message type, e.g. an SMS PDU,
+    // instead of UTF-8.
+    pub fn native_charset(mut self, native_charset: bool) -> PushMessageOptions {
+        self.native_charset = Some(native_charset);
+        self
+    }
+
+    fn to_message_item(&self) -> MessageItem {
+        let mut args: Vec<(&str, MessageItem)> = Vec::new();
+        if let Some(transparent) = self.transparent {
+            args.push(("Transparent", transparent.into()));
+        }
+        if let Some(retry) = self.retry {
+            args.push(("Retry", retry.into()));
+        }
+        if let Some(native_charset) = self.native_charset {
+            args.push((
+                "Charset",
+                (if native_charset { "native" } else { "utf8" }).into(),
+            ));
+        }
+        bluetooth_utils::properties_dict(args)
+    }
+}
+
+// A Message Access (MAP) session, exposing org.bluez.obex.MessageAccess1.
+obex_session!(BluetoothOBEXMessageAccess, SessionTarget::Map);
+
+impl<'a> BluetoothOBEXMessageAccess<'a> {
+    fn call_method(&self, method: &str, args: &[MessageItem]) -> Result<(), Box<Error>> {
+        self.session
+            .call_method(MESSAGE_ACCESS_INTERFACE, method, args, 5000)?;
+        Ok(())
+    }
+
+    fn call_method_with_reply(
+        &self,
+        method: &str,
+        args: &[MessageItem],
+    ) -> Result<MessageItem, Box<Error>> {
+        let r = self
+            .session
+            .call_method(MESSAGE_ACCESS_INTERFACE, method, args, 30000)?;
+        match r.get1() {
+            Some(reply) => Ok(reply),
+            None => Err(Box::from(format!("Invalid {} reply", method))),
+        }
+    }
+
+    // `folder` is relative to the current folder, ".." goes up and "" to the root.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n617
+    pub fn set_folder(&self, folder: &str) -> Result<(), Box<Error>> {
+        self.call_method("SetFolder", &[folder.into()])
+    }
+
+    // Names of the subfolders of the current folder.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n626
+    pub fn list_folders(&self, filter: &MessageFilter) -> Result<Vec<String>, Box<Error>> {
+        let folders = self.call_method_with_reply("ListFolders", &[filter.to_message_item()])?;
+        let folders: &[MessageItem] = folders.inner().unwrap();
+        Ok(folders
+            .iter()
+            .filter_map(|f| {
+                bluetooth_utils::variant_dict(f)
+                    .get("Name")
+                    .and_then(|n| n.inner::<&str>().ok())
+                    .map(String::from)
+            })
+            .collect())
+    }
+
+    // Fields supported by MessageFilter::fields.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n646
+    pub fn list_filter_fields(&self) -> Result<Vec<String>, Box<Error>> {
+        let r =
+            self.session
+                .call_method(MESSAGE_ACCESS_INTERFACE, "ListFilterFields", &[], 5000)?;
+        let fields: Vec<&str> = r.read1()?;
+        Ok(fields.into_iter().map(String::from).collect())
+    }
+
+    // Messages of `folder`, relative to the current folder.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n652
+    pub fn list_messages(
+        &self,
+        folder: &str,
+        filter: &MessageFilter,
+    ) -> Result<Vec<BluetoothOBEXMessage>, Box<Error>> {
+        let messages = self
+            .call_method_with_reply("ListMessages", &[folder.into(), filter.to_message_item()])?;
+        let messages: &[MessageItem] = messages.inner().unwrap();
+        Ok(messages
+            .iter()
+            .filter_map(|m| match *m {
+                MessageItem::DictEntry(ref path, _) => match **path {
+                    MessageItem::ObjectPath(ref path) => {
+                        Some(BluetoothOBEXMessage::new(&self.session, path.to_string()))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect())
+    }
+
+    // Asks the remote to check for new messages on its server.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n754
+    pub fn update_inbox(&self) -> Result<(), Box<Error>> {
+        self.call_method("UpdateInbox", &[])
+    }
+
+    // Sends the bMessage in the local `source_file` through `folder`, usually
+    // "telecom/msg/outbox". See formats::bmessage::BMessage::write_file.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n760
+    pub fn push_message(
+        &self,
+        source_file: &str,
+        folder: &str,
+        options: &PushMessageOptions,
+    ) -> Result<BluetoothOBEXTransfer, Box<Error>> {
+        BluetoothOBEXTransfer::start(
+            &self.session,
+            MESSAGE_ACCESS_INTERFACE,
+            "PushMessage",
+            &[source_file.into(), folder.into(), options.to_message_item()],
+        )
+    }
+}
+
+// A message listed by BluetoothOBEXMessageAccess::list_messages, exposing
+// org.bluez.obex.Message1.
+pub struct BluetoothOBEXMessage<'a> {
+    session: &'a BluetoothOBEXSession<'a>,
+    object_path: String,
+}
+
+impl<'a> BluetoothOBEXMessage<'a> {
+    pub fn new(session: &'a BluetoothOBEXSession, object_path: String) -> BluetoothOBEXMessage<'a> {
+        BluetoothOBEXMessage {
+            session: session,
+            object_path: object_path,
+        }
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    fn props(&self) -> Props {
+        Props::new(
+            self.session.get_connection(),
+            OBEX_BUS,
+            &self.object_path,
+            MESSAGE_INTERFACE,
+            1000,
+        )
+    }
+
+    fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
+        Ok(self.props().get(prop)?)
+    }
+
+    fn get_string(&self, prop: &str) -> Result<String, Box<Error>> {
+        let value = self.get_property(prop)?;
+        Ok(String::from(value.inner::<&str>().unwrap()))
+    }
+
+    /*
+     * Properties
+     */
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n820
+    pub fn get_folder(&self) -> Result<String, Box<Error>> {
+        self.get_string("Folder")
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n824
+    pub fn get_subject(&self) -> Result<String, Box<Error>> {
+        self.get_string("Subject")
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n828
+    pub fn get_timestamp(&self) -> Result<ObexTime, Box<Error>> {
+        let timestamp = self.get_string("Timestamp")?;
+        match ObexTime::parse(&timestamp) {
+            Some(timestamp) => Ok(timestamp),
+            None => Err(Box::from(format!("Invalid timestamp: {}", timestamp))),
+        }
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n832
+    pub fn get_sender(&self) -> Result<String, Box<Error>> {
+        self.get_string("Sender")
+    }
+
+    // Phone number or email address of the sender.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n836
+    pub fn get_sender_address(&self) -> Result<String, Box<Error>> {
+        self.get_string("SenderAddress")
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n844
+    pub fn get_recipient(&self) -> Result<String, Box<Error>> {
+        self.get_string("Recipient")
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n848
+    pub fn get_recipient_address(&self) -> Result<String, Box<Error>> {
+        self.get_string("RecipientAddress")
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n852
+    pub fn get_type(&self) -> Result<MessageType, Box<Error>> {
+        let message_type = self.get_string("Type")?;
+        match MessageType::from_name(&message_type) {
+            Some(message_type) => Ok(message_type),
+            None => Err(Box::from(format!("Unknown message type: {}", message_type))),
+        }
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n857
+    pub fn get_size(&self) -> Result<u64, Box<Error>> {
+        let size = self.get_property("Size")?;
+        Ok(size.inner::<u64>().unwrap())
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n861
+    pub fn get_status(&self) -> Result<MessageStatus, Box<Error>> {
+        let status = self.get_string("Status")?;
+        match MessageStatus::from_name(&status) {
+            Some(status) => Ok(status),
+            None => Err(Box::from(format!("Unknown message status: {}", status))),
+        }
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n866
+    pub fn is_priority(&self) -> Result<bool, Box<Error>> {
+        let priority = self.get_property("Priority")?;
+        Ok(priority.inner::<bool>().unwrap())
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n870
+    pub fn is_read(&self) -> Result<bool, Box<Error>> {
+        let read = self.get_property("Read")?;
+        Ok(read.inner::<bool>().unwrap())
+    }
+
+    // Marks the message as read or unread on the remote.
+    pub fn set_read(&self, read: bool) -> Result<(), Box<Error>> {
+        Ok(self.props().set("Read", read.into())?)
+    }
+
+    // Moves the message to or restores it from the deleted folder of the remote.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n874
+    pub fn set_deleted(&self, deleted: bool) -> Result<(), Box<Error>> {
+        Ok(self.props().set("Deleted", deleted.into())?)
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n878
+    pub fn is_sent(&self) -> Result<bool, Box<Error>> {
+        let sent = self.get_property("Sent")?;
+        Ok(sent.inner::<bool>().unwrap())
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n882
+    pub fn is_protected(&self) -> Result<bool, Box<Error>> {
+        let protected = self.get_property("Protected")?;
+        Ok(protected.inner::<bool>().unwrap())
+    }
+
+    /*
+     * Methods
+     */
+
+    // Downloads the message as a bMessage to the local `target_file`, an empty
+    // target lets obexd pick a temporary file. See formats::bmessage::BMessage::read_file.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n805
+    pub fn get(
+        &self,
+        target_file: &str,
+        attachment: bool,
+    ) -> Result<BluetoothOBEXTransfer<'a>, Box<Error>> {
+        let r = self.session.call_object_method(
+            &self.object_path,
+            MESSAGE_INTERFACE,
+            "Get",
+            &[target_file.into(), attachment.into()],
+            1000,
+        )?;
+        BluetoothOBEXTransfer::from_reply(self.session, &r)
+    }
+}
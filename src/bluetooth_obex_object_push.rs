@@ -0,0 +1,45 @@
+use bluetooth_obex::{BluetoothOBEXTransfer, SessionTarget};
+
+use std::error::Error;
+
+static OBJECT_PUSH_INTERFACE: &'static str = "org.bluez.obex.ObjectPush1";
+
+// An Object Push (OPP) session, exposing org.bluez.obex.ObjectPush1.
+obex_session!(BluetoothOBEXObjectPush, SessionTarget::Opp);
+
+impl<'a> BluetoothOBEXObjectPush<'a> {
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n169
+    pub fn send_file(&self, file_path: &str) -> Result<BluetoothOBEXTransfer, Box<Error>> {
+        BluetoothOBEXTransfer::send_file(&self.session, file_path)
+    }
+
+    // Downloads the default business card of the remote to the local `target_file`.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n184
+    pub fn pull_business_card(
+        &self,
+        target_file: &str,
+    ) -> Result<BluetoothOBEXTransfer, Box<Error>> {
+        BluetoothOBEXTransfer::start(
+            &self.session,
+            OBJECT_PUSH_INTERFACE,
+            "PullBusinessCard",
+            &[target_file.into()],
+        )
+    }
+
+    // Sends the local card in `client_file` and stores the remote's in `target_file`.
+    // The local card can be written with VCardBuilder and VCard::write_file.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n193
+    pub fn exchange_business_cards(
+        &self,
+        client_file: &str,
+        target_file: &str,
+    ) -> Result<BluetoothOBEXTransfer, Box<Error>> {
+        BluetoothOBEXTransfer::start(
+            &self.session,
+            OBJECT_PUSH_INTERFACE,
+            "ExchangeBusinessCards",
+            &[client_file.into(), target_file.into()],
+        )
+    }
+}
@@ -0,0 +1,403 @@
+use bluetooth_obex::{BluetoothOBEXTransfer, SessionTarget};
+use bluetooth_utils;
+use dbus::{MessageItem, MessageItemArray, Props, Signature};
+
+use std::error::Error;
+
+static OBEX_BUS: &'static str = "org.bluez.obex";
+static PHONEBOOK_ACCESS_INTERFACE: &'static str = "org.bluez.obex.PhonebookAccess1";
+
+// Repository holding the phonebooks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhonebookRepository {
+    Internal,
+    Sim1,
+}
+
+impl PhonebookRepository {
+    pub fn from_name(name: &str) -> Option<PhonebookRepository> {
+        match name {
+            "int" | "internal" => Some(PhonebookRepository::Internal),
+            "sim1" => Some(PhonebookRepository::Sim1),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            PhonebookRepository::Internal => "int",
+            PhonebookRepository::Sim1 => "sim1",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phonebook {
+    Contacts,
+    IncomingCalls,
+    OutgoingCalls,
+    MissedCalls,
+    // Incoming, outgoing and missed calls.
+    CombinedCalls,
+    SpeedDial,
+    Favorites,
+}
+
+impl Phonebook {
+    pub fn from_name(name: &str) -> Option<Phonebook> {
+        match name {
+            "pb" => Some(Phonebook::Contacts),
+            "ich" => Some(Phonebook::IncomingCalls),
+            "och" => Some(Phonebook::OutgoingCalls),
+            "mch" => Some(Phonebook::MissedCalls),
+            "cch" => Some(Phonebook::CombinedCalls),
+            "spd" => Some(Phonebook::SpeedDial),
+            "fav" => Some(Phonebook::Favorites),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Phonebook::Contacts => "pb",
+            Phonebook::IncomingCalls => "ich",
+            Phonebook::OutgoingCalls => "och",
+            Phonebook::MissedCalls => "mch",
+            Phonebook::CombinedCalls => "cch",
+            Phonebook::SpeedDial => "spd",
+            Phonebook::Favorites => "fav",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VCardFormat {
+    VCard21,
+    VCard30,
+}
+
+impl VCardFormat {
+    pub fn from_name(name: &str) -> Option<VCardFormat> {
+        match name {
+            "vcard21" => Some(VCardFormat::VCard21),
+            "vcard30" => Some(VCardFormat::VCard30),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            VCardFormat::VCard21 => "vcard21",
+            VCardFormat::VCard30 => "vcard30",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhonebookOrder {
+    Indexed,
+    Alphanumeric,
+    Phonetic,
+}
+
+impl PhonebookOrder {
+    pub fn from_name(name: &str) -> Option<PhonebookOrder> {
+        match name {
+            "indexed" => Some(PhonebookOrder::Indexed),
+            "alphanumeric" => Some(PhonebookOrder::Alphanumeric),
+            "phonetic" => Some(PhonebookOrder::Phonetic),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            PhonebookOrder::Indexed => "indexed",
+            PhonebookOrder::Alphanumeric => "alphanumeric",
+            PhonebookOrder::Phonetic => "phonetic",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchField {
+    Name,
+    Number,
+    Sound,
+}
+
+impl SearchField {
+    pub fn from_name(name: &str) -> Option<SearchField> {
+        match name {
+            "name" => Some(SearchField::Name),
+            "number" => Some(SearchField::Number),
+            "sound" => Some(SearchField::Sound),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            SearchField::Name => "name",
+            SearchField::Number => "number",
+            SearchField::Sound => "sound",
+        }
+    }
+}
+
+// Filter of PullAll, Pull, List and Search, unset entries are left to the remote.
+// https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n520
+#[derive(Clone, Debug, Default)]
+pub struct PhonebookFilter {
+    format: Option<VCardFormat>,
+    order: Option<PhonebookOrder>,
+    offset: Option<u16>,
+    max_count: Option<u16>,
+    fields: Option<Vec<String>>,
+}
+
+impl PhonebookFilter {
+    pub fn new() -> PhonebookFilter {
+        PhonebookFilter::default()
+    }
+
+    pub fn format(mut self, format: VCardFormat) -> PhonebookFilter {
+        self.format = Some(format);
+        self
+    }
+
+    // Order of List and Search results.
+    pub fn order(mut self, order: PhonebookOrder) -> PhonebookFilter {
+        self.order = Some(order);
+        self
+    }
+
+    pub fn offset(mut self, offset: u16) -> PhonebookFilter {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub fn max_count(mut self, max_count: u16) -> PhonebookFilter {
+        self.max_count = Some(max_count);
+        self
+    }
+
+    // vCard fields to include, as returned by list_filter_fields, e.g. "TEL".
+    pub fn fields(mut self, fields: Vec<String>) -> PhonebookFilter {
+        self.fields = Some(fields);
+        self
+    }
+
+    fn to_message_item(&self) -> MessageItem {
+        let mut filter: Vec<(&str, MessageItem)> = Vec::new();
+        if let Some(format) = self.format {
+            filter.push(("Format", format.name().into()));
+        }
+        if let Some(order) = self.order {
+            filter.push(("Order", order.name().into()));
+        }
+        if let Some(offset) = self.offset {
+            filter.push(("Offset", offset.into()));
+        }
+        if let Some(max_count) = self.max_count {
+            filter.push(("MaxCount", max_count.into()));
+        }
+        if let Some(ref fields) = self.fields {
+            let fields = fields
+                .iter()
+                .map(|f| MessageItem::from(f.as_str()))
+                .collect();
+            filter.push((
+                "Fields",
+                MessageItem::Array(MessageItemArray::new(fields, Signature::from("as")).unwrap()),
+            ));
+        }
+        bluetooth_utils::properties_dict(filter)
+    }
+}
+
+// An entry of List and Search, `handle` is the vCard name to pass to pull,
+// e.g. "1.vcf".
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhonebookEntry {
+    pub handle: String,
+    pub name: String,
+}
+
+// A Phonebook Access (PBAP) session, exposing org.bluez.obex.PhonebookAccess1.
+obex_session!(BluetoothOBEXPhonebookAccess, SessionTarget::Pbap);
+
+impl<'a> BluetoothOBEXPhonebookAccess<'a> {
+    fn get_property(&self, prop: &str) -> Result<MessageItem, Box<Error>> {
+        let object_path = self.session.get_id();
+        let p = Props::new(
+            self.session.get_connection(),
+            OBEX_BUS,
+            &object_path,
+            PHONEBOOK_ACCESS_INTERFACE,
+            1000,
+        );
+        Ok(p.get(prop)?)
+    }
+
+    fn list_entries(
+        &self,
+        method: &str,
+        args: &[MessageItem],
+    ) -> Result<Vec<PhonebookEntry>, Box<Error>> {
+        let r = self
+            .session
+            .call_method(PHONEBOOK_ACCESS_INTERFACE, method, args, 30000)?;
+        let entries: MessageItem = match r.get1() {
+            Some(entries) => entries,
+            None => return Err(Box::from(format!("Invalid {} reply", method))),
+        };
+        let entries: &[MessageItem] = entries.inner().unwrap();
+        Ok(entries
+            .iter()
+            .filter_map(|e| match *e {
+                MessageItem::Struct(ref fields) if fields.len() == 2 => Some(PhonebookEntry {
+                    handle: String::from(fields[0].inner::<&str>().ok()?),
+                    name: String::from(fields[1].inner::<&str>().ok()?),
+                }),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /*
+     * Properties
+     */
+
+    // Current folder, e.g. "/telecom/pb".
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n566
+    pub fn get_folder(&self) -> Result<String, Box<Error>> {
+        let folder = self.get_property("Folder")?;
+        Ok(String::from(folder.inner::<&str>().unwrap()))
+    }
+
+    // Changes when the remote resets its phonebook database, cached handles are
+    // then stale.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n570
+    pub fn get_database_identifier(&self) -> Result<String, Box<Error>> {
+        let identifier = self.get_property("DatabaseIdentifier")?;
+        Ok(String::from(identifier.inner::<&str>().unwrap()))
+    }
+
+    // Folder version counters of the selected phonebook, the primary counter changes
+    // on any change of a vCard and the secondary one only on changes of the name,
+    // telephone and email fields. Refreshed by update_version.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n575
+    pub fn get_primary_counter(&self) -> Result<String, Box<Error>> {
+        let counter = self.get_property("PrimaryCounter")?;
+        Ok(String::from(counter.inner::<&str>().unwrap()))
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n580
+    pub fn get_secondary_counter(&self) -> Result<String, Box<Error>> {
+        let counter = self.get_property("SecondaryCounter")?;
+        Ok(String::from(counter.inner::<&str>().unwrap()))
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n585
+    pub fn has_fixed_image_size(&self) -> Result<bool, Box<Error>> {
+        let fixed = self.get_property("FixedImageSize")?;
+        Ok(fixed.inner::<bool>().unwrap())
+    }
+
+    /*
+     * Methods
+     */
+
+    // Selects the phonebook the other methods work on.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n420
+    pub fn select(
+        &self,
+        repository: PhonebookRepository,
+        phonebook: Phonebook,
+    ) -> Result<(), Box<Error>> {
+        self.session.call_method(
+            PHONEBOOK_ACCESS_INTERFACE,
+            "Select",
+            &[repository.name().into(), phonebook.name().into()],
+            5000,
+        )?;
+        Ok(())
+    }
+
+    // Downloads the whole phonebook to the local `target_file`, an empty target
+    // lets obexd pick a temporary file. The result is read with formats::vcard::read_file.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n443
+    pub fn pull_all(
+        &self,
+        target_file: &str,
+        filter: &PhonebookFilter,
+    ) -> Result<BluetoothOBEXTransfer, Box<Error>> {
+        BluetoothOBEXTransfer::start(
+            &self.session,
+            PHONEBOOK_ACCESS_INTERFACE,
+            "PullAll",
+            &[target_file.into(), filter.to_message_item()],
+        )
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n476
+    pub fn list(&self, filter: &PhonebookFilter) -> Result<Vec<PhonebookEntry>, Box<Error>> {
+        self.list_entries("List", &[filter.to_message_item()])
+    }
+
+    // Downloads the vCard `handle` of the selected phonebook to `target_file`.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n491
+    pub fn pull(
+        &self,
+        handle: &str,
+        target_file: &str,
+        filter: &PhonebookFilter,
+    ) -> Result<BluetoothOBEXTransfer, Box<Error>> {
+        BluetoothOBEXTransfer::start(
+            &self.session,
+            PHONEBOOK_ACCESS_INTERFACE,
+            "Pull",
+            &[handle.into(), target_file.into(), filter.to_message_item()],
+        )
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n512
+    pub fn search(
+        &self,
+        field: SearchField,
+        value: &str,
+        filter: &PhonebookFilter,
+    ) -> Result<Vec<PhonebookEntry>, Box<Error>> {
+        self.list_entries(
+            "Search",
+            &[field.name().into(), value.into(), filter.to_message_item()],
+        )
+    }
+
+    // Number of entries of the selected phonebook.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n531
+    pub fn get_size(&self) -> Result<u16, Box<Error>> {
+        let r = self
+            .session
+            .call_method(PHONEBOOK_ACCESS_INTERFACE, "GetSize", &[], 5000)?;
+        Ok(r.read1::<u16>()?)
+    }
+
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n540
+    pub fn update_version(&self) -> Result<(), Box<Error>> {
+        self.session
+            .call_method(PHONEBOOK_ACCESS_INTERFACE, "UpdateVersion", &[], 5000)?;
+        Ok(())
+    }
+
+    // vCard fields supported by PhonebookFilter::fields.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n547
+    pub fn list_filter_fields(&self) -> Result<Vec<String>, Box<Error>> {
+        let r =
+            self.session
+                .call_method(PHONEBOOK_ACCESS_INTERFACE, "ListFilterFields", &[], 5000)?;
+        let fields: Vec<&str> = r.read1()?;
+        Ok(fields.into_iter().map(String::from).collect())
+    }
+}
@@ -0,0 +1,46 @@
+use bluetooth_obex::{BluetoothOBEXTransfer, SessionTarget};
+use bluetooth_obex_phonebook::PhonebookRepository;
+
+use std::error::Error;
+
+static SYNCHRONIZATION_INTERFACE: &'static str = "org.bluez.obex.Synchronization1";
+
+// A Synchronization (IrMC) session, exposing org.bluez.obex.Synchronization1.
+obex_session!(BluetoothOBEXSynchronization, SessionTarget::Sync_);
+
+impl<'a> BluetoothOBEXSynchronization<'a> {
+    // Selects the phonebook repository of get_phonebook and put_phonebook.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n590
+    pub fn set_location(&self, location: PhonebookRepository) -> Result<(), Box<Error>> {
+        self.session.call_method(
+            SYNCHRONIZATION_INTERFACE,
+            "SetLocation",
+            &[location.name().into()],
+            5000,
+        )?;
+        Ok(())
+    }
+
+    // Downloads the whole phonebook to the local `target_file`, an empty target
+    // lets obexd pick a temporary file. The result is read with formats::vcard::read_file.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n598
+    pub fn get_phonebook(&self, target_file: &str) -> Result<BluetoothOBEXTransfer, Box<Error>> {
+        BluetoothOBEXTransfer::start(
+            &self.session,
+            SYNCHRONIZATION_INTERFACE,
+            "GetPhonebook",
+            &[target_file.into()],
+        )
+    }
+
+    // Replaces the remote phonebook with the vCards of the local `source_file`.
+    // https://git.kernel.org/pub/scm/bluetooth/bluez.git/tree/doc/obex-api.txt#n609
+    pub fn put_phonebook(&self, source_file: &str) -> Result<BluetoothOBEXTransfer, Box<Error>> {
+        BluetoothOBEXTransfer::start(
+            &self.session,
+            SYNCHRONIZATION_INTERFACE,
+            "PutPhonebook",
+            &[source_file.into()],
+        )
+    }
+}
@@ -0,0 +1,421 @@
+use bluetooth_session::BluetoothSession;
+use bluetooth_utils;
+use dbus::{Message, MessageItem, MessageType, MsgHandler, MsgHandlerResult, MsgHandlerType, Path};
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::rc::Rc;
+
+static PROFILE_MANAGER_INTERFACE: &'static str = "org.bluez.ProfileManager1";
+static PROFILE_MANAGER_PATH: &'static str = "/org/bluez";
+static PROFILE_INTERFACE: &'static str = "org.bluez.Profile1";
+static INVALID_ARGUMENTS_ERROR: &'static str = "org.bluez.Error.InvalidArguments";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileRole {
+    Client,
+    Server,
+}
+
+impl ProfileRole {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            ProfileRole::Client => "client",
+            ProfileRole::Server => "server",
+        }
+    }
+}
+
+// Options of RegisterProfile, unset ones are left to BlueZ's defaults for the UUID.
+// http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/profile-api.txt#n22
+#[derive(Clone, Debug, Default)]
+pub struct ProfileOptions {
+    name: Option<String>,
+    service: Option<String>,
+    role: Option<ProfileRole>,
+    channel: Option<u16>,
+    psm: Option<u16>,
+    require_authentication: Option<bool>,
+    require_authorization: Option<bool>,
+    auto_connect: Option<bool>,
+    service_record: Option<String>,
+    version: Option<u16>,
+    features: Option<u16>,
+}
+
+impl ProfileOptions {
+    pub fn new() -> ProfileOptions {
+        ProfileOptions::default()
+    }
+
+    pub fn name(mut self, name: &str) -> ProfileOptions {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn service(mut self, service: &str) -> ProfileOptions {
+        self.service = Some(service.to_string());
+        self
+    }
+
+    pub fn role(mut self, role: ProfileRole) -> ProfileOptions {
+        self.role = Some(role);
+        self
+    }
+
+    // RFCOMM channel number.
+    pub fn channel(mut self, channel: u16) -> ProfileOptions {
+        self.channel = Some(channel);
+        self
+    }
+
+    pub fn psm(mut self, psm: u16) -> ProfileOptions {
+        self.psm = Some(psm);
+        self
+    }
+
+    pub fn require_authentication(mut self, require: bool) -> ProfileOptions {
+        self.require_authentication = Some(require);
+        self
+    }
+
+    pub fn require_authorization(mut self, require: bool) -> ProfileOptions {
+        self.require_authorization = Some(require);
+        self
+    }
+
+    pub fn auto_connect(mut self, auto_connect: bool) -> ProfileOptions {
+        self.auto_connect = Some(auto_connect);
+        self
+    }
+
+    // Full SDP record in XML, replaces the one BlueZ would generate.
+    pub fn service_record(mut self, service_record: &str) -> ProfileOptions {
+        self.service_record = Some(service_record.to_string());
+        self
+    }
+
+    pub fn version(mut self, version: u16) -> ProfileOptions {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn features(mut self, features: u16) -> ProfileOptions {
+        self.features = Some(features);
+        self
+    }
+
+    fn to_message_item(&self) -> MessageItem {
+        let mut options: Vec<(&str, MessageItem)> = Vec::new();
+        if let Some(ref name) = self.name {
+            options.push(("Name", name.as_str().into()));
+        }
+        if let Some(ref service) = self.service {
+            options.push(("Service", service.as_str().into()));
+        }
+        if let Some(role) = self.role {
+            options.push(("Role", role.as_str().into()));
+        }
+        if let Some(channel) = self.channel {
+            options.push(("Channel", channel.into()));
+        }
+        if let Some(psm) = self.psm {
+            options.push(("PSM", psm.into()));
+        }
+        if let Some(require) = self.require_authentication {
+            options.push(("RequireAuthentication", require.into()));
+        }
+        if let Some(require) = self.require_authorization {
+            options.push(("RequireAuthorization", require.into()));
+        }
+        if let Some(auto_connect) = self.auto_connect {
+            options.push(("AutoConnect", auto_connect.into()));
+        }
+        if let Some(ref service_record) = self.service_record {
+            options.push(("ServiceRecord", service_record.as_str().into()));
+        }
+        if let Some(version) = self.version {
+            options.push(("Version", version.into()));
+        }
+        if let Some(features) = self.features {
+            options.push(("Features", features.into()));
+        }
+        bluetooth_utils::properties_dict(options)
+    }
+}
+
+// The socket handed over with NewConnection. Closing it (dropping the stream)
+// disconnects the profile.
+#[derive(Debug)]
+pub struct ProfileStream {
+    file: File,
+}
+
+impl ProfileStream {
+    fn new(fd: RawFd) -> ProfileStream {
+        ProfileStream {
+            file: unsafe { File::from_raw_fd(fd) },
+        }
+    }
+
+    pub fn try_clone(&self) -> io::Result<ProfileStream> {
+        Ok(ProfileStream {
+            file: try!(self.file.try_clone()),
+        })
+    }
+}
+
+impl Read for ProfileStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf)
+    }
+}
+
+impl Write for ProfileStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl AsRawFd for ProfileStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for ProfileStream {
+    fn into_raw_fd(self) -> RawFd {
+        self.file.into_raw_fd()
+    }
+}
+
+#[derive(Debug)]
+pub struct ProfileConnection {
+    pub device: String,
+    pub stream: ProfileStream,
+    // Profile version and features found in the remote SDP record, if any.
+    pub version: Option<u16>,
+    pub features: Option<u16>,
+    pub fd_properties: HashMap<String, MessageItem>,
+}
+
+#[derive(Debug)]
+pub enum ProfileEvent {
+    NewConnection(ProfileConnection),
+    // The connection of `device` should be closed by dropping its stream.
+    RequestDisconnection { device: String },
+    // BlueZ unregistered the profile, no further events follow.
+    Release,
+}
+
+type ProfileEvents = Rc<RefCell<VecDeque<ProfileEvent>>>;
+
+// A Profile1 object exported on the session's connection and registered with
+// ProfileManager1. Method calls from BlueZ are answered while the session is
+// polled, `next_event` does so itself.
+pub struct BluetoothProfile<'a> {
+    session: &'a BluetoothSession,
+    object_path: String,
+    uuid: String,
+    events: ProfileEvents,
+    active: Rc<Cell<bool>>,
+    registered: bool,
+}
+
+impl<'a> BluetoothProfile<'a> {
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/profile-api.txt#n12
+    pub fn register(
+        session: &'a BluetoothSession,
+        object_path: &str,
+        uuid: &str,
+        options: &ProfileOptions,
+    ) -> Result<BluetoothProfile<'a>, Box<Error>> {
+        let events: ProfileEvents = Rc::new(RefCell::new(VecDeque::new()));
+        let active = Rc::new(Cell::new(true));
+        let connection = session.get_connection();
+        try!(connection.register_object_path(object_path));
+        connection.add_handler(ProfileHandler {
+            object_path: String::from(object_path),
+            events: events.clone(),
+            active: active.clone(),
+        });
+
+        let mut profile = BluetoothProfile {
+            session: session,
+            object_path: String::from(object_path),
+            uuid: String::from(uuid),
+            events: events,
+            active: active,
+            registered: false,
+        };
+        try!(profile.call_manager(
+            "RegisterProfile",
+            &[
+                MessageItem::ObjectPath(Path::from(profile.object_path.clone())),
+                uuid.into(),
+                options.to_message_item(),
+            ],
+        ));
+        profile.registered = true;
+        Ok(profile)
+    }
+
+    pub fn get_id(&self) -> String {
+        self.object_path.clone()
+    }
+
+    pub fn get_uuid(&self) -> String {
+        self.uuid.clone()
+    }
+
+    fn call_manager(&self, method: &str, param: &[MessageItem]) -> Result<(), Box<Error>> {
+        bluetooth_utils::call_method(
+            self.session.get_connection(),
+            PROFILE_MANAGER_INTERFACE,
+            PROFILE_MANAGER_PATH,
+            method,
+            Some(param),
+            1000,
+        )
+    }
+
+    // Waits up to `timeout_ms` for the next call from BlueZ.
+    pub fn next_event(&self, timeout_ms: u32) -> Option<ProfileEvent> {
+        let events = self.events.clone();
+        self.session
+            .poll_until(timeout_ms, move || events.borrow_mut().pop_front())
+    }
+
+    // Same as dropping the profile, but reports whether BlueZ accepted it.
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/profile-api.txt#n98
+    pub fn unregister(mut self) -> Result<(), Box<Error>> {
+        self.registered = false;
+        self.call_unregister()
+    }
+
+    fn call_unregister(&self) -> Result<(), Box<Error>> {
+        self.call_manager(
+            "UnregisterProfile",
+            &[MessageItem::ObjectPath(Path::from(
+                self.object_path.clone(),
+            ))],
+        )
+    }
+}
+
+impl<'a> Drop for BluetoothProfile<'a> {
+    fn drop(&mut self) {
+        if self.registered {
+            let _ = self.call_unregister();
+        }
+        self.active.set(false);
+        self.session
+            .get_connection()
+            .unregister_object_path(&self.object_path);
+    }
+}
+
+struct ProfileHandler {
+    object_path: String,
+    events: ProfileEvents,
+    active: Rc<Cell<bool>>,
+}
+
+impl ProfileHandler {
+    // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/profile-api.txt#n118
+    fn new_connection(&self, msg: &Message) -> Option<ProfileEvent> {
+        let mut items = msg.get_items().into_iter();
+        let device = match items.next() {
+            Some(MessageItem::ObjectPath(path)) => path.to_string(),
+            _ => return None,
+        };
+        let fd = match items.next() {
+            Some(MessageItem::UnixFd(fd)) => fd.into_fd(),
+            _ => return None,
+        };
+        let mut fd_properties = HashMap::new();
+        if let Some(properties) = items.next() {
+            if let Ok(entries) = properties.inner::<&[MessageItem]>() {
+                for entry in entries {
+                    if let Ok((key, value)) = entry.inner::<(&MessageItem, &MessageItem)>() {
+                        if let (Ok(key), Ok(value)) =
+                            (key.inner::<&str>(), value.inner::<&MessageItem>())
+                        {
+                            fd_properties.insert(String::from(key), value.clone());
+                        }
+                    }
+                }
+            }
+        }
+        Some(ProfileEvent::NewConnection(ProfileConnection {
+            device: device,
+            stream: ProfileStream::new(fd),
+            version: fd_properties
+                .get("Version")
+                .and_then(|v| v.inner::<u16>().ok()),
+            features: fd_properties
+                .get("Features")
+                .and_then(|v| v.inner::<u16>().ok()),
+            fd_properties: fd_properties,
+        }))
+    }
+}
+
+impl MsgHandler for ProfileHandler {
+    fn handler_type(&self) -> MsgHandlerType {
+        MsgHandlerType::MsgType(MessageType::MethodCall)
+    }
+
+    fn handle_msg(&mut self, msg: &Message) -> Option<MsgHandlerResult> {
+        if !self.active.get() {
+            return Some(MsgHandlerResult {
+                handled: false,
+                done: true,
+                reply: Vec::new(),
+            });
+        }
+        let path = msg.path().map(|p| p.to_string()).unwrap_or_default();
+        let interface = msg.interface().map(|i| i.to_string()).unwrap_or_default();
+        if path != self.object_path || interface != PROFILE_INTERFACE {
+            return None;
+        }
+        let event = match msg
+            .member()
+            .map(|m| m.to_string())
+            .unwrap_or_default()
+            .as_str()
+        {
+            "Release" => Some(ProfileEvent::Release),
+            "NewConnection" => self.new_connection(msg),
+            // http://git.kernel.org/cgit/bluetooth/bluez.git/tree/doc/profile-api.txt#n129
+            "RequestDisconnection" => msg
+                .get_items()
+                .get(0)
+                .and_then(|d| d.inner::<&str>().ok())
+                .map(|device| ProfileEvent::RequestDisconnection {
+                    device: String::from(device),
+                }),
+            _ => return None,
+        };
+        let reply = match event {
+            Some(event) => {
+                self.events.borrow_mut().push_back(event);
+                Message::new_method_return(msg)
+            }
+            None => Message::new_error(msg, INVALID_ARGUMENTS_ERROR, "Invalid arguments"),
+        };
+        Some(MsgHandlerResult {
+            handled: true,
+            done: false,
+            reply: reply.into_iter().collect(),
+        })
+    }
+}
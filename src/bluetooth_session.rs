@@ -1,12 +1,29 @@
-use dbus::{BusType, ConnMsgs, Connection};
+use bluetooth_utils;
+use dbus::{BusType, ConnMsgs, Connection, Message, MessageItem, MessageType};
 
+use std::cell::RefCell;
+use std::cmp;
+use std::collections::VecDeque;
 use std::error::Error;
+use std::time::{Duration, Instant};
 
 static BLUEZ_MATCH: &'static str = "type='signal',sender='org.bluez'";
+static PROPERTIES_INTERFACE: &'static str = "org.freedesktop.DBus.Properties";
+
+// Upper bound for a single blocking read in `poll_until`, after a handled
+// message the read would otherwise wait for the full remaining time.
+const POLL_MS: u32 = 100;
+
+// Messages kept for `incoming_queued`, so a session that is only polled through
+// `poll_until` doesn't grow with every signal of the bus.
+const PENDING_LIMIT: usize = 64;
 
 #[derive(Debug)]
 pub struct BluetoothSession {
     connection: Connection,
+    // Messages read by the waiting methods but not meant for them, returned first by
+    // the next `incoming_queued` call. The oldest are dropped beyond PENDING_LIMIT.
+    pending: RefCell<VecDeque<Message>>,
 }
 
 impl BluetoothSession {
@@ -24,9 +41,11 @@ impl BluetoothSession {
         Ok(BluetoothSession::new(c))
     }
 
-    fn new(connection: Connection) -> BluetoothSession {
+    // Also wraps the connection of ObexClient, to obexd on the session bus.
+    pub(crate) fn new(connection: Connection) -> BluetoothSession {
         BluetoothSession {
             connection: connection,
+            pending: RefCell::new(VecDeque::new()),
         }
     }
 
@@ -34,7 +53,207 @@ impl BluetoothSession {
         &self.connection
     }
 
+    // Reads the connection only, messages kept by the waiting methods are returned by
+    // `incoming_queued`.
     pub fn incoming(&self, timeout_ms: u32) -> ConnMsgs<&Connection> {
         self.connection.incoming(timeout_ms)
     }
+
+    // Same as `incoming`, but the messages kept by `poll_until` and the other waiting
+    // methods come first, without blocking.
+    pub fn incoming_queued(&self, timeout_ms: u32) -> SessionMessages {
+        SessionMessages {
+            pending: &self.pending,
+            messages: self.connection.incoming(timeout_ms),
+        }
+    }
+
+    // Polls the connection until `ready` returns a value or `timeout_ms` elapses.
+    // Exported objects registered as message handlers are served meanwhile, the
+    // latest PENDING_LIMIT other messages are kept for `incoming_queued`.
+    pub fn poll_until<T, F>(&self, timeout_ms: u32, mut ready: F) -> Option<T>
+    where
+        F: FnMut() -> Option<T>,
+    {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms as u64);
+        loop {
+            if let Some(value) = ready() {
+                return Some(value);
+            }
+            let remaining_ms = match remaining_ms(deadline) {
+                Some(remaining_ms) => remaining_ms,
+                None => return None,
+            };
+            let poll_ms = cmp::min(remaining_ms, POLL_MS);
+            if let Some(msg) = self.connection.incoming(poll_ms).next() {
+                keep(&mut self.pending.borrow_mut(), msg);
+            }
+        }
+    }
+
+    // Passes the messages kept for `incoming_queued`, then new ones, to `check` until it
+    // returns a value or `timeout_ms` elapses. The message `check` returned a value
+    // for is consumed, the latest PENDING_LIMIT others are kept for `incoming_queued`.
+    pub fn wait_for_message<T, F>(&self, timeout_ms: u32, mut check: F) -> Option<T>
+    where
+        F: FnMut(&Message) -> Option<T>,
+    {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms as u64);
+        let mut skipped = VecDeque::new();
+        let mut found = None;
+        loop {
+            let queued = self.pending.borrow_mut().pop_front();
+            let msg = match queued {
+                Some(msg) => msg,
+                None => break,
+            };
+            found = check(&msg);
+            if found.is_some() {
+                break;
+            }
+            skipped.push_back(msg);
+        }
+        while found.is_none() {
+            let remaining_ms = match remaining_ms(deadline) {
+                Some(remaining_ms) => remaining_ms,
+                None => break,
+            };
+            for msg in self.connection.incoming(remaining_ms) {
+                found = check(&msg);
+                if found.is_some() {
+                    break;
+                }
+                keep(&mut skipped, msg);
+                if Instant::now() >= deadline {
+                    break;
+                }
+            }
+        }
+
+        // Ahead of the ones not looked at when `check` returned early.
+        let mut pending = self.pending.borrow_mut();
+        for msg in skipped.into_iter().rev() {
+            pending.push_front(msg);
+        }
+        while pending.len() > PENDING_LIMIT {
+            pending.pop_front();
+        }
+        found
+    }
+
+    // Blocks until `prop` of `interface` on `object_path` satisfies `predicate`, or
+    // `timeout_ms` elapses. The current value is checked first, afterwards only
+    // PropertiesChanged signals are inspected, so the session must have been
+    // created with a match covering `object_path`.
+    //
+    // Other messages read while waiting are kept for `incoming_queued`.
+    pub fn wait_for_property<F>(
+        &self,
+        object_path: &str,
+        interface: &str,
+        prop: &str,
+        predicate: F,
+        timeout_ms: u32,
+    ) -> Result<MessageItem, Box<Error>>
+    where
+        F: Fn(&MessageItem) -> bool,
+    {
+        if let Ok(value) =
+            bluetooth_utils::get_property(&self.connection, interface, object_path, prop)
+        {
+            if predicate(&value) {
+                return Ok(value);
+            }
+        }
+
+        let value = self.wait_for_message(timeout_ms, |msg| {
+            changed_property(msg, object_path, interface, prop).and_then(|value| {
+                if predicate(&value) {
+                    Some(value)
+                } else {
+                    None
+                }
+            })
+        });
+        if let Some(value) = value {
+            return Ok(value);
+        }
+        Err(Box::from(format!(
+            "Timed out waiting for {} on {}",
+            prop, object_path
+        )))
+    }
+}
+
+// Messages of a BluetoothSession, see `BluetoothSession::incoming_queued`.
+pub struct SessionMessages<'a> {
+    pending: &'a RefCell<VecDeque<Message>>,
+    messages: ConnMsgs<&'a Connection>,
+}
+
+impl<'a> Iterator for SessionMessages<'a> {
+    type Item = Message;
+
+    fn next(&mut self) -> Option<Message> {
+        let queued = self.pending.borrow_mut().pop_front();
+        if queued.is_some() {
+            return queued;
+        }
+        self.messages.next()
+    }
+}
+
+// Queues `msg`, dropping the oldest message beyond PENDING_LIMIT.
+fn keep(queue: &mut VecDeque<Message>, msg: Message) {
+    queue.push_back(msg);
+    if queue.len() > PENDING_LIMIT {
+        queue.pop_front();
+    }
+}
+
+// Milliseconds until `deadline`, at least 1 so a read doesn't block indefinitely.
+fn remaining_ms(deadline: Instant) -> Option<u32> {
+    let now = Instant::now();
+    if now >= deadline {
+        return None;
+    }
+    let remaining = deadline - now;
+    let remaining_ms = remaining.as_secs() * 1000 + remaining.subsec_nanos() as u64 / 1_000_000;
+    Some(cmp::max(1, cmp::min(remaining_ms, u32::max_value() as u64)) as u32)
+}
+
+fn changed_property(
+    msg: &Message,
+    object_path: &str,
+    interface: &str,
+    prop: &str,
+) -> Option<MessageItem> {
+    if msg.msg_type() != MessageType::Signal {
+        return None;
+    }
+    match (msg.path(), msg.interface(), msg.member()) {
+        (Some(ref path), Some(ref i), Some(ref member))
+            if &**path == object_path
+                && &**i == PROPERTIES_INTERFACE
+                && &**member == "PropertiesChanged" => {}
+        _ => return None,
+    }
+
+    let items = msg.get_items();
+    match items.get(0).and_then(|i| i.inner::<&str>().ok()) {
+        Some(changed_interface) if changed_interface == interface => (),
+        _ => return None,
+    }
+    let changed: &[MessageItem] = match items.get(1).and_then(|i| i.inner().ok()) {
+        Some(changed) => changed,
+        None => return None,
+    };
+    for entry in changed {
+        if let Ok((key, value)) = entry.inner::<(&MessageItem, &MessageItem)>() {
+            if key.inner::<&str>() == Ok(prop) {
+                return value.inner::<&MessageItem>().ok().cloned();
+            }
+        }
+    }
+    None
 }
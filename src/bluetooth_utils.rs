@@ -1,4 +1,5 @@
-use dbus::{Connection, Message, MessageItem, Props};
+use dbus::{Connection, Message, MessageItem, MessageItemArray, Props, Signature};
+use std::collections::HashMap;
 use std::error::Error;
 
 static ADAPTER_INTERFACE: &'static str = "org.bluez.Adapter1";
@@ -7,6 +8,10 @@ static SERVICE_INTERFACE: &'static str = "org.bluez.GattService1";
 static CHARACTERISTIC_INTERFACE: &'static str = "org.bluez.GattCharacteristic1";
 static DESCRIPTOR_INTERFACE: &'static str = "org.bluez.GattDescriptor1";
 static SERVICE_NAME: &'static str = "org.bluez";
+static BASE_UUID_SUFFIX: &'static str = "-0000-1000-8000-00805f9b34fb";
+
+// object path -> interface -> property -> value
+pub type ManagedObjects = HashMap<String, HashMap<String, HashMap<String, MessageItem>>>;
 
 fn get_managed_objects(c: &Connection) -> Result<Vec<MessageItem>, Box<Error>> {
     let m = try!(Message::new_method_call(
@@ -19,6 +24,98 @@ fn get_managed_objects(c: &Connection) -> Result<Vec<MessageItem>, Box<Error>> {
     Ok(r.get_items())
 }
 
+pub fn get_managed_objects_properties(c: &Connection) -> Result<ManagedObjects, Box<Error>> {
+    let mut objects_map: ManagedObjects = HashMap::new();
+    let objects: Vec<MessageItem> = try!(get_managed_objects(&c));
+    let z: &[MessageItem] = objects.get(0).unwrap().inner().unwrap();
+    for y in z {
+        let (path, interfaces) = y.inner().unwrap();
+        let mut interfaces_map = HashMap::new();
+        let x: &[MessageItem] = interfaces.inner().unwrap();
+        for interface in x {
+            let (i, props) = interface.inner().unwrap();
+            let mut props_map = HashMap::new();
+            let w: &[MessageItem] = props.inner().unwrap();
+            for prop in w {
+                let (name, value) = prop.inner().unwrap();
+                let value: &MessageItem = value.inner().unwrap();
+                props_map.insert(String::from(name.inner::<&str>().unwrap()), value.clone());
+            }
+            interfaces_map.insert(String::from(i.inner::<&str>().unwrap()), props_map);
+        }
+        objects_map.insert(String::from(path.inner::<&str>().unwrap()), interfaces_map);
+    }
+    Ok(objects_map)
+}
+
+// Expands 16 and 32 bit UUIDs to their full 128 bit form over the Bluetooth base UUID.
+pub fn normalize_uuid(uuid: &str) -> String {
+    let uuid = uuid.trim().to_lowercase();
+    let uuid = if uuid.starts_with("0x") {
+        String::from(&uuid[2..])
+    } else {
+        uuid
+    };
+    match uuid.len() {
+        4 => format!("0000{}{}", uuid, BASE_UUID_SUFFIX),
+        8 => format!("{}{}", uuid, BASE_UUID_SUFFIX),
+        _ => uuid,
+    }
+}
+
+pub fn uuid_matches(a: &str, b: &str) -> bool {
+    normalize_uuid(a) == normalize_uuid(b)
+}
+
+// Fails with an error naming the `name` field when `value` is shorter than `expected`.
+pub fn check_length(name: &str, value: &[u8], expected: usize) -> Result<(), Box<Error>> {
+    if value.len() < expected {
+        return Err(Box::from(format!(
+            "{} needs {} bytes, got {}",
+            name,
+            expected,
+            value.len()
+        )));
+    }
+    Ok(())
+}
+
+// Little endian, as all Bluetooth multi-byte fields.
+pub fn read_u16(value: &[u8], offset: usize) -> u16 {
+    value[offset] as u16 | (value[offset + 1] as u16) << 8
+}
+
+// a{sv} dictionary as used for the properties of exported objects.
+pub fn properties_dict(properties: Vec<(&str, MessageItem)>) -> MessageItem {
+    let entries = properties
+        .into_iter()
+        .map(|(name, value)| {
+            MessageItem::DictEntry(
+                Box::new(name.into()),
+                Box::new(MessageItem::Variant(Box::new(value))),
+            )
+        })
+        .collect();
+    MessageItem::Array(MessageItemArray::new(entries, Signature::from("a{sv}")).unwrap())
+}
+
+// Entries of an a{sv} dictionary keyed by name, with the variants unwrapped.
+pub fn variant_dict(dict: &MessageItem) -> HashMap<String, MessageItem> {
+    let mut map = HashMap::new();
+    if let Ok(entries) = dict.inner::<&[MessageItem]>() {
+        for entry in entries {
+            if let Ok((key, value)) = entry.inner::<(&MessageItem, &MessageItem)>() {
+                if let (Ok(key), Ok(value)) =
+                    (key.inner::<&str>(), value.inner::<&MessageItem>())
+                {
+                    map.insert(String::from(key), value.clone());
+                }
+            }
+        }
+    }
+    map
+}
+
 pub fn get_adapters(c: &Connection) -> Result<Vec<String>, Box<Error>> {
     let mut adapters: Vec<String> = Vec::new();
     let objects: Vec<MessageItem> = try!(get_managed_objects(&c));
@@ -57,6 +154,60 @@ pub fn list_descriptors(c: &Connection, device_path: &String) -> Result<Vec<Stri
     list_item(c, DESCRIPTOR_INTERFACE, device_path, "Characteristic")
 }
 
+pub fn find_characteristic(
+    c: &Connection,
+    service_path: &str,
+    uuid: &str,
+) -> Result<Option<String>, Box<Error>> {
+    find_item(c, CHARACTERISTIC_INTERFACE, service_path, "Service", uuid)
+}
+
+pub fn find_descriptor(
+    c: &Connection,
+    characteristic_path: &str,
+    uuid: &str,
+) -> Result<Option<String>, Box<Error>> {
+    find_item(
+        c,
+        DESCRIPTOR_INTERFACE,
+        characteristic_path,
+        "Characteristic",
+        uuid,
+    )
+}
+
+// Like list_item, but matches the UUID too and reads both properties from the
+// GetManagedObjects reply instead of asking each object.
+fn find_item(
+    c: &Connection,
+    item_interface: &str,
+    item_path: &str,
+    item_property: &str,
+    uuid: &str,
+) -> Result<Option<String>, Box<Error>> {
+    let objects = try!(get_managed_objects_properties(c));
+    let property = |props: &HashMap<String, MessageItem>, name: &str| {
+        props
+            .get(name)
+            .and_then(|p| p.inner::<&str>().ok())
+            .map(String::from)
+    };
+    let mut paths: Vec<&String> = objects
+        .iter()
+        .filter(|&(_, interfaces)| match interfaces.get(item_interface) {
+            Some(props) => {
+                property(props, item_property).as_ref().map(|p| p.as_str()) == Some(item_path)
+                    && property(props, "UUID").map_or(false, |u| uuid_matches(&u, uuid))
+            }
+            None => false,
+        })
+        .map(|(path, _)| path)
+        .collect();
+    // Object path order, as list_item returns them.
+    paths.sort();
+    Ok(paths.first().map(|p| (*p).clone()))
+}
+
 fn list_item(
     c: &Connection,
     item_interface: &str,
@@ -0,0 +1,214 @@
+use std::fmt;
+
+// https://www.bluetooth.com/specifications/assigned-numbers/ (Appearance Values)
+static CATEGORIES: &'static [(u16, &'static str)] = &[
+    (0x000, "Unknown"),
+    (0x001, "Phone"),
+    (0x002, "Computer"),
+    (0x003, "Watch"),
+    (0x004, "Clock"),
+    (0x005, "Display"),
+    (0x006, "Remote Control"),
+    (0x007, "Eye-glasses"),
+    (0x008, "Tag"),
+    (0x009, "Keyring"),
+    (0x00A, "Media Player"),
+    (0x00B, "Barcode Scanner"),
+    (0x00C, "Thermometer"),
+    (0x00D, "Heart Rate Sensor"),
+    (0x00E, "Blood Pressure"),
+    (0x00F, "Human Interface Device"),
+    (0x010, "Glucose Meter"),
+    (0x011, "Running Walking Sensor"),
+    (0x012, "Cycling"),
+    (0x013, "Control Device"),
+    (0x014, "Network Device"),
+    (0x015, "Sensor"),
+    (0x016, "Light Fixtures"),
+    (0x017, "Fan"),
+    (0x018, "HVAC"),
+    (0x019, "Air Conditioning"),
+    (0x01A, "Humidifier"),
+    (0x01B, "Heating"),
+    (0x01C, "Access Control"),
+    (0x01D, "Motorized Device"),
+    (0x01E, "Power Device"),
+    (0x01F, "Light Source"),
+    (0x020, "Window Covering"),
+    (0x021, "Audio Sink"),
+    (0x022, "Audio Source"),
+    (0x023, "Motorized Vehicle"),
+    (0x024, "Domestic Appliance"),
+    (0x025, "Wearable Audio Device"),
+    (0x026, "Aircraft"),
+    (0x027, "AV Equipment"),
+    (0x028, "Display Equipment"),
+    (0x029, "Hearing aid"),
+    (0x02A, "Gaming"),
+    (0x02B, "Signage"),
+    (0x031, "Pulse Oximeter"),
+    (0x032, "Weight Scale"),
+    (0x033, "Personal Mobility Device"),
+    (0x034, "Continuous Glucose Monitor"),
+    (0x035, "Insulin Pump"),
+    (0x036, "Medication Delivery"),
+    (0x037, "Spirometer"),
+    (0x051, "Outdoor Sports Activity"),
+];
+
+static SUBCATEGORIES: &'static [(u16, u8, &'static str)] = &[
+    (0x002, 0x01, "Desktop Workstation"),
+    (0x002, 0x02, "Server-class Computer"),
+    (0x002, 0x03, "Laptop"),
+    (0x002, 0x04, "Handheld PC/PDA"),
+    (0x002, 0x05, "Palm-size PC/PDA"),
+    (0x002, 0x06, "Wearable computer"),
+    (0x002, 0x07, "Tablet"),
+    (0x002, 0x08, "Docking Station"),
+    (0x002, 0x09, "All in One"),
+    (0x002, 0x0A, "Blade Server"),
+    (0x002, 0x0B, "Convertible"),
+    (0x002, 0x0C, "Detachable"),
+    (0x002, 0x0D, "IoT Gateway"),
+    (0x002, 0x0E, "Mini PC"),
+    (0x002, 0x0F, "Stick PC"),
+    (0x003, 0x01, "Sports Watch"),
+    (0x003, 0x02, "Smartwatch"),
+    (0x00C, 0x01, "Ear Thermometer"),
+    (0x00D, 0x01, "Heart Rate Belt"),
+    (0x00E, 0x01, "Arm Blood Pressure"),
+    (0x00E, 0x02, "Wrist Blood Pressure"),
+    (0x00F, 0x01, "Keyboard"),
+    (0x00F, 0x02, "Mouse"),
+    (0x00F, 0x03, "Joystick"),
+    (0x00F, 0x04, "Gamepad"),
+    (0x00F, 0x05, "Digitizer Tablet"),
+    (0x00F, 0x06, "Card Reader"),
+    (0x00F, 0x07, "Digital Pen"),
+    (0x00F, 0x08, "Barcode Scanner"),
+    (0x00F, 0x09, "Touchpad"),
+    (0x00F, 0x0A, "Presentation Remote"),
+    (0x011, 0x01, "In-Shoe Running Walking Sensor"),
+    (0x011, 0x02, "On-Shoe Running Walking Sensor"),
+    (0x011, 0x03, "On-Hip Running Walking Sensor"),
+    (0x012, 0x01, "Cycling Computer"),
+    (0x012, 0x02, "Speed Sensor"),
+    (0x012, 0x03, "Cadence Sensor"),
+    (0x012, 0x04, "Power Sensor"),
+    (0x012, 0x05, "Speed and Cadence Sensor"),
+    (0x021, 0x01, "Standalone Speaker"),
+    (0x021, 0x02, "Soundbar"),
+    (0x021, 0x03, "Bookshelf Speaker"),
+    (0x021, 0x04, "Standmounted Speaker"),
+    (0x021, 0x05, "Speakerphone"),
+    (0x022, 0x01, "Microphone"),
+    (0x022, 0x02, "Alarm"),
+    (0x022, 0x03, "Bell"),
+    (0x022, 0x04, "Horn"),
+    (0x022, 0x05, "Broadcasting Device"),
+    (0x022, 0x06, "Service Desk"),
+    (0x022, 0x07, "Kiosk"),
+    (0x022, 0x08, "Broadcasting Room"),
+    (0x022, 0x09, "Auditorium"),
+    (0x025, 0x01, "Earbud"),
+    (0x025, 0x02, "Headset"),
+    (0x025, 0x03, "Headphones"),
+    (0x025, 0x04, "Neck Band"),
+    (0x029, 0x01, "In-ear hearing aid"),
+    (0x029, 0x02, "Behind-ear hearing aid"),
+    (0x029, 0x03, "Cochlear Implant"),
+    (0x02A, 0x01, "Home Video Game Console"),
+    (0x02A, 0x02, "Portable handheld console"),
+    (0x031, 0x01, "Fingertip Pulse Oximeter"),
+    (0x031, 0x02, "Wrist Worn Pulse Oximeter"),
+    (0x033, 0x01, "Powered Wheelchair"),
+    (0x033, 0x02, "Mobility Scooter"),
+    (0x035, 0x01, "Insulin Pump, durable pump"),
+    (0x035, 0x04, "Insulin Pump, patch pump"),
+    (0x035, 0x08, "Insulin Pen"),
+    (0x037, 0x01, "Handheld Spirometer"),
+    (0x051, 0x01, "Location Display"),
+    (0x051, 0x02, "Location and Navigation Display"),
+    (0x051, 0x03, "Location Pod"),
+    (0x051, 0x04, "Location and Navigation Pod"),
+];
+
+// GAP Appearance value, a 10 bit category followed by a 6 bit subcategory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Appearance(u16);
+
+impl Appearance {
+    pub fn new(value: u16) -> Appearance {
+        Appearance(value)
+    }
+
+    pub fn value(&self) -> u16 {
+        self.0
+    }
+
+    pub fn category(&self) -> u16 {
+        self.0 >> 6
+    }
+
+    // 0 is the generic subcategory of every category.
+    pub fn subcategory(&self) -> u8 {
+        (self.0 & 0x3F) as u8
+    }
+
+    pub fn category_name(&self) -> Option<&'static str> {
+        let category = self.category();
+        CATEGORIES
+            .iter()
+            .find(|&&(c, _)| c == category)
+            .map(|&(_, name)| name)
+    }
+
+    pub fn subcategory_name(&self) -> Option<&'static str> {
+        let (category, subcategory) = (self.category(), self.subcategory());
+        SUBCATEGORIES
+            .iter()
+            .find(|&&(c, s, _)| c == category && s == subcategory)
+            .map(|&(_, _, name)| name)
+    }
+}
+
+impl From<u16> for Appearance {
+    fn from(value: u16) -> Appearance {
+        Appearance(value)
+    }
+}
+
+impl fmt::Display for Appearance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.category_name(), self.subcategory_name()) {
+            (Some(category), Some(subcategory)) => write!(f, "{}: {}", category, subcategory),
+            (Some(category), None) if self.subcategory() == 0 => write!(f, "{}", category),
+            (Some(category), None) => write!(f, "{} (0x{:04x})", category, self.0),
+            (None, _) => write!(f, "Reserved (0x{:04x})", self.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Appearance;
+
+    #[test]
+    fn split_category_and_subcategory() {
+        // Heart Rate Sensor: Heart Rate Belt.
+        let appearance = Appearance::new(0x0341);
+        assert_eq!(appearance.category(), 0x00D);
+        assert_eq!(appearance.subcategory(), 0x01);
+        assert_eq!(appearance.category_name(), Some("Heart Rate Sensor"));
+        assert_eq!(appearance.subcategory_name(), Some("Heart Rate Belt"));
+        assert_eq!(appearance.to_string(), "Heart Rate Sensor: Heart Rate Belt");
+    }
+
+    #[test]
+    fn generic_and_unknown_subcategories() {
+        assert_eq!(Appearance::new(0x00C0).to_string(), "Watch");
+        assert_eq!(Appearance::new(0x00FF).to_string(), "Watch (0x00ff)");
+        assert_eq!(Appearance::new(0xFFC0).category_name(), None);
+        assert_eq!(Appearance::new(0xFFC0).to_string(), "Reserved (0xffc0)");
+    }
+}
@@ -0,0 +1,508 @@
+use bluetooth_utils;
+use hex;
+use std::collections::HashMap;
+
+pub static EDDYSTONE_SERVICE_UUID: &'static str = "0000feaa-0000-1000-8000-00805f9b34fb";
+
+pub const APPLE_COMPANY_ID: u16 = 0x004C;
+pub const RUUVI_COMPANY_ID: u16 = 0x0499;
+
+const EDDYSTONE_UID: u8 = 0x00;
+const EDDYSTONE_URL: u8 = 0x10;
+const EDDYSTONE_TLM: u8 = 0x20;
+const EDDYSTONE_EID: u8 = 0x30;
+
+static EDDYSTONE_URL_SCHEMES: [&'static str; 4] =
+    ["http://www.", "https://www.", "http://", "https://"];
+
+static EDDYSTONE_URL_EXPANSIONS: [&'static str; 14] = [
+    ".com/", ".org/", ".edu/", ".net/", ".info/", ".biz/", ".gov/", ".com", ".org", ".edu", ".net",
+    ".info", ".biz", ".gov",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IBeacon {
+    pub uuid: String,
+    pub major: u16,
+    pub minor: u16,
+    // Calibrated RSSI at 1 m.
+    pub tx_power: i8,
+}
+
+impl IBeacon {
+    // `data` is the Apple manufacturer data, without the company identifier.
+    pub fn parse(data: &[u8]) -> Option<IBeacon> {
+        if data.len() < 23 || data[0] != 0x02 || data[1] != 0x15 {
+            return None;
+        }
+        Some(IBeacon {
+            uuid: format_uuid(&data[2..18]),
+            major: read_u16_be(data, 18),
+            minor: read_u16_be(data, 20),
+            tx_power: data[22] as i8,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AltBeacon {
+    pub manufacturer_id: u16,
+    // The 20 byte beacon identifier, conventionally split into a 16 byte
+    // id1 followed by two 2 byte ids.
+    pub beacon_id: Vec<u8>,
+    // Average RSSI at 1 m.
+    pub reference_rssi: i8,
+    pub manufacturer_reserved: u8,
+}
+
+impl AltBeacon {
+    pub fn parse(manufacturer_id: u16, data: &[u8]) -> Option<AltBeacon> {
+        if data.len() < 24 || data[0] != 0xBE || data[1] != 0xAC {
+            return None;
+        }
+        Some(AltBeacon {
+            manufacturer_id: manufacturer_id,
+            beacon_id: data[2..22].to_vec(),
+            reference_rssi: data[22] as i8,
+            manufacturer_reserved: data[23],
+        })
+    }
+
+    pub fn id1(&self) -> String {
+        format_uuid(&self.beacon_id[..16])
+    }
+
+    pub fn id2(&self) -> u16 {
+        read_u16_be(&self.beacon_id, 16)
+    }
+
+    pub fn id3(&self) -> u16 {
+        read_u16_be(&self.beacon_id, 18)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Eddystone {
+    Uid {
+        // Calibrated power at 0 m.
+        tx_power: i8,
+        namespace: String,
+        instance: String,
+    },
+    Url {
+        tx_power: i8,
+        url: String,
+    },
+    Tlm(EddystoneTelemetry),
+    // Encrypted TLM frames carry no readable data.
+    EncryptedTlm,
+    Eid {
+        tx_power: i8,
+        eid: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EddystoneTelemetry {
+    // Millivolts, None if the beacon is not battery powered.
+    pub battery_voltage: Option<u16>,
+    // Degrees Celsius, None if not supported.
+    pub temperature: Option<f64>,
+    pub advertisement_count: u32,
+    // Time since power-on in 0.1 second units.
+    pub uptime: u32,
+}
+
+impl Eddystone {
+    // `data` is the service data of the Eddystone UUID.
+    pub fn parse(data: &[u8]) -> Option<Eddystone> {
+        if data.is_empty() {
+            return None;
+        }
+        match data[0] {
+            EDDYSTONE_UID if data.len() >= 18 => Some(Eddystone::Uid {
+                tx_power: data[1] as i8,
+                namespace: hex::encode(&data[2..12]),
+                instance: hex::encode(&data[12..18]),
+            }),
+            EDDYSTONE_URL if data.len() >= 3 => match decode_eddystone_url(&data[2..]) {
+                Some(url) => Some(Eddystone::Url {
+                    tx_power: data[1] as i8,
+                    url: url,
+                }),
+                None => None,
+            },
+            EDDYSTONE_TLM if data.len() >= 2 && data[1] == 0x01 => Some(Eddystone::EncryptedTlm),
+            EDDYSTONE_TLM if data.len() >= 14 && data[1] == 0x00 => {
+                let battery_voltage = read_u16_be(data, 2);
+                let temperature = read_u16_be(data, 4);
+                Some(Eddystone::Tlm(EddystoneTelemetry {
+                    battery_voltage: if battery_voltage == 0 {
+                        None
+                    } else {
+                        Some(battery_voltage)
+                    },
+                    // Signed 8.8 fixed point.
+                    temperature: if temperature == 0x8000 {
+                        None
+                    } else {
+                        Some(temperature as i16 as f64 / 256.0)
+                    },
+                    advertisement_count: read_u32_be(data, 6),
+                    uptime: read_u32_be(data, 10),
+                }))
+            }
+            EDDYSTONE_EID if data.len() >= 10 => Some(Eddystone::Eid {
+                tx_power: data[1] as i8,
+                eid: hex::encode(&data[2..10]),
+            }),
+            _ => None,
+        }
+    }
+}
+
+fn decode_eddystone_url(data: &[u8]) -> Option<String> {
+    let mut url = match EDDYSTONE_URL_SCHEMES.get(data[0] as usize) {
+        Some(scheme) => scheme.to_string(),
+        None => return None,
+    };
+    for b in data[1..].iter() {
+        match EDDYSTONE_URL_EXPANSIONS.get(*b as usize) {
+            Some(expansion) => url.push_str(expansion),
+            None if *b > 0x20 && *b < 0x7F => url.push(*b as char),
+            None => return None,
+        }
+    }
+    Some(url)
+}
+
+// RuuviTag sensor readings. Fields the tag reports as invalid, or that the
+// data format does not carry, are None.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuuviData {
+    pub data_format: u8,
+    // Degrees Celsius.
+    pub temperature: Option<f64>,
+    // Relative humidity in percent.
+    pub humidity: Option<f64>,
+    // Pascal.
+    pub pressure: Option<u32>,
+    // X, Y and Z in milli-g.
+    pub acceleration: Option<(i16, i16, i16)>,
+    // Millivolts.
+    pub battery_voltage: Option<u16>,
+    pub tx_power: Option<i8>,
+    pub movement_counter: Option<u8>,
+    pub measurement_sequence: Option<u16>,
+    pub mac_address: Option<String>,
+}
+
+impl RuuviData {
+    // `data` is the Ruuvi manufacturer data, without the company identifier.
+    // Data formats 3 (RAWv1) and 5 (RAWv2) are supported.
+    pub fn parse(data: &[u8]) -> Option<RuuviData> {
+        match data.first() {
+            Some(&3) if data.len() >= 14 => Some(RuuviData::parse_v3(data)),
+            Some(&5) if data.len() >= 24 => Some(RuuviData::parse_v5(data)),
+            _ => None,
+        }
+    }
+
+    fn parse_v3(data: &[u8]) -> RuuviData {
+        // Sign and magnitude integer part, hundredths in the following byte.
+        let magnitude = (data[2] & 0x7F) as f64 + data[3] as f64 / 100.0;
+        RuuviData {
+            data_format: 3,
+            temperature: Some(if data[2] & 0x80 != 0 {
+                -magnitude
+            } else {
+                magnitude
+            }),
+            humidity: Some(data[1] as f64 * 0.5),
+            pressure: Some(read_u16_be(data, 4) as u32 + 50000),
+            acceleration: Some((
+                read_u16_be(data, 6) as i16,
+                read_u16_be(data, 8) as i16,
+                read_u16_be(data, 10) as i16,
+            )),
+            battery_voltage: Some(read_u16_be(data, 12)),
+            tx_power: None,
+            movement_counter: None,
+            measurement_sequence: None,
+            mac_address: None,
+        }
+    }
+
+    fn parse_v5(data: &[u8]) -> RuuviData {
+        let temperature = read_u16_be(data, 1);
+        let humidity = read_u16_be(data, 3);
+        let pressure = read_u16_be(data, 5);
+        let acceleration = (
+            read_u16_be(data, 7),
+            read_u16_be(data, 9),
+            read_u16_be(data, 11),
+        );
+        let power_info = read_u16_be(data, 13);
+        let battery = power_info >> 5;
+        let tx_power = power_info & 0x1F;
+        let sequence = read_u16_be(data, 16);
+        let mac = &data[18..24];
+        RuuviData {
+            data_format: 5,
+            temperature: if temperature == 0x8000 {
+                None
+            } else {
+                Some(temperature as i16 as f64 * 0.005)
+            },
+            humidity: if humidity == 0xFFFF {
+                None
+            } else {
+                Some(humidity as f64 * 0.0025)
+            },
+            pressure: if pressure == 0xFFFF {
+                None
+            } else {
+                Some(pressure as u32 + 50000)
+            },
+            acceleration: if acceleration.0 == 0x8000
+                || acceleration.1 == 0x8000
+                || acceleration.2 == 0x8000
+            {
+                None
+            } else {
+                Some((
+                    acceleration.0 as i16,
+                    acceleration.1 as i16,
+                    acceleration.2 as i16,
+                ))
+            },
+            battery_voltage: if battery == 0x07FF {
+                None
+            } else {
+                Some(battery + 1600)
+            },
+            tx_power: if tx_power == 0x1F {
+                None
+            } else {
+                Some(tx_power as i8 * 2 - 40)
+            },
+            movement_counter: if data[15] == 0xFF {
+                None
+            } else {
+                Some(data[15])
+            },
+            measurement_sequence: if sequence == 0xFFFF {
+                None
+            } else {
+                Some(sequence)
+            },
+            mac_address: if mac.iter().all(|b| *b == 0xFF) {
+                None
+            } else {
+                Some(
+                    mac.iter()
+                        .map(|b| format!("{:02X}", b))
+                        .collect::<Vec<String>>()
+                        .join(":"),
+                )
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Beacon {
+    IBeacon(IBeacon),
+    AltBeacon(AltBeacon),
+    Eddystone(Eddystone),
+    Ruuvi(RuuviData),
+}
+
+impl Beacon {
+    pub fn from_manufacturer_data(manufacturer_id: u16, data: &[u8]) -> Option<Beacon> {
+        if manufacturer_id == APPLE_COMPANY_ID {
+            if let Some(ibeacon) = IBeacon::parse(data) {
+                return Some(Beacon::IBeacon(ibeacon));
+            }
+        }
+        if manufacturer_id == RUUVI_COMPANY_ID {
+            if let Some(ruuvi) = RuuviData::parse(data) {
+                return Some(Beacon::Ruuvi(ruuvi));
+            }
+        }
+        AltBeacon::parse(manufacturer_id, data).map(Beacon::AltBeacon)
+    }
+
+    pub fn from_service_data(uuid: &str, data: &[u8]) -> Option<Beacon> {
+        if bluetooth_utils::uuid_matches(uuid, EDDYSTONE_SERVICE_UUID) {
+            return Eddystone::parse(data).map(Beacon::Eddystone);
+        }
+        None
+    }
+
+    // Decodes the maps returned by `BluetoothDevice::get_manufacturer_data` and
+    // `get_service_data`, entries in an unknown format are skipped.
+    pub fn from_advertisement(
+        manufacturer_data: &HashMap<u16, Vec<u8>>,
+        service_data: &HashMap<String, Vec<u8>>,
+    ) -> Vec<Beacon> {
+        let mut beacons: Vec<Beacon> = manufacturer_data
+            .iter()
+            .filter_map(|(id, data)| Beacon::from_manufacturer_data(*id, data))
+            .collect();
+        beacons.extend(
+            service_data
+                .iter()
+                .filter_map(|(uuid, data)| Beacon::from_service_data(uuid, data)),
+        );
+        beacons
+    }
+}
+
+fn format_uuid(bytes: &[u8]) -> String {
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+fn read_u16_be(data: &[u8], offset: usize) -> u16 {
+    (data[offset] as u16) << 8 | data[offset + 1] as u16
+}
+
+fn read_u32_be(data: &[u8], offset: usize) -> u32 {
+    (read_u16_be(data, offset) as u32) << 16 | read_u16_be(data, offset + 2) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ibeacon_data() -> Vec<u8> {
+        let mut data = vec![0x02, 0x15];
+        data.extend(hex::decode("e2c56db5dffb48d2b060d0f5a71096e0").unwrap());
+        data.extend(&[0x00, 0x01, 0x00, 0x02, 0xC5]);
+        data
+    }
+
+    #[test]
+    fn parse_ibeacon() {
+        assert_eq!(
+            Beacon::from_manufacturer_data(APPLE_COMPANY_ID, &ibeacon_data()),
+            Some(Beacon::IBeacon(IBeacon {
+                uuid: String::from("e2c56db5-dffb-48d2-b060-d0f5a71096e0"),
+                major: 1,
+                minor: 2,
+                tx_power: -59,
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_altbeacon() {
+        let mut data = vec![0xBE, 0xAC];
+        data.extend(hex::decode("e2c56db5dffb48d2b060d0f5a71096e000030004").unwrap());
+        data.extend(&[0xC5, 0x00]);
+        let beacon = match Beacon::from_manufacturer_data(0x0118, &data) {
+            Some(Beacon::AltBeacon(beacon)) => beacon,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(beacon.manufacturer_id, 0x0118);
+        assert_eq!(beacon.id1(), "e2c56db5-dffb-48d2-b060-d0f5a71096e0");
+        assert_eq!(beacon.id2(), 3);
+        assert_eq!(beacon.id3(), 4);
+        assert_eq!(beacon.reference_rssi, -59);
+    }
+
+    #[test]
+    fn parse_eddystone() {
+        let uid = hex::decode("00e800010203040506070809a0b0c0d0e0f0").unwrap();
+        assert_eq!(
+            Beacon::from_service_data("feaa", &uid),
+            Some(Beacon::Eddystone(Eddystone::Uid {
+                tx_power: -24,
+                namespace: String::from("00010203040506070809"),
+                instance: String::from("a0b0c0d0e0f0"),
+            }))
+        );
+
+        let mut url = vec![0x10, 0xEB, 0x00];
+        url.extend(b"example");
+        url.push(0x00);
+        url.extend(b"test");
+        assert_eq!(
+            Eddystone::parse(&url),
+            Some(Eddystone::Url {
+                tx_power: -21,
+                url: String::from("http://www.example.com/test"),
+            })
+        );
+
+        let tlm = hex::decode("20000bb81a800000002a00000064").unwrap();
+        assert_eq!(
+            Eddystone::parse(&tlm),
+            Some(Eddystone::Tlm(EddystoneTelemetry {
+                battery_voltage: Some(3000),
+                temperature: Some(26.5),
+                advertisement_count: 42,
+                uptime: 100,
+            }))
+        );
+        assert_eq!(
+            Eddystone::parse(&[0x20, 0x01]),
+            Some(Eddystone::EncryptedTlm)
+        );
+    }
+
+    #[test]
+    fn parse_ruuvi() {
+        let v5 = hex::decode("0512fc5394c37c0004fffc040cac364200cdcbb8334c884f").unwrap();
+        let ruuvi = RuuviData::parse(&v5).unwrap();
+        assert!((ruuvi.temperature.unwrap() - 24.3).abs() < 1e-9);
+        assert!((ruuvi.humidity.unwrap() - 53.49).abs() < 1e-9);
+        assert_eq!(ruuvi.pressure, Some(100044));
+        assert_eq!(ruuvi.acceleration, Some((4, -4, 1036)));
+        assert_eq!(ruuvi.battery_voltage, Some(2977));
+        assert_eq!(ruuvi.tx_power, Some(4));
+        assert_eq!(ruuvi.movement_counter, Some(66));
+        assert_eq!(ruuvi.measurement_sequence, Some(205));
+        assert_eq!(ruuvi.mac_address, Some(String::from("CB:B8:33:4C:88:4F")));
+
+        let v3 = hex::decode("03291a1ece1efc18f94202ca0b53").unwrap();
+        let ruuvi = RuuviData::parse(&v3).unwrap();
+        assert!((ruuvi.temperature.unwrap() - 26.3).abs() < 1e-9);
+        assert!((ruuvi.humidity.unwrap() - 20.5).abs() < 1e-9);
+        assert_eq!(ruuvi.pressure, Some(102766));
+        assert_eq!(ruuvi.acceleration, Some((-1000, -1726, 714)));
+        assert_eq!(ruuvi.battery_voltage, Some(2899));
+    }
+
+    #[test]
+    fn parse_malformed_beacons() {
+        let ibeacon = ibeacon_data();
+        assert_eq!(IBeacon::parse(&ibeacon[..22]), None);
+        assert_eq!(IBeacon::parse(&[]), None);
+        assert_eq!(AltBeacon::parse(0x0118, &[0xBE, 0xAC, 0x00]), None);
+        assert_eq!(Beacon::from_manufacturer_data(0x0118, &ibeacon), None);
+
+        assert_eq!(Eddystone::parse(&[]), None);
+        assert_eq!(Eddystone::parse(&[0x00, 0xE8, 0x00]), None);
+        assert_eq!(Eddystone::parse(&[0x10, 0xEB]), None);
+        // Unknown URL scheme and a byte that is neither an expansion nor printable.
+        assert_eq!(Eddystone::parse(&[0x10, 0xEB, 0x04, b'a']), None);
+        assert_eq!(Eddystone::parse(&[0x10, 0xEB, 0x00, 0x7F]), None);
+        assert_eq!(Eddystone::parse(&[0x20, 0x00, 0x0B]), None);
+        assert_eq!(Eddystone::parse(&[0x40]), None);
+        assert_eq!(Beacon::from_service_data("180f", &[0x10, 0xEB, 0x00]), None);
+
+        assert_eq!(RuuviData::parse(&[]), None);
+        assert_eq!(RuuviData::parse(&[0x03, 0x29]), None);
+        assert_eq!(RuuviData::parse(&[0x05; 23]), None);
+        assert_eq!(RuuviData::parse(&[0x04; 24]), None);
+    }
+}
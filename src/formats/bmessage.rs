@@ -0,0 +1,296 @@
+use formats::vcard::{VCard, VCardName, VCardTelephone};
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageType {
+    Email,
+    SmsGsm,
+    SmsCdma,
+    Mms,
+    Im,
+}
+
+impl MessageType {
+    // Names used by obexd, bMessage itself uses upper case names such as "SMS_GSM".
+    pub fn from_name(name: &str) -> Option<MessageType> {
+        match name {
+            "email" => Some(MessageType::Email),
+            "sms-gsm" => Some(MessageType::SmsGsm),
+            "sms-cdma" => Some(MessageType::SmsCdma),
+            "mms" => Some(MessageType::Mms),
+            "im" => Some(MessageType::Im),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            MessageType::Email => "email",
+            MessageType::SmsGsm => "sms-gsm",
+            MessageType::SmsCdma => "sms-cdma",
+            MessageType::Mms => "mms",
+            MessageType::Im => "im",
+        }
+    }
+}
+
+// A message in the bMessage format of MAP, as downloaded by BluetoothOBEXMessage::get
+// and uploaded by BluetoothOBEXMessageAccess::push_message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BMessage {
+    pub read: bool,
+    pub message_type: MessageType,
+    // Folder of the message, e.g. "telecom/msg/inbox", empty for pushed messages.
+    pub folder: String,
+    pub originators: Vec<VCard>,
+    pub recipients: Vec<VCard>,
+    // Body encoding, e.g. "8BIT" for emails or "G-7BIT" for SMS PDUs.
+    pub encoding: Option<String>,
+    pub charset: Option<String>,
+    pub language: Option<String>,
+    pub body: String,
+}
+
+impl BMessage {
+    pub fn new(message_type: MessageType, body: &str) -> BMessage {
+        BMessage {
+            read: false,
+            message_type: message_type,
+            folder: String::new(),
+            originators: Vec::new(),
+            recipients: Vec::new(),
+            encoding: None,
+            charset: Some(String::from("UTF-8")),
+            language: None,
+            body: String::from(body),
+        }
+    }
+
+    // A GSM text message to the phone number `recipient`, ready for push_message.
+    pub fn sms(recipient: &str, text: &str) -> BMessage {
+        let mut message = BMessage::new(MessageType::SmsGsm, text);
+        message.recipients.push(VCard {
+            version: String::from("2.1"),
+            name: Some(VCardName::default()),
+            telephones: vec![VCardTelephone {
+                number: String::from(recipient),
+                types: Vec::new(),
+            }],
+            ..VCard::default()
+        });
+        message
+    }
+
+    pub fn parse(text: &str) -> Result<BMessage, Box<Error>> {
+        let mut message = BMessage::new(MessageType::SmsGsm, "");
+        message.charset = None;
+        let mut seen_message = false;
+        let mut envelope_depth = 0;
+        let mut vcard: Option<String> = None;
+        let mut body: Option<Vec<&str>> = None;
+        let mut parts: Vec<String> = Vec::new();
+        for line in text.split('\n') {
+            let line = line.trim_end_matches('\r');
+            if let Some(ref mut lines) = body {
+                if line != "END:MSG" {
+                    lines.push(line);
+                    continue;
+                }
+            }
+            if line == "END:MSG" {
+                if let Some(lines) = body.take() {
+                    parts.push(lines.join("\r\n"));
+                }
+                continue;
+            }
+            if let Some(mut card) = vcard.take() {
+                card.push_str(line);
+                card.push_str("\r\n");
+                if line != "END:VCARD" {
+                    vcard = Some(card);
+                    continue;
+                }
+                let card = VCard::parse(&card)?;
+                match envelope_depth {
+                    0 => message.originators.push(card),
+                    1 => message.recipients.push(card),
+                    // vCards of nested envelopes belong to forwarded messages.
+                    _ => {}
+                }
+                continue;
+            }
+            let (name, value) = match line.find(':') {
+                Some(colon) => (&line[..colon], line[colon + 1..].trim()),
+                None => continue,
+            };
+            match (name, value) {
+                ("BEGIN", "BMSG") => seen_message = true,
+                ("BEGIN", "VCARD") => vcard = Some(String::from("BEGIN:VCARD\r\n")),
+                ("BEGIN", "BENV") => envelope_depth += 1,
+                ("BEGIN", "MSG") => body = Some(Vec::new()),
+                ("STATUS", status) => message.read = status.eq_ignore_ascii_case("READ"),
+                ("TYPE", message_type) => match type_from_name(message_type) {
+                    Some(message_type) => message.message_type = message_type,
+                    None => {
+                        return Err(Box::from(format!("Unknown message type: {}", message_type)))
+                    }
+                },
+                ("FOLDER", folder) => message.folder = String::from(folder),
+                ("ENCODING", encoding) => message.encoding = Some(String::from(encoding)),
+                ("CHARSET", charset) => message.charset = Some(String::from(charset)),
+                ("LANGUAGE", language) => message.language = Some(String::from(language)),
+                _ => {}
+            }
+        }
+        if !seen_message {
+            return Err(Box::from("No bMessage found"));
+        }
+        message.body = parts.join("\r\n");
+        Ok(message)
+    }
+
+    pub fn read_file(path: &str) -> Result<BMessage, Box<Error>> {
+        let mut file = File::open(path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        BMessage::parse(&String::from_utf8_lossy(&bytes))
+    }
+
+    pub fn write_file(&self, path: &str) -> Result<(), Box<Error>> {
+        let mut file = File::create(path)?;
+        file.write_all(self.to_string().as_bytes())?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for BMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BEGIN:BMSG\r\nVERSION:1.0\r\n")?;
+        write!(
+            f,
+            "STATUS:{}\r\n",
+            if self.read { "READ" } else { "UNREAD" }
+        )?;
+        write!(f, "TYPE:{}\r\n", type_name(self.message_type))?;
+        write!(f, "FOLDER:{}\r\n", self.folder)?;
+        for originator in self.originators.iter() {
+            write!(f, "{}", originator)?;
+        }
+        write!(f, "BEGIN:BENV\r\n")?;
+        for recipient in self.recipients.iter() {
+            write!(f, "{}", recipient)?;
+        }
+        write!(f, "BEGIN:BBODY\r\n")?;
+        if let Some(ref encoding) = self.encoding {
+            write!(f, "ENCODING:{}\r\n", encoding)?;
+        }
+        if let Some(ref charset) = self.charset {
+            write!(f, "CHARSET:{}\r\n", charset)?;
+        }
+        if let Some(ref language) = self.language {
+            write!(f, "LANGUAGE:{}\r\n", language)?;
+        }
+        // LENGTH covers the body including the BEGIN:MSG and END:MSG lines.
+        let body = self.body.replace("\r\n", "\n").replace('\n', "\r\n");
+        let length = "BEGIN:MSG\r\n".len() + body.len() + "\r\nEND:MSG\r\n".len();
+        write!(f, "LENGTH:{}\r\n", length)?;
+        write!(f, "BEGIN:MSG\r\n{}\r\nEND:MSG\r\n", body)?;
+        write!(f, "END:BBODY\r\nEND:BENV\r\nEND:BMSG\r\n")
+    }
+}
+
+fn type_name(message_type: MessageType) -> &'static str {
+    match message_type {
+        MessageType::Email => "EMAIL",
+        MessageType::SmsGsm => "SMS_GSM",
+        MessageType::SmsCdma => "SMS_CDMA",
+        MessageType::Mms => "MMS",
+        MessageType::Im => "IM",
+    }
+}
+
+fn type_from_name(name: &str) -> Option<MessageType> {
+    match name {
+        "EMAIL" => Some(MessageType::Email),
+        "SMS_GSM" => Some(MessageType::SmsGsm),
+        "SMS_CDMA" => Some(MessageType::SmsCdma),
+        "MMS" => Some(MessageType::Mms),
+        "IM" => Some(MessageType::Im),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use formats::vcard::VCardBuilder;
+
+    #[test]
+    fn sms_round_trip() {
+        let message = BMessage::sms("+123456789", "Hello\r\nWorld");
+        let text = message.to_string();
+        assert!(text.contains("TYPE:SMS_GSM\r\n"));
+        assert!(text.contains("LENGTH:34\r\nBEGIN:MSG\r\nHello\r\nWorld\r\nEND:MSG\r\n"));
+        assert_eq!(BMessage::parse(&text).unwrap(), message);
+    }
+
+    #[test]
+    fn email_round_trip() {
+        let mut message = BMessage::new(MessageType::Email, "Subject: Hi\r\n\r\nBody");
+        message.read = true;
+        message.folder = String::from("telecom/msg/inbox");
+        message.encoding = Some(String::from("8BIT"));
+        message.language = Some(String::from("English"));
+        message.originators.push(
+            VCardBuilder::new("Alice")
+                .email("alice@example.com", &[])
+                .build(),
+        );
+        message.recipients.push(
+            VCardBuilder::new("Bob")
+                .email("bob@example.com", &[])
+                .build(),
+        );
+        assert_eq!(BMessage::parse(&message.to_string()).unwrap(), message);
+    }
+
+    #[test]
+    fn parse_nested_envelopes() {
+        let text = "BEGIN:BMSG\r\n\
+                    TYPE:MMS\r\n\
+                    BEGIN:BENV\r\n\
+                    BEGIN:VCARD\r\nFN:Recipient\r\nEND:VCARD\r\n\
+                    BEGIN:BENV\r\n\
+                    BEGIN:VCARD\r\nFN:Forwarded\r\nEND:VCARD\r\n\
+                    BEGIN:BBODY\r\n\
+                    BEGIN:MSG\r\nfirst\r\nEND:MSG\r\n\
+                    BEGIN:MSG\r\nsecond\r\nEND:MSG\r\n\
+                    END:BBODY\r\nEND:BENV\r\nEND:BENV\r\nEND:BMSG\r\n";
+        let message = BMessage::parse(text).unwrap();
+        assert_eq!(message.message_type, MessageType::Mms);
+        assert!(message.originators.is_empty());
+        assert_eq!(message.recipients.len(), 1);
+        assert_eq!(
+            message.recipients[0].formatted_name,
+            Some(String::from("Recipient"))
+        );
+        assert_eq!(message.body, "first\r\nsecond");
+    }
+
+    #[test]
+    fn parse_malformed_bmessages() {
+        assert!(BMessage::parse("").is_err());
+        assert!(BMessage::parse("BEGIN:MSG\r\nbody\r\nEND:MSG\r\n").is_err());
+        assert!(BMessage::parse("BEGIN:BMSG\r\nTYPE:FAX\r\nEND:BMSG\r\n").is_err());
+        // An END:VCARD without an open vCard is ignored.
+        let message = BMessage::parse("BEGIN:BMSG\r\nEND:VCARD\r\nEND:BMSG\r\n").unwrap();
+        assert!(message.originators.is_empty());
+        // An unterminated vCard swallows the rest of the message.
+        let message = BMessage::parse("BEGIN:BMSG\r\nBEGIN:VCARD\r\nFN:Open\r\n").unwrap();
+        assert!(message.originators.is_empty());
+    }
+}
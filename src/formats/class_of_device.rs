@@ -0,0 +1,374 @@
+use std::fmt;
+
+// https://www.bluetooth.com/specifications/assigned-numbers/ (Baseband)
+static SERVICE_CLASS_NAMES: &'static [(&'static str, u32)] = &[
+    (
+        "Limited Discoverable Mode",
+        ServiceClasses::LIMITED_DISCOVERABLE_MODE.0,
+    ),
+    ("LE Audio", ServiceClasses::LE_AUDIO.0),
+    ("Positioning", ServiceClasses::POSITIONING.0),
+    ("Networking", ServiceClasses::NETWORKING.0),
+    ("Rendering", ServiceClasses::RENDERING.0),
+    ("Capturing", ServiceClasses::CAPTURING.0),
+    ("Object Transfer", ServiceClasses::OBJECT_TRANSFER.0),
+    ("Audio", ServiceClasses::AUDIO.0),
+    ("Telephony", ServiceClasses::TELEPHONY.0),
+    ("Information", ServiceClasses::INFORMATION.0),
+];
+
+static COMPUTER_MINOR_CLASSES: &'static [&'static str] = &[
+    "Uncategorized",
+    "Desktop workstation",
+    "Server-class computer",
+    "Laptop",
+    "Handheld PC/PDA",
+    "Palm-size PC/PDA",
+    "Wearable computer",
+    "Tablet",
+];
+
+static PHONE_MINOR_CLASSES: &'static [&'static str] = &[
+    "Uncategorized",
+    "Cellular",
+    "Cordless",
+    "Smartphone",
+    "Wired modem or voice gateway",
+    "Common ISDN access",
+];
+
+static NETWORK_LOAD_FACTORS: &'static [&'static str] = &[
+    "Fully available",
+    "1% to 17% utilized",
+    "17% to 33% utilized",
+    "33% to 50% utilized",
+    "50% to 67% utilized",
+    "67% to 83% utilized",
+    "83% to 99% utilized",
+    "No service available",
+];
+
+static AUDIO_VIDEO_MINOR_CLASSES: &'static [&'static str] = &[
+    "Uncategorized",
+    "Wearable Headset Device",
+    "Hands-free Device",
+    "Reserved",
+    "Microphone",
+    "Loudspeaker",
+    "Headphones",
+    "Portable Audio",
+    "Car audio",
+    "Set-top box",
+    "HiFi Audio Device",
+    "VCR",
+    "Video Camera",
+    "Camcorder",
+    "Video Monitor",
+    "Video Display and Loudspeaker",
+    "Video Conferencing",
+    "Reserved",
+    "Gaming/Toy",
+];
+
+static PERIPHERAL_INPUT_CLASSES: &'static [&'static str] = &[
+    "",
+    "Keyboard",
+    "Pointing device",
+    "Combo keyboard/pointing device",
+];
+
+static PERIPHERAL_MINOR_CLASSES: &'static [&'static str] = &[
+    "",
+    "Joystick",
+    "Gamepad",
+    "Remote control",
+    "Sensing device",
+    "Digitizer tablet",
+    "Card Reader",
+    "Digital Pen",
+    "Handheld scanner",
+    "Handheld gestural input device",
+];
+
+static IMAGING_MINOR_CLASSES: &'static [(&'static str, u8)] = &[
+    ("Display", 0x04),
+    ("Camera", 0x08),
+    ("Scanner", 0x10),
+    ("Printer", 0x20),
+];
+
+static WEARABLE_MINOR_CLASSES: &'static [&'static str] = &[
+    "Uncategorized",
+    "Wristwatch",
+    "Pager",
+    "Jacket",
+    "Helmet",
+    "Glasses",
+    "Pin",
+];
+
+static TOY_MINOR_CLASSES: &'static [&'static str] = &[
+    "Uncategorized",
+    "Robot",
+    "Vehicle",
+    "Doll/Action figure",
+    "Controller",
+    "Game",
+];
+
+static HEALTH_MINOR_CLASSES: &'static [&'static str] = &[
+    "Undefined",
+    "Blood Pressure Monitor",
+    "Thermometer",
+    "Weighing Scale",
+    "Glucose Meter",
+    "Pulse Oximeter",
+    "Heart/Pulse Rate Monitor",
+    "Health Data Display",
+    "Step Counter",
+    "Body Composition Analyzer",
+    "Peak Flow Monitor",
+    "Medication Monitor",
+    "Knee Prosthesis",
+    "Ankle Prosthesis",
+    "Generic Health Manager",
+    "Personal Mobility Device",
+];
+
+// Major service classes, bits 13 to 23 of the Class of Device.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ServiceClasses(u32);
+
+impl ServiceClasses {
+    pub const LIMITED_DISCOVERABLE_MODE: ServiceClasses = ServiceClasses(1 << 13);
+    pub const LE_AUDIO: ServiceClasses = ServiceClasses(1 << 14);
+    pub const POSITIONING: ServiceClasses = ServiceClasses(1 << 16);
+    pub const NETWORKING: ServiceClasses = ServiceClasses(1 << 17);
+    pub const RENDERING: ServiceClasses = ServiceClasses(1 << 18);
+    pub const CAPTURING: ServiceClasses = ServiceClasses(1 << 19);
+    pub const OBJECT_TRANSFER: ServiceClasses = ServiceClasses(1 << 20);
+    pub const AUDIO: ServiceClasses = ServiceClasses(1 << 21);
+    pub const TELEPHONY: ServiceClasses = ServiceClasses(1 << 22);
+    pub const INFORMATION: ServiceClasses = ServiceClasses(1 << 23);
+
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(&self, other: ServiceClasses) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        SERVICE_CLASS_NAMES
+            .iter()
+            .filter(|&&(_, bit)| self.0 & bit != 0)
+            .map(|&(name, _)| name)
+            .collect()
+    }
+}
+
+impl fmt::Debug for ServiceClasses {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ServiceClasses({:?})", self.names())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MajorDeviceClass {
+    Miscellaneous,
+    Computer,
+    Phone,
+    NetworkAccessPoint,
+    AudioVideo,
+    Peripheral,
+    Imaging,
+    Wearable,
+    Toy,
+    Health,
+    Uncategorized,
+    Reserved(u8),
+}
+
+impl MajorDeviceClass {
+    pub fn from_u8(value: u8) -> MajorDeviceClass {
+        match value {
+            0x00 => MajorDeviceClass::Miscellaneous,
+            0x01 => MajorDeviceClass::Computer,
+            0x02 => MajorDeviceClass::Phone,
+            0x03 => MajorDeviceClass::NetworkAccessPoint,
+            0x04 => MajorDeviceClass::AudioVideo,
+            0x05 => MajorDeviceClass::Peripheral,
+            0x06 => MajorDeviceClass::Imaging,
+            0x07 => MajorDeviceClass::Wearable,
+            0x08 => MajorDeviceClass::Toy,
+            0x09 => MajorDeviceClass::Health,
+            0x1F => MajorDeviceClass::Uncategorized,
+            other => MajorDeviceClass::Reserved(other),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            MajorDeviceClass::Miscellaneous => "Miscellaneous",
+            MajorDeviceClass::Computer => "Computer",
+            MajorDeviceClass::Phone => "Phone",
+            MajorDeviceClass::NetworkAccessPoint => "LAN/Network Access point",
+            MajorDeviceClass::AudioVideo => "Audio/Video",
+            MajorDeviceClass::Peripheral => "Peripheral",
+            MajorDeviceClass::Imaging => "Imaging",
+            MajorDeviceClass::Wearable => "Wearable",
+            MajorDeviceClass::Toy => "Toy",
+            MajorDeviceClass::Health => "Health",
+            MajorDeviceClass::Uncategorized => "Uncategorized",
+            MajorDeviceClass::Reserved(_) => "Reserved",
+        }
+    }
+}
+
+// Class of Device as reported by the Class property of adapters and devices:
+// major service classes, major device class and a minor class whose meaning
+// depends on the major one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct ClassOfDevice(u32);
+
+impl ClassOfDevice {
+    pub fn new(value: u32) -> ClassOfDevice {
+        ClassOfDevice(value & 0x00FF_FFFF)
+    }
+
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+
+    pub fn service_classes(&self) -> ServiceClasses {
+        ServiceClasses(self.0 & 0x00FF_E000)
+    }
+
+    pub fn major_device_class(&self) -> MajorDeviceClass {
+        MajorDeviceClass::from_u8(((self.0 >> 8) & 0x1F) as u8)
+    }
+
+    // Raw 6 bit minor device class.
+    pub fn minor_device_class(&self) -> u8 {
+        ((self.0 >> 2) & 0x3F) as u8
+    }
+
+    pub fn minor_device_class_name(&self) -> Option<String> {
+        let minor = self.minor_device_class();
+        let lookup =
+            |table: &[&'static str], index: u8| table.get(index as usize).map(|n| n.to_string());
+        match self.major_device_class() {
+            MajorDeviceClass::Computer => lookup(COMPUTER_MINOR_CLASSES, minor),
+            MajorDeviceClass::Phone => lookup(PHONE_MINOR_CLASSES, minor),
+            MajorDeviceClass::NetworkAccessPoint => lookup(NETWORK_LOAD_FACTORS, minor >> 3),
+            MajorDeviceClass::AudioVideo => lookup(AUDIO_VIDEO_MINOR_CLASSES, minor),
+            MajorDeviceClass::Peripheral => {
+                let names: Vec<&str> = [
+                    PERIPHERAL_INPUT_CLASSES[(minor >> 4) as usize],
+                    PERIPHERAL_MINOR_CLASSES
+                        .get((minor & 0x0F) as usize)
+                        .cloned()
+                        .unwrap_or(""),
+                ]
+                .iter()
+                .filter(|n| !n.is_empty())
+                .cloned()
+                .collect();
+                if names.is_empty() {
+                    Some(String::from("Uncategorized"))
+                } else {
+                    Some(names.join(", "))
+                }
+            }
+            MajorDeviceClass::Imaging => {
+                let names: Vec<&str> = IMAGING_MINOR_CLASSES
+                    .iter()
+                    .filter(|&&(_, bit)| minor & bit != 0)
+                    .map(|&(name, _)| name)
+                    .collect();
+                if names.is_empty() {
+                    None
+                } else {
+                    Some(names.join(", "))
+                }
+            }
+            MajorDeviceClass::Wearable => lookup(WEARABLE_MINOR_CLASSES, minor),
+            MajorDeviceClass::Toy => lookup(TOY_MINOR_CLASSES, minor),
+            MajorDeviceClass::Health => lookup(HEALTH_MINOR_CLASSES, minor),
+            _ => None,
+        }
+    }
+}
+
+impl From<u32> for ClassOfDevice {
+    fn from(value: u32) -> ClassOfDevice {
+        ClassOfDevice::new(value)
+    }
+}
+
+impl fmt::Display for ClassOfDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{}", self.major_device_class().name()));
+        if let Some(minor) = self.minor_device_class_name() {
+            try!(write!(f, " ({})", minor));
+        }
+        let services = self.service_classes().names();
+        if !services.is_empty() {
+            try!(write!(f, " [{}]", services.join(", ")));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClassOfDevice, MajorDeviceClass, ServiceClasses};
+
+    #[test]
+    fn smartphone() {
+        let class = ClassOfDevice::new(0x5A020C);
+        assert_eq!(class.major_device_class(), MajorDeviceClass::Phone);
+        assert_eq!(class.minor_device_class(), 3);
+        assert_eq!(
+            class.minor_device_class_name(),
+            Some(String::from("Smartphone"))
+        );
+        let services = class.service_classes();
+        assert!(services.contains(ServiceClasses::NETWORKING));
+        assert!(services.contains(ServiceClasses::CAPTURING));
+        assert!(services.contains(ServiceClasses::OBJECT_TRANSFER));
+        assert!(services.contains(ServiceClasses::TELEPHONY));
+        assert!(!services.contains(ServiceClasses::AUDIO));
+        assert_eq!(services.bits(), 0x5A0000);
+    }
+
+    #[test]
+    fn headset() {
+        let class = ClassOfDevice::new(0x240404);
+        assert_eq!(class.major_device_class(), MajorDeviceClass::AudioVideo);
+        assert_eq!(
+            class.to_string(),
+            "Audio/Video (Wearable Headset Device) [Rendering, Audio]"
+        );
+    }
+
+    #[test]
+    fn peripheral_and_reserved_bits() {
+        assert_eq!(
+            ClassOfDevice::new(0x002540).minor_device_class_name(),
+            Some(String::from("Keyboard"))
+        );
+        // Only the low 24 bits belong to the Class of Device.
+        assert_eq!(ClassOfDevice::new(0xFF00_0100).value(), 0x000100);
+        assert_eq!(
+            ClassOfDevice::new(0x001500).major_device_class(),
+            MajorDeviceClass::Reserved(0x15)
+        );
+        assert!(ClassOfDevice::new(0x000100).service_classes().is_empty());
+    }
+}
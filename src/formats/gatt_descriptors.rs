@@ -0,0 +1,710 @@
+use bluetooth_utils::{self, check_length, read_u16};
+use formats::ieee11073;
+use std::error::Error;
+use std::fmt;
+
+// https://www.bluetooth.com/specifications/gatt/descriptors
+pub static CHARACTERISTIC_EXTENDED_PROPERTIES_UUID: &'static str =
+    "00002900-0000-1000-8000-00805f9b34fb";
+pub static CHARACTERISTIC_USER_DESCRIPTION_UUID: &'static str =
+    "00002901-0000-1000-8000-00805f9b34fb";
+pub static CLIENT_CHARACTERISTIC_CONFIGURATION_UUID: &'static str =
+    "00002902-0000-1000-8000-00805f9b34fb";
+pub static CHARACTERISTIC_PRESENTATION_FORMAT_UUID: &'static str =
+    "00002904-0000-1000-8000-00805f9b34fb";
+pub static VALID_RANGE_UUID: &'static str = "00002906-0000-1000-8000-00805f9b34fb";
+pub static REPORT_REFERENCE_UUID: &'static str = "00002908-0000-1000-8000-00805f9b34fb";
+pub static ENVIRONMENTAL_SENSING_CONFIGURATION_UUID: &'static str =
+    "0000290b-0000-1000-8000-00805f9b34fb";
+pub static ENVIRONMENTAL_SENSING_MEASUREMENT_UUID: &'static str =
+    "0000290c-0000-1000-8000-00805f9b34fb";
+pub static ENVIRONMENTAL_SENSING_TRIGGER_SETTING_UUID: &'static str =
+    "0000290d-0000-1000-8000-00805f9b34fb";
+
+fn read_u24(value: &[u8], offset: usize) -> u32 {
+    value[offset] as u32 | (value[offset + 1] as u32) << 8 | (value[offset + 2] as u32) << 16
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ClientCharacteristicConfiguration {
+    pub notifications: bool,
+    pub indications: bool,
+}
+
+impl ClientCharacteristicConfiguration {
+    pub fn parse(value: &[u8]) -> Result<ClientCharacteristicConfiguration, Box<Error>> {
+        try!(check_length(
+            "Client Characteristic Configuration",
+            value,
+            2
+        ));
+        let bits = read_u16(value, 0);
+        Ok(ClientCharacteristicConfiguration {
+            notifications: bits & 0x0001 != 0,
+            indications: bits & 0x0002 != 0,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bits = 0u8;
+        if self.notifications {
+            bits |= 0x01;
+        }
+        if self.indications {
+            bits |= 0x02;
+        }
+        vec![bits, 0]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ExtendedProperties {
+    pub reliable_write: bool,
+    pub writable_auxiliaries: bool,
+}
+
+impl ExtendedProperties {
+    pub fn parse(value: &[u8]) -> Result<ExtendedProperties, Box<Error>> {
+        try!(check_length("Characteristic Extended Properties", value, 2));
+        let bits = read_u16(value, 0);
+        Ok(ExtendedProperties {
+            reliable_write: bits & 0x0001 != 0,
+            writable_auxiliaries: bits & 0x0002 != 0,
+        })
+    }
+}
+
+pub fn parse_user_description(value: &[u8]) -> String {
+    String::from_utf8_lossy(value).into_owned()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PresentationFormat {
+    pub format: u8,
+    pub exponent: i8,
+    pub unit: u16,
+    pub namespace: u8,
+    pub description: u16,
+}
+
+impl PresentationFormat {
+    pub fn parse(value: &[u8]) -> Result<PresentationFormat, Box<Error>> {
+        try!(check_length("Characteristic Presentation Format", value, 7));
+        Ok(PresentationFormat {
+            format: value[0],
+            exponent: value[1] as i8,
+            unit: read_u16(value, 2),
+            namespace: value[4],
+            description: read_u16(value, 5),
+        })
+    }
+
+    pub fn format_name(&self) -> Option<&'static str> {
+        format_name(self.format)
+    }
+
+    pub fn unit_name(&self) -> Option<&'static str> {
+        unit_name(self.unit)
+    }
+
+    pub fn unit_symbol(&self) -> Option<&'static str> {
+        unit_symbol(self.unit)
+    }
+
+    // Decodes a characteristic value of this format and applies the exponent,
+    // value = raw * 10^exponent. Returns None for non-numeric formats or short input.
+    pub fn decode_value(&self, value: &[u8]) -> Option<f64> {
+        let raw = match decode_number(self.format, value) {
+            Some(raw) => raw,
+            None => return None,
+        };
+        Some(raw * 10f64.powi(self.exponent as i32))
+    }
+}
+
+fn decode_number(format: u8, value: &[u8]) -> Option<f64> {
+    let size = match format {
+        0x01..=0x04 | 0x0C => 1,
+        0x05 | 0x06 | 0x0D | 0x0E | 0x16 => 2,
+        0x07 | 0x0F => 3,
+        0x08 | 0x10 | 0x14 | 0x17 => 4,
+        0x09 | 0x11 => 6,
+        0x0A | 0x12 | 0x15 => 8,
+        _ => return None,
+    };
+    if value.len() < size {
+        return None;
+    }
+    let mut unsigned: u64 = 0;
+    for (i, b) in value[..size].iter().enumerate() {
+        unsigned |= (*b as u64) << (8 * i);
+    }
+    let number = match format {
+        // boolean, 2bit, nibble
+        0x01 => (unsigned & 0x01) as f64,
+        0x02 => (unsigned & 0x03) as f64,
+        0x03 => (unsigned & 0x0f) as f64,
+        0x05 => (unsigned & 0x0fff) as f64,
+        0x04 | 0x06 | 0x07 | 0x08 | 0x09 | 0x0A => unsigned as f64,
+        0x0D => sign_extend(unsigned & 0x0fff, 12) as f64,
+        0x0C | 0x0E | 0x0F | 0x10 | 0x11 | 0x12 => sign_extend(unsigned, size * 8) as f64,
+        0x14 => f32::from_bits(unsigned as u32) as f64,
+        0x15 => f64::from_bits(unsigned),
+        0x16 => ieee11073::sfloat_to_f64(unsigned as u16),
+        0x17 => ieee11073::float_to_f64(unsigned as u32),
+        _ => return None,
+    };
+    Some(number)
+}
+
+fn sign_extend(value: u64, bits: usize) -> i64 {
+    let shift = 64 - bits;
+    ((value << shift) as i64) >> shift
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidRange {
+    pub lower: Vec<u8>,
+    pub upper: Vec<u8>,
+}
+
+impl ValidRange {
+    // The bounds use the characteristic's own format, so they are split evenly
+    // and left for the caller (or `PresentationFormat::decode_value`) to interpret.
+    pub fn parse(value: &[u8]) -> Result<ValidRange, Box<Error>> {
+        if value.is_empty() || value.len() % 2 != 0 {
+            return Err(Box::from(format!(
+                "Valid Range needs an even, non-zero length, got {}",
+                value.len()
+            )));
+        }
+        let half = value.len() / 2;
+        Ok(ValidRange {
+            lower: value[..half].to_vec(),
+            upper: value[half..].to_vec(),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportType {
+    Input,
+    Output,
+    Feature,
+    Unknown(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReportReference {
+    pub report_id: u8,
+    pub report_type: ReportType,
+}
+
+impl ReportReference {
+    pub fn parse(value: &[u8]) -> Result<ReportReference, Box<Error>> {
+        try!(check_length("Report Reference", value, 2));
+        Ok(ReportReference {
+            report_id: value[0],
+            report_type: match value[1] {
+                0x01 => ReportType::Input,
+                0x02 => ReportType::Output,
+                0x03 => ReportType::Feature,
+                other => ReportType::Unknown(other),
+            },
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerLogic {
+    And,
+    Or,
+    Unknown(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EnvironmentalSensingConfiguration {
+    pub trigger_logic: TriggerLogic,
+}
+
+impl EnvironmentalSensingConfiguration {
+    pub fn parse(value: &[u8]) -> Result<EnvironmentalSensingConfiguration, Box<Error>> {
+        try!(check_length(
+            "Environmental Sensing Configuration",
+            value,
+            1
+        ));
+        Ok(EnvironmentalSensingConfiguration {
+            trigger_logic: match value[0] {
+                0x00 => TriggerLogic::And,
+                0x01 => TriggerLogic::Or,
+                other => TriggerLogic::Unknown(other),
+            },
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EnvironmentalSensingMeasurement {
+    pub flags: u16,
+    pub sampling_function: u8,
+    // Seconds, 24 bit.
+    pub measurement_period: u32,
+    // Seconds, 24 bit.
+    pub update_interval: u32,
+    pub application: u8,
+    // Percent in 0.5 steps, raw value.
+    pub measurement_uncertainty: u8,
+}
+
+impl EnvironmentalSensingMeasurement {
+    pub fn parse(value: &[u8]) -> Result<EnvironmentalSensingMeasurement, Box<Error>> {
+        try!(check_length("Environmental Sensing Measurement", value, 11));
+        Ok(EnvironmentalSensingMeasurement {
+            flags: read_u16(value, 0),
+            sampling_function: value[2],
+            measurement_period: read_u24(value, 3),
+            update_interval: read_u24(value, 6),
+            application: value[9],
+            measurement_uncertainty: value[10],
+        })
+    }
+
+    pub fn sampling_function_name(&self) -> &'static str {
+        match self.sampling_function {
+            0x00 => "Unspecified",
+            0x01 => "Instantaneous",
+            0x02 => "Arithmetic Mean",
+            0x03 => "RMS",
+            0x04 => "Maximum",
+            0x05 => "Minimum",
+            0x06 => "Accumulated",
+            0x07 => "Count",
+            _ => "Reserved",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnvironmentalSensingTriggerSetting {
+    pub condition: u8,
+    // Time interval (24 bit seconds) or a value in the characteristic's format,
+    // depending on `condition`.
+    pub operand: Vec<u8>,
+}
+
+impl EnvironmentalSensingTriggerSetting {
+    pub fn parse(value: &[u8]) -> Result<EnvironmentalSensingTriggerSetting, Box<Error>> {
+        try!(check_length(
+            "Environmental Sensing Trigger Setting",
+            value,
+            1
+        ));
+        Ok(EnvironmentalSensingTriggerSetting {
+            condition: value[0],
+            operand: value[1..].to_vec(),
+        })
+    }
+
+    pub fn condition_name(&self) -> &'static str {
+        match self.condition {
+            0x00 => "Trigger inactive",
+            0x01 => "Fixed time interval",
+            0x02 => "No less than time interval",
+            0x03 => "Value changed",
+            0x04 => "Less than value",
+            0x05 => "Less than or equal to value",
+            0x06 => "Greater than value",
+            0x07 => "Greater than or equal to value",
+            0x08 => "Equal to value",
+            0x09 => "Not equal to value",
+            _ => "Reserved",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DescriptorValue {
+    ExtendedProperties(ExtendedProperties),
+    UserDescription(String),
+    ClientCharacteristicConfiguration(ClientCharacteristicConfiguration),
+    PresentationFormat(PresentationFormat),
+    ValidRange(ValidRange),
+    ReportReference(ReportReference),
+    EnvironmentalSensingConfiguration(EnvironmentalSensingConfiguration),
+    EnvironmentalSensingMeasurement(EnvironmentalSensingMeasurement),
+    EnvironmentalSensingTriggerSetting(EnvironmentalSensingTriggerSetting),
+    Unknown(Vec<u8>),
+}
+
+impl DescriptorValue {
+    pub fn decode(uuid: &str, value: &[u8]) -> Result<DescriptorValue, Box<Error>> {
+        let is = |known: &str| bluetooth_utils::uuid_matches(uuid, known);
+        let decoded = if is(CHARACTERISTIC_EXTENDED_PROPERTIES_UUID) {
+            DescriptorValue::ExtendedProperties(try!(ExtendedProperties::parse(value)))
+        } else if is(CHARACTERISTIC_USER_DESCRIPTION_UUID) {
+            DescriptorValue::UserDescription(parse_user_description(value))
+        } else if is(CLIENT_CHARACTERISTIC_CONFIGURATION_UUID) {
+            DescriptorValue::ClientCharacteristicConfiguration(try!(
+                ClientCharacteristicConfiguration::parse(value)
+            ))
+        } else if is(CHARACTERISTIC_PRESENTATION_FORMAT_UUID) {
+            DescriptorValue::PresentationFormat(try!(PresentationFormat::parse(value)))
+        } else if is(VALID_RANGE_UUID) {
+            DescriptorValue::ValidRange(try!(ValidRange::parse(value)))
+        } else if is(REPORT_REFERENCE_UUID) {
+            DescriptorValue::ReportReference(try!(ReportReference::parse(value)))
+        } else if is(ENVIRONMENTAL_SENSING_CONFIGURATION_UUID) {
+            DescriptorValue::EnvironmentalSensingConfiguration(try!(
+                EnvironmentalSensingConfiguration::parse(value)
+            ))
+        } else if is(ENVIRONMENTAL_SENSING_MEASUREMENT_UUID) {
+            DescriptorValue::EnvironmentalSensingMeasurement(try!(
+                EnvironmentalSensingMeasurement::parse(value)
+            ))
+        } else if is(ENVIRONMENTAL_SENSING_TRIGGER_SETTING_UUID) {
+            DescriptorValue::EnvironmentalSensingTriggerSetting(try!(
+                EnvironmentalSensingTriggerSetting::parse(value)
+            ))
+        } else {
+            DescriptorValue::Unknown(value.to_vec())
+        };
+        Ok(decoded)
+    }
+}
+
+// https://www.bluetooth.com/specifications/assigned-numbers/format-types
+pub fn format_name(format: u8) -> Option<&'static str> {
+    let name = match format {
+        0x01 => "boolean",
+        0x02 => "2bit",
+        0x03 => "nibble",
+        0x04 => "uint8",
+        0x05 => "uint12",
+        0x06 => "uint16",
+        0x07 => "uint24",
+        0x08 => "uint32",
+        0x09 => "uint48",
+        0x0A => "uint64",
+        0x0B => "uint128",
+        0x0C => "sint8",
+        0x0D => "sint12",
+        0x0E => "sint16",
+        0x0F => "sint24",
+        0x10 => "sint32",
+        0x11 => "sint48",
+        0x12 => "sint64",
+        0x13 => "sint128",
+        0x14 => "float32",
+        0x15 => "float64",
+        0x16 => "SFLOAT",
+        0x17 => "FLOAT",
+        0x18 => "duint16",
+        0x19 => "utf8s",
+        0x1A => "utf16s",
+        0x1B => "struct",
+        _ => return None,
+    };
+    Some(name)
+}
+
+// https://www.bluetooth.com/specifications/assigned-numbers/units
+static UNITS: &'static [(u16, &'static str, &'static str)] = &[
+    (0x2700, "unitless", ""),
+    (0x2701, "length (metre)", "m"),
+    (0x2702, "mass (kilogram)", "kg"),
+    (0x2703, "time (second)", "s"),
+    (0x2704, "electric current (ampere)", "A"),
+    (0x2705, "thermodynamic temperature (kelvin)", "K"),
+    (0x2706, "amount of substance (mole)", "mol"),
+    (0x2707, "luminous intensity (candela)", "cd"),
+    (0x2710, "area (square metres)", "m²"),
+    (0x2711, "volume (cubic metres)", "m³"),
+    (0x2712, "velocity (metres per second)", "m/s"),
+    (0x2713, "acceleration (metres per second squared)", "m/s²"),
+    (0x2714, "wavenumber (reciprocal metre)", "1/m"),
+    (0x2715, "density (kilogram per cubic metre)", "kg/m³"),
+    (
+        0x2716,
+        "surface density (kilogram per square metre)",
+        "kg/m²",
+    ),
+    (
+        0x2717,
+        "specific volume (cubic metre per kilogram)",
+        "m³/kg",
+    ),
+    (0x2718, "current density (ampere per square metre)", "A/m²"),
+    (0x2719, "magnetic field strength (ampere per metre)", "A/m"),
+    (
+        0x271A,
+        "amount concentration (mole per cubic metre)",
+        "mol/m³",
+    ),
+    (
+        0x271B,
+        "mass concentration (kilogram per cubic metre)",
+        "kg/m³",
+    ),
+    (0x271C, "luminance (candela per square metre)", "cd/m²"),
+    (0x271D, "refractive index", ""),
+    (0x271E, "relative permeability", ""),
+    (0x2720, "plane angle (radian)", "rad"),
+    (0x2721, "solid angle (steradian)", "sr"),
+    (0x2722, "frequency (hertz)", "Hz"),
+    (0x2723, "force (newton)", "N"),
+    (0x2724, "pressure (pascal)", "Pa"),
+    (0x2725, "energy (joule)", "J"),
+    (0x2726, "power (watt)", "W"),
+    (0x2727, "electric charge (coulomb)", "C"),
+    (0x2728, "electric potential difference (volt)", "V"),
+    (0x2729, "capacitance (farad)", "F"),
+    (0x272A, "electric resistance (ohm)", "Ω"),
+    (0x272B, "electric conductance (siemens)", "S"),
+    (0x272C, "magnetic flux (weber)", "Wb"),
+    (0x272D, "magnetic flux density (tesla)", "T"),
+    (0x272E, "inductance (henry)", "H"),
+    (0x272F, "Celsius temperature (degree Celsius)", "°C"),
+    (0x2730, "luminous flux (lumen)", "lm"),
+    (0x2731, "illuminance (lux)", "lx"),
+    (
+        0x2732,
+        "activity referred to a radionuclide (becquerel)",
+        "Bq",
+    ),
+    (0x2733, "absorbed dose (gray)", "Gy"),
+    (0x2734, "dose equivalent (sievert)", "Sv"),
+    (0x2735, "catalytic activity (katal)", "kat"),
+    (0x2740, "dynamic viscosity (pascal second)", "Pa·s"),
+    (0x2741, "moment of force (newton metre)", "N·m"),
+    (0x2742, "surface tension (newton per metre)", "N/m"),
+    (0x2743, "angular velocity (radian per second)", "rad/s"),
+    (
+        0x2744,
+        "angular acceleration (radian per second squared)",
+        "rad/s²",
+    ),
+    (0x2745, "heat flux density (watt per square metre)", "W/m²"),
+    (0x2746, "heat capacity (joule per kelvin)", "J/K"),
+    (
+        0x2747,
+        "specific heat capacity (joule per kilogram kelvin)",
+        "J/(kg·K)",
+    ),
+    (0x2748, "specific energy (joule per kilogram)", "J/kg"),
+    (
+        0x2749,
+        "thermal conductivity (watt per metre kelvin)",
+        "W/(m·K)",
+    ),
+    (0x274A, "energy density (joule per cubic metre)", "J/m³"),
+    (0x274B, "electric field strength (volt per metre)", "V/m"),
+    (
+        0x274C,
+        "electric charge density (coulomb per cubic metre)",
+        "C/m³",
+    ),
+    (
+        0x274D,
+        "surface charge density (coulomb per square metre)",
+        "C/m²",
+    ),
+    (
+        0x274E,
+        "electric flux density (coulomb per square metre)",
+        "C/m²",
+    ),
+    (0x274F, "permittivity (farad per metre)", "F/m"),
+    (0x2750, "permeability (henry per metre)", "H/m"),
+    (0x2751, "molar energy (joule per mole)", "J/mol"),
+    (0x2752, "molar entropy (joule per mole kelvin)", "J/(mol·K)"),
+    (0x2753, "exposure (coulomb per kilogram)", "C/kg"),
+    (0x2754, "absorbed dose rate (gray per second)", "Gy/s"),
+    (0x2755, "radiant intensity (watt per steradian)", "W/sr"),
+    (
+        0x2756,
+        "radiance (watt per square metre steradian)",
+        "W/(m²·sr)",
+    ),
+    (
+        0x2757,
+        "catalytic activity concentration (katal per cubic metre)",
+        "kat/m³",
+    ),
+    (0x2760, "time (minute)", "min"),
+    (0x2761, "time (hour)", "h"),
+    (0x2762, "time (day)", "d"),
+    (0x2763, "plane angle (degree)", "°"),
+    (0x2764, "plane angle (minute)", "′"),
+    (0x2765, "plane angle (second)", "″"),
+    (0x2766, "area (hectare)", "ha"),
+    (0x2767, "volume (litre)", "L"),
+    (0x2768, "mass (tonne)", "t"),
+    (0x2780, "pressure (bar)", "bar"),
+    (0x2781, "pressure (millimetre of mercury)", "mmHg"),
+    (0x2782, "length (ångström)", "Å"),
+    (0x2783, "length (nautical mile)", "NM"),
+    (0x2784, "area (barn)", "b"),
+    (0x2785, "velocity (knot)", "kn"),
+    (0x2786, "logarithmic radio quantity (neper)", "Np"),
+    (0x2787, "logarithmic radio quantity (bel)", "B"),
+    (0x27A0, "length (yard)", "yd"),
+    (0x27A1, "length (parsec)", "pc"),
+    (0x27A2, "length (inch)", "in"),
+    (0x27A3, "length (foot)", "ft"),
+    (0x27A4, "length (mile)", "mi"),
+    (0x27A5, "pressure (pound-force per square inch)", "psi"),
+    (0x27A6, "velocity (kilometre per hour)", "km/h"),
+    (0x27A7, "velocity (mile per hour)", "mph"),
+    (0x27A8, "angular velocity (revolution per minute)", "rpm"),
+    (0x27A9, "energy (gram calorie)", "cal"),
+    (0x27AA, "energy (kilogram calorie)", "kcal"),
+    (0x27AB, "energy (kilowatt hour)", "kWh"),
+    (
+        0x27AC,
+        "thermodynamic temperature (degree Fahrenheit)",
+        "°F",
+    ),
+    (0x27AD, "percentage", "%"),
+    (0x27AE, "per mille", "‰"),
+    (0x27AF, "period (beats per minute)", "bpm"),
+    (0x27B0, "electric charge (ampere hours)", "Ah"),
+    (0x27B1, "mass density (milligram per decilitre)", "mg/dL"),
+    (0x27B2, "mass density (millimole per litre)", "mmol/L"),
+    (0x27B3, "time (year)", "y"),
+    (0x27B4, "time (month)", "mo"),
+    (0x27B5, "concentration (count per cubic metre)", "1/m³"),
+    (0x27B6, "irradiance (watt per square metre)", "W/m²"),
+    (
+        0x27B7,
+        "milliliter (per kilogram per minute)",
+        "mL/(kg·min)",
+    ),
+    (0x27B8, "mass (pound)", "lb"),
+    (0x27B9, "metabolic equivalent", "MET"),
+    (0x27BA, "step (per minute)", "steps/min"),
+    (0x27BC, "stroke (per minute)", "strokes/min"),
+    (0x27BD, "pace (kilometre per minute)", "km/min"),
+    (0x27BE, "luminous efficacy (lumen per watt)", "lm/W"),
+    (0x27BF, "luminous energy (lumen hour)", "lm·h"),
+    (0x27C0, "luminous exposure (lux hour)", "lx·h"),
+    (0x27C1, "mass flow (gram per second)", "g/s"),
+    (0x27C2, "volume flow (litre per second)", "L/s"),
+    (0x27C3, "sound pressure (decibel)", "dB"),
+    (0x27C4, "parts per million", "ppm"),
+    (0x27C5, "parts per billion", "ppb"),
+];
+
+pub fn unit_name(unit: u16) -> Option<&'static str> {
+    UNITS
+        .iter()
+        .find(|&&(code, _, _)| code == unit)
+        .map(|&(_, name, _)| name)
+}
+
+pub fn unit_symbol(unit: u16) -> Option<&'static str> {
+    UNITS
+        .iter()
+        .find(|&&(code, _, _)| code == unit)
+        .map(|&(_, _, symbol)| symbol)
+}
+
+impl fmt::Display for PresentationFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} x10^{} {}",
+            self.format_name().unwrap_or("unknown format"),
+            self.exponent,
+            self.unit_name().unwrap_or("unknown unit")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DescriptorValue, PresentationFormat, ReportReference, ReportType, ValidRange,
+        CHARACTERISTIC_PRESENTATION_FORMAT_UUID,
+    };
+
+    // sint16 in hundredths of a degree Celsius, Bluetooth SIG namespace.
+    static TEMPERATURE_FORMAT: [u8; 7] = [0x0E, 0xFE, 0x2F, 0x27, 0x01, 0x00, 0x00];
+
+    #[test]
+    fn parse_presentation_format() {
+        let format = PresentationFormat::parse(&TEMPERATURE_FORMAT).unwrap();
+        assert_eq!(
+            format,
+            PresentationFormat {
+                format: 0x0E,
+                exponent: -2,
+                unit: 0x272F,
+                namespace: 0x01,
+                description: 0x0000,
+            }
+        );
+        assert_eq!(format.format_name(), Some("sint16"));
+        assert_eq!(format.unit_symbol(), Some("°C"));
+        assert_eq!(
+            format.to_string(),
+            "sint16 x10^-2 Celsius temperature (degree Celsius)"
+        );
+        assert!(PresentationFormat::parse(&TEMPERATURE_FORMAT[..6]).is_err());
+    }
+
+    #[test]
+    fn decode_value_applies_exponent() {
+        let format = PresentationFormat::parse(&TEMPERATURE_FORMAT).unwrap();
+        let value = format.decode_value(&[0xF3, 0xFD]).unwrap();
+        assert!((value - -5.25).abs() < 1e-9);
+        assert_eq!(format.decode_value(&[0xF3]), None);
+
+        // uint24 with a positive exponent.
+        let format = PresentationFormat::parse(&[0x07, 0x02, 0x00, 0x27, 0x01, 0x00, 0x00]);
+        let value = format.unwrap().decode_value(&[0x01, 0x00, 0x01]).unwrap();
+        assert!((value - 6553700.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parse_valid_range() {
+        assert_eq!(
+            ValidRange::parse(&[0x00, 0x00, 0x10, 0x27]).unwrap(),
+            ValidRange {
+                lower: vec![0x00, 0x00],
+                upper: vec![0x10, 0x27],
+            }
+        );
+        assert!(ValidRange::parse(&[]).is_err());
+        assert!(ValidRange::parse(&[0x00, 0x00, 0x10]).is_err());
+    }
+
+    #[test]
+    fn parse_report_reference() {
+        assert_eq!(
+            ReportReference::parse(&[0x01, 0x01]).unwrap(),
+            ReportReference {
+                report_id: 1,
+                report_type: ReportType::Input,
+            }
+        );
+        assert_eq!(
+            ReportReference::parse(&[0x02, 0x03]).unwrap().report_type,
+            ReportType::Feature
+        );
+        assert_eq!(
+            ReportReference::parse(&[0x02, 0x07]).unwrap().report_type,
+            ReportType::Unknown(7)
+        );
+        assert!(ReportReference::parse(&[0x01]).is_err());
+    }
+
+    #[test]
+    fn decode_by_uuid() {
+        match DescriptorValue::decode(CHARACTERISTIC_PRESENTATION_FORMAT_UUID, &TEMPERATURE_FORMAT)
+        {
+            Ok(DescriptorValue::PresentationFormat(format)) => assert_eq!(format.unit, 0x272F),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::f64;
+
+// IEEE 11073-20601 medical device floating point numbers, used by the health
+// related GATT services. Both types are a base 10 mantissa and exponent pair.
+//
+// The reserved special values are mapped to f64: NaN, NRes (not at this
+// resolution) and the reserved value decode as NaN, +/-INFINITY as infinities.
+
+const SFLOAT_NAN: u16 = 0x07FF;
+const SFLOAT_NRES: u16 = 0x0800;
+const SFLOAT_POSITIVE_INFINITY: u16 = 0x07FE;
+const SFLOAT_NEGATIVE_INFINITY: u16 = 0x0802;
+const SFLOAT_RESERVED: u16 = 0x0801;
+
+const FLOAT_NAN: u32 = 0x007F_FFFF;
+const FLOAT_NRES: u32 = 0x0080_0000;
+const FLOAT_POSITIVE_INFINITY: u32 = 0x007F_FFFE;
+const FLOAT_NEGATIVE_INFINITY: u32 = 0x0080_0002;
+const FLOAT_RESERVED: u32 = 0x0080_0001;
+
+// 16 bit SFLOAT: 4 bit signed exponent, 12 bit signed mantissa.
+pub fn sfloat_to_f64(raw: u16) -> f64 {
+    let mantissa = raw & 0x0FFF;
+    match mantissa {
+        SFLOAT_NAN | SFLOAT_NRES | SFLOAT_RESERVED => return f64::NAN,
+        SFLOAT_POSITIVE_INFINITY => return f64::INFINITY,
+        SFLOAT_NEGATIVE_INFINITY => return f64::NEG_INFINITY,
+        _ => (),
+    }
+    let mantissa = ((mantissa << 4) as i16 >> 4) as f64;
+    let exponent = (raw as i16) >> 12;
+    mantissa * 10f64.powi(exponent as i32)
+}
+
+// 32 bit FLOAT: 8 bit signed exponent, 24 bit signed mantissa.
+pub fn float_to_f64(raw: u32) -> f64 {
+    let mantissa = raw & 0x00FF_FFFF;
+    match mantissa {
+        FLOAT_NAN | FLOAT_NRES | FLOAT_RESERVED => return f64::NAN,
+        FLOAT_POSITIVE_INFINITY => return f64::INFINITY,
+        FLOAT_NEGATIVE_INFINITY => return f64::NEG_INFINITY,
+        _ => (),
+    }
+    let mantissa = ((mantissa << 8) as i32 >> 8) as f64;
+    let exponent = (raw as i32) >> 24;
+    mantissa * 10f64.powi(exponent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{float_to_f64, sfloat_to_f64};
+
+    fn assert_close(value: f64, expected: f64) {
+        assert!((value - expected).abs() < 1e-9, "{} != {}", value, expected);
+    }
+
+    #[test]
+    fn decode_sfloat() {
+        assert_close(sfloat_to_f64(0x0072), 114.0);
+        assert_close(sfloat_to_f64(0xF0A4), 16.4);
+        assert_close(sfloat_to_f64(0xFFFE), -0.2);
+        assert_close(sfloat_to_f64(0x2001), 100.0);
+    }
+
+    #[test]
+    fn decode_sfloat_special_values() {
+        assert!(sfloat_to_f64(0x07FF).is_nan());
+        assert!(sfloat_to_f64(0x0800).is_nan());
+        assert!(sfloat_to_f64(0x0801).is_nan());
+        assert_eq!(sfloat_to_f64(0x07FE), f64::INFINITY);
+        assert_eq!(sfloat_to_f64(0x0802), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn decode_float() {
+        assert_close(float_to_f64(0xFE00_0C1C), 31.0);
+        assert_close(float_to_f64(0x00FF_FFFF), -1.0);
+        assert_close(float_to_f64(0x0300_0002), 2000.0);
+    }
+
+    #[test]
+    fn decode_float_special_values() {
+        assert!(float_to_f64(0x007F_FFFF).is_nan());
+        assert!(float_to_f64(0x0080_0000).is_nan());
+        assert!(float_to_f64(0x0080_0001).is_nan());
+        assert_eq!(float_to_f64(0x007F_FFFE), f64::INFINITY);
+        assert_eq!(float_to_f64(0x0080_0002), f64::NEG_INFINITY);
+    }
+}
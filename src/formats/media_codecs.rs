@@ -0,0 +1,706 @@
+use bluetooth_utils::{check_length, read_u16};
+use std::error::Error;
+
+pub static A2DP_SOURCE_UUID: &'static str = "0000110a-0000-1000-8000-00805f9b34fb";
+pub static A2DP_SINK_UUID: &'static str = "0000110b-0000-1000-8000-00805f9b34fb";
+pub static PAC_SINK_UUID: &'static str = "00008f96-0000-1000-8000-00805f9b34fb";
+pub static PAC_SOURCE_UUID: &'static str = "00008f98-0000-1000-8000-00805f9b34fb";
+
+// A2DP codec types, LC3 is the coding format id used by LE Audio.
+pub const CODEC_SBC: u8 = 0x00;
+pub const CODEC_MPEG12: u8 = 0x01;
+pub const CODEC_AAC: u8 = 0x02;
+pub const CODEC_LC3: u8 = 0x06;
+pub const CODEC_VENDOR: u8 = 0xFF;
+
+// SBC bits, each constant sits at its position within the capability byte.
+pub const SBC_SAMPLING_FREQ_16000: u8 = 0x80;
+pub const SBC_SAMPLING_FREQ_32000: u8 = 0x40;
+pub const SBC_SAMPLING_FREQ_44100: u8 = 0x20;
+pub const SBC_SAMPLING_FREQ_48000: u8 = 0x10;
+
+pub const SBC_CHANNEL_MODE_MONO: u8 = 0x08;
+pub const SBC_CHANNEL_MODE_DUAL_CHANNEL: u8 = 0x04;
+pub const SBC_CHANNEL_MODE_STEREO: u8 = 0x02;
+pub const SBC_CHANNEL_MODE_JOINT_STEREO: u8 = 0x01;
+
+pub const SBC_BLOCK_LENGTH_4: u8 = 0x80;
+pub const SBC_BLOCK_LENGTH_8: u8 = 0x40;
+pub const SBC_BLOCK_LENGTH_12: u8 = 0x20;
+pub const SBC_BLOCK_LENGTH_16: u8 = 0x10;
+
+pub const SBC_SUBBANDS_4: u8 = 0x08;
+pub const SBC_SUBBANDS_8: u8 = 0x04;
+
+pub const SBC_ALLOCATION_SNR: u8 = 0x02;
+pub const SBC_ALLOCATION_LOUDNESS: u8 = 0x01;
+
+pub const SBC_MIN_BITPOOL: u8 = 2;
+// Bitpool of the "high quality" joint stereo setting recommended by the A2DP spec.
+pub const SBC_MAX_BITPOOL_HIGH_QUALITY: u8 = 53;
+
+pub const AAC_OBJECT_TYPE_MPEG2_LC: u8 = 0x80;
+pub const AAC_OBJECT_TYPE_MPEG4_LC: u8 = 0x40;
+pub const AAC_OBJECT_TYPE_MPEG4_LTP: u8 = 0x20;
+pub const AAC_OBJECT_TYPE_MPEG4_SCALABLE: u8 = 0x10;
+
+pub const AAC_CHANNELS_1: u8 = 0x08;
+pub const AAC_CHANNELS_2: u8 = 0x04;
+
+pub const LC3_FRAME_DURATION_7_5: u8 = 0x01;
+pub const LC3_FRAME_DURATION_10: u8 = 0x02;
+
+static SBC_SAMPLING_FREQUENCIES: [(u8, u32); 4] = [
+    (SBC_SAMPLING_FREQ_48000, 48000),
+    (SBC_SAMPLING_FREQ_44100, 44100),
+    (SBC_SAMPLING_FREQ_32000, 32000),
+    (SBC_SAMPLING_FREQ_16000, 16000),
+];
+
+// Bit of each rate in the 12 bit AAC sampling frequency field.
+static AAC_SAMPLING_FREQUENCIES: [(u16, u32); 12] = [
+    (0x008, 48000),
+    (0x010, 44100),
+    (0x001, 96000),
+    (0x002, 88200),
+    (0x004, 64000),
+    (0x020, 32000),
+    (0x040, 24000),
+    (0x080, 22050),
+    (0x100, 16000),
+    (0x200, 12000),
+    (0x400, 11025),
+    (0x800, 8000),
+];
+
+// Supported Sampling Frequencies bit and Sampling Frequency configuration value per rate.
+static LC3_SAMPLING_FREQUENCIES: [(u16, u8, u32); 13] = [
+    (0x0001, 0x01, 8000),
+    (0x0002, 0x02, 11025),
+    (0x0004, 0x03, 16000),
+    (0x0008, 0x04, 22050),
+    (0x0010, 0x05, 24000),
+    (0x0020, 0x06, 32000),
+    (0x0040, 0x07, 44100),
+    (0x0080, 0x08, 48000),
+    (0x0100, 0x09, 88200),
+    (0x0200, 0x0A, 96000),
+    (0x0400, 0x0B, 176400),
+    (0x0800, 0x0C, 192000),
+    (0x1000, 0x0D, 384000),
+];
+
+// Octets per codec frame of the BAP presets (e.g. 48_4) for 7.5 and 10 ms frames.
+static LC3_PRESET_OCTETS: [(u32, u16, u16); 5] = [
+    (48000, 90, 120),
+    (32000, 60, 80),
+    (24000, 45, 60),
+    (16000, 30, 40),
+    (8000, 26, 30),
+];
+
+// LTV types of capabilities, configurations use the same numbers except that
+// 0x03 carries the channel allocation instead of the supported channel counts.
+const LC3_SAMPLING_FREQUENCIES_TYPE: u8 = 0x01;
+const LC3_FRAME_DURATIONS_TYPE: u8 = 0x02;
+const LC3_CHANNEL_COUNTS_TYPE: u8 = 0x03;
+const LC3_OCTETS_PER_FRAME_TYPE: u8 = 0x04;
+const LC3_FRAMES_PER_SDU_TYPE: u8 = 0x05;
+const LC3_CHANNEL_ALLOCATION_TYPE: u8 = 0x03;
+
+// SBC Codec Specific Information Elements. A configuration uses the same
+// layout with a single bit set in each field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SbcCapabilities {
+    pub sampling_frequencies: u8,
+    pub channel_modes: u8,
+    pub block_lengths: u8,
+    pub subbands: u8,
+    pub allocation_methods: u8,
+    pub min_bitpool: u8,
+    pub max_bitpool: u8,
+}
+
+impl SbcCapabilities {
+    // Everything an SBC decoder has to support.
+    pub fn all() -> SbcCapabilities {
+        SbcCapabilities {
+            sampling_frequencies: 0xF0,
+            channel_modes: 0x0F,
+            block_lengths: 0xF0,
+            subbands: SBC_SUBBANDS_4 | SBC_SUBBANDS_8,
+            allocation_methods: SBC_ALLOCATION_SNR | SBC_ALLOCATION_LOUDNESS,
+            min_bitpool: SBC_MIN_BITPOOL,
+            max_bitpool: SBC_MAX_BITPOOL_HIGH_QUALITY,
+        }
+    }
+
+    pub fn parse(value: &[u8]) -> Result<SbcCapabilities, Box<Error>> {
+        try!(check_length("SBC capabilities", value, 4));
+        Ok(SbcCapabilities {
+            sampling_frequencies: value[0] & 0xF0,
+            channel_modes: value[0] & 0x0F,
+            block_lengths: value[1] & 0xF0,
+            subbands: value[1] & 0x0C,
+            allocation_methods: value[1] & 0x03,
+            min_bitpool: value[2],
+            max_bitpool: value[3],
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        vec![
+            self.sampling_frequencies | self.channel_modes,
+            self.block_lengths | self.subbands | self.allocation_methods,
+            self.min_bitpool,
+            self.max_bitpool,
+        ]
+    }
+
+    pub fn sampling_frequencies_hz(&self) -> Vec<u32> {
+        SBC_SAMPLING_FREQUENCIES
+            .iter()
+            .filter(|&&(bit, _)| self.sampling_frequencies & bit != 0)
+            .map(|&(_, hz)| hz)
+            .collect()
+    }
+
+    // Picks the highest quality configuration within these capabilities, as an
+    // answer to SelectConfiguration. None if the capabilities do not overlap.
+    pub fn select_configuration(&self) -> Option<SbcCapabilities> {
+        let max_bitpool = if self.max_bitpool < SBC_MAX_BITPOOL_HIGH_QUALITY {
+            self.max_bitpool
+        } else {
+            SBC_MAX_BITPOOL_HIGH_QUALITY
+        };
+        let min_bitpool = if self.min_bitpool > SBC_MIN_BITPOOL {
+            self.min_bitpool
+        } else {
+            SBC_MIN_BITPOOL
+        };
+        if min_bitpool > max_bitpool {
+            return None;
+        }
+        Some(SbcCapabilities {
+            sampling_frequencies: first_bit(
+                self.sampling_frequencies,
+                &[
+                    SBC_SAMPLING_FREQ_48000,
+                    SBC_SAMPLING_FREQ_44100,
+                    SBC_SAMPLING_FREQ_32000,
+                    SBC_SAMPLING_FREQ_16000,
+                ],
+            )?,
+            channel_modes: first_bit(
+                self.channel_modes,
+                &[
+                    SBC_CHANNEL_MODE_JOINT_STEREO,
+                    SBC_CHANNEL_MODE_STEREO,
+                    SBC_CHANNEL_MODE_DUAL_CHANNEL,
+                    SBC_CHANNEL_MODE_MONO,
+                ],
+            )?,
+            block_lengths: first_bit(
+                self.block_lengths,
+                &[
+                    SBC_BLOCK_LENGTH_16,
+                    SBC_BLOCK_LENGTH_12,
+                    SBC_BLOCK_LENGTH_8,
+                    SBC_BLOCK_LENGTH_4,
+                ],
+            )?,
+            subbands: first_bit(self.subbands, &[SBC_SUBBANDS_8, SBC_SUBBANDS_4])?,
+            allocation_methods: first_bit(
+                self.allocation_methods,
+                &[SBC_ALLOCATION_LOUDNESS, SBC_ALLOCATION_SNR],
+            )?,
+            min_bitpool: min_bitpool,
+            max_bitpool: max_bitpool,
+        })
+    }
+}
+
+// MPEG-2/4 AAC Codec Specific Information Elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AacCapabilities {
+    pub object_types: u8,
+    // 12 bit field, 8000 Hz in the most significant bit down to 96000 Hz.
+    pub sampling_frequencies: u16,
+    pub channels: u8,
+    pub vbr: bool,
+    // Bits per second, 0 if unknown.
+    pub bitrate: u32,
+}
+
+impl AacCapabilities {
+    pub fn parse(value: &[u8]) -> Result<AacCapabilities, Box<Error>> {
+        try!(check_length("AAC capabilities", value, 6));
+        Ok(AacCapabilities {
+            object_types: value[0],
+            sampling_frequencies: (value[1] as u16) << 4 | (value[2] as u16) >> 4,
+            channels: value[2] & 0x0F,
+            vbr: value[3] & 0x80 != 0,
+            bitrate: ((value[3] & 0x7F) as u32) << 16 | (value[4] as u32) << 8 | value[5] as u32,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        vec![
+            self.object_types,
+            (self.sampling_frequencies >> 4) as u8,
+            ((self.sampling_frequencies & 0x0F) as u8) << 4 | self.channels & 0x0F,
+            if self.vbr { 0x80 } else { 0x00 } | ((self.bitrate >> 16) & 0x7F) as u8,
+            (self.bitrate >> 8) as u8,
+            self.bitrate as u8,
+        ]
+    }
+
+    pub fn sampling_frequencies_hz(&self) -> Vec<u32> {
+        let mut frequencies: Vec<u32> = AAC_SAMPLING_FREQUENCIES
+            .iter()
+            .filter(|&&(bit, _)| self.sampling_frequencies & bit != 0)
+            .map(|&(_, hz)| hz)
+            .collect();
+        frequencies.sort();
+        frequencies
+    }
+
+    pub fn select_configuration(&self) -> Option<AacCapabilities> {
+        let frequencies: Vec<u16> = AAC_SAMPLING_FREQUENCIES
+            .iter()
+            .map(|&(bit, _)| bit)
+            .collect();
+        Some(AacCapabilities {
+            object_types: first_bit(
+                self.object_types,
+                &[AAC_OBJECT_TYPE_MPEG4_LC, AAC_OBJECT_TYPE_MPEG2_LC],
+            )?,
+            sampling_frequencies: first_bit(self.sampling_frequencies, &frequencies)?,
+            channels: first_bit(self.channels, &[AAC_CHANNELS_2, AAC_CHANNELS_1])?,
+            vbr: self.vbr,
+            bitrate: self.bitrate,
+        })
+    }
+}
+
+// LC3 Codec Specific Capabilities of a PAC record, encoded as LTV structures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lc3Capabilities {
+    // Bit 0 is 8000 Hz, see the Supported_Sampling_Frequencies assigned numbers.
+    pub sampling_frequencies: u16,
+    pub frame_durations: u8,
+    // Bit n set means n + 1 channels are supported, absent means mono only.
+    pub channel_counts: Option<u8>,
+    pub min_octets_per_frame: u16,
+    pub max_octets_per_frame: u16,
+    pub max_frames_per_sdu: Option<u8>,
+}
+
+impl Lc3Capabilities {
+    pub fn parse(value: &[u8]) -> Result<Lc3Capabilities, Box<Error>> {
+        let mut capabilities = Lc3Capabilities {
+            sampling_frequencies: 0,
+            frame_durations: 0,
+            channel_counts: None,
+            min_octets_per_frame: 0,
+            max_octets_per_frame: 0,
+            max_frames_per_sdu: None,
+        };
+        for (ltv_type, data) in try!(parse_ltv(value)) {
+            match ltv_type {
+                LC3_SAMPLING_FREQUENCIES_TYPE => {
+                    try!(check_length("LC3 sampling frequencies", data, 2));
+                    capabilities.sampling_frequencies = read_u16(data, 0);
+                }
+                LC3_FRAME_DURATIONS_TYPE => {
+                    try!(check_length("LC3 frame durations", data, 1));
+                    capabilities.frame_durations = data[0];
+                }
+                LC3_CHANNEL_COUNTS_TYPE => {
+                    try!(check_length("LC3 channel counts", data, 1));
+                    capabilities.channel_counts = Some(data[0]);
+                }
+                LC3_OCTETS_PER_FRAME_TYPE => {
+                    try!(check_length("LC3 octets per frame", data, 4));
+                    capabilities.min_octets_per_frame = read_u16(data, 0);
+                    capabilities.max_octets_per_frame = read_u16(data, 2);
+                }
+                LC3_FRAMES_PER_SDU_TYPE => {
+                    try!(check_length("LC3 frames per SDU", data, 1));
+                    capabilities.max_frames_per_sdu = Some(data[0]);
+                }
+                _ => (),
+            }
+        }
+        Ok(capabilities)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        push_ltv(
+            &mut bytes,
+            LC3_SAMPLING_FREQUENCIES_TYPE,
+            &u16_bytes(self.sampling_frequencies),
+        );
+        push_ltv(
+            &mut bytes,
+            LC3_FRAME_DURATIONS_TYPE,
+            &[self.frame_durations],
+        );
+        if let Some(channel_counts) = self.channel_counts {
+            push_ltv(&mut bytes, LC3_CHANNEL_COUNTS_TYPE, &[channel_counts]);
+        }
+        let mut octets = u16_bytes(self.min_octets_per_frame);
+        octets.extend(u16_bytes(self.max_octets_per_frame));
+        push_ltv(&mut bytes, LC3_OCTETS_PER_FRAME_TYPE, &octets);
+        if let Some(frames) = self.max_frames_per_sdu {
+            push_ltv(&mut bytes, LC3_FRAMES_PER_SDU_TYPE, &[frames]);
+        }
+        bytes
+    }
+
+    pub fn sampling_frequencies_hz(&self) -> Vec<u32> {
+        LC3_SAMPLING_FREQUENCIES
+            .iter()
+            .filter(|&&(bit, _, _)| self.sampling_frequencies & bit != 0)
+            .map(|&(_, _, hz)| hz)
+            .collect()
+    }
+
+    // Picks the highest rate BAP preset the capabilities allow, with 10 ms
+    // frames when one of those fits and 7.5 ms frames otherwise.
+    pub fn select_configuration(&self) -> Option<Lc3Configuration> {
+        let supported = self.sampling_frequencies_hz();
+        for &long_frames in [true, false].iter() {
+            let duration = if long_frames {
+                LC3_FRAME_DURATION_10
+            } else {
+                LC3_FRAME_DURATION_7_5
+            };
+            if self.frame_durations & duration == 0 {
+                continue;
+            }
+            for &(hz, short_octets, long_octets) in LC3_PRESET_OCTETS.iter() {
+                let octets = if long_frames {
+                    long_octets
+                } else {
+                    short_octets
+                };
+                if supported.contains(&hz)
+                    && octets >= self.min_octets_per_frame
+                    && octets <= self.max_octets_per_frame
+                {
+                    return Some(Lc3Configuration {
+                        sampling_frequency: hz,
+                        frame_duration_us: if long_frames { 10000 } else { 7500 },
+                        channel_allocation: None,
+                        octets_per_frame: octets,
+                        frames_per_sdu: None,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+// LC3 Codec Specific Configuration, encoded as LTV structures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lc3Configuration {
+    pub sampling_frequency: u32,
+    // 7500 or 10000.
+    pub frame_duration_us: u32,
+    // Audio locations bitmask, absent means mono.
+    pub channel_allocation: Option<u32>,
+    pub octets_per_frame: u16,
+    pub frames_per_sdu: Option<u8>,
+}
+
+impl Lc3Configuration {
+    pub fn parse(value: &[u8]) -> Result<Lc3Configuration, Box<Error>> {
+        let mut configuration = Lc3Configuration {
+            sampling_frequency: 0,
+            frame_duration_us: 0,
+            channel_allocation: None,
+            octets_per_frame: 0,
+            frames_per_sdu: None,
+        };
+        for (ltv_type, data) in try!(parse_ltv(value)) {
+            match ltv_type {
+                LC3_SAMPLING_FREQUENCIES_TYPE => {
+                    try!(check_length("LC3 sampling frequency", data, 1));
+                    configuration.sampling_frequency = match LC3_SAMPLING_FREQUENCIES
+                        .iter()
+                        .find(|&&(_, code, _)| code == data[0])
+                    {
+                        Some(&(_, _, hz)) => hz,
+                        None => {
+                            return Err(Box::from(format!(
+                                "Unknown LC3 sampling frequency: {:#04x}",
+                                data[0]
+                            )))
+                        }
+                    };
+                }
+                LC3_FRAME_DURATIONS_TYPE => {
+                    try!(check_length("LC3 frame duration", data, 1));
+                    configuration.frame_duration_us = match data[0] {
+                        0x00 => 7500,
+                        0x01 => 10000,
+                        other => {
+                            return Err(Box::from(format!(
+                                "Unknown LC3 frame duration: {:#04x}",
+                                other
+                            )))
+                        }
+                    };
+                }
+                LC3_CHANNEL_ALLOCATION_TYPE => {
+                    try!(check_length("LC3 channel allocation", data, 4));
+                    configuration.channel_allocation =
+                        Some(read_u16(data, 0) as u32 | (read_u16(data, 2) as u32) << 16);
+                }
+                LC3_OCTETS_PER_FRAME_TYPE => {
+                    try!(check_length("LC3 octets per frame", data, 2));
+                    configuration.octets_per_frame = read_u16(data, 0);
+                }
+                LC3_FRAMES_PER_SDU_TYPE => {
+                    try!(check_length("LC3 frames per SDU", data, 1));
+                    configuration.frames_per_sdu = Some(data[0]);
+                }
+                _ => (),
+            }
+        }
+        Ok(configuration)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Box<Error>> {
+        let frequency = match LC3_SAMPLING_FREQUENCIES
+            .iter()
+            .find(|&&(_, _, hz)| hz == self.sampling_frequency)
+        {
+            Some(&(_, code, _)) => code,
+            None => {
+                return Err(Box::from(format!(
+                    "Unsupported LC3 sampling frequency: {}",
+                    self.sampling_frequency
+                )))
+            }
+        };
+        let duration = match self.frame_duration_us {
+            7500 => 0x00,
+            10000 => 0x01,
+            other => {
+                return Err(Box::from(format!(
+                    "Unsupported LC3 frame duration: {}",
+                    other
+                )))
+            }
+        };
+        let mut bytes = Vec::new();
+        push_ltv(&mut bytes, LC3_SAMPLING_FREQUENCIES_TYPE, &[frequency]);
+        push_ltv(&mut bytes, LC3_FRAME_DURATIONS_TYPE, &[duration]);
+        if let Some(allocation) = self.channel_allocation {
+            let mut value = u16_bytes(allocation as u16);
+            value.extend(u16_bytes((allocation >> 16) as u16));
+            push_ltv(&mut bytes, LC3_CHANNEL_ALLOCATION_TYPE, &value);
+        }
+        push_ltv(
+            &mut bytes,
+            LC3_OCTETS_PER_FRAME_TYPE,
+            &u16_bytes(self.octets_per_frame),
+        );
+        if let Some(frames) = self.frames_per_sdu {
+            push_ltv(&mut bytes, LC3_FRAMES_PER_SDU_TYPE, &[frames]);
+        }
+        Ok(bytes)
+    }
+}
+
+// The first of `preferred` that is set in `mask`.
+fn first_bit<T>(mask: T, preferred: &[T]) -> Option<T>
+where
+    T: Copy + PartialEq + ::std::ops::BitAnd<Output = T> + Default,
+{
+    preferred
+        .iter()
+        .find(|&&bit| mask & bit != T::default())
+        .cloned()
+}
+
+fn parse_ltv(value: &[u8]) -> Result<Vec<(u8, &[u8])>, Box<Error>> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    while offset < value.len() {
+        let length = value[offset] as usize;
+        if length == 0 {
+            offset += 1;
+            continue;
+        }
+        if offset + 1 + length > value.len() {
+            return Err(Box::from("Truncated LTV structure"));
+        }
+        entries.push((value[offset + 1], &value[offset + 2..offset + 1 + length]));
+        offset += 1 + length;
+    }
+    Ok(entries)
+}
+
+fn push_ltv(bytes: &mut Vec<u8>, ltv_type: u8, value: &[u8]) {
+    bytes.push(value.len() as u8 + 1);
+    bytes.push(ltv_type);
+    bytes.extend_from_slice(value);
+}
+
+fn u16_bytes(value: u16) -> Vec<u8> {
+    vec![value as u8, (value >> 8) as u8]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sbc_round_trip() {
+        let all = SbcCapabilities::all();
+        assert_eq!(all.to_bytes(), vec![0xFF, 0xFF, 2, 53]);
+        assert_eq!(SbcCapabilities::parse(&all.to_bytes()).unwrap(), all);
+        assert_eq!(
+            all.sampling_frequencies_hz(),
+            vec![48000, 44100, 32000, 16000]
+        );
+    }
+
+    #[test]
+    fn sbc_select_configuration() {
+        let configuration = SbcCapabilities::all().select_configuration().unwrap();
+        assert_eq!(
+            configuration,
+            SbcCapabilities {
+                sampling_frequencies: SBC_SAMPLING_FREQ_48000,
+                channel_modes: SBC_CHANNEL_MODE_JOINT_STEREO,
+                block_lengths: SBC_BLOCK_LENGTH_16,
+                subbands: SBC_SUBBANDS_8,
+                allocation_methods: SBC_ALLOCATION_LOUDNESS,
+                min_bitpool: 2,
+                max_bitpool: 53,
+            }
+        );
+
+        let mut capabilities = SbcCapabilities::all();
+        capabilities.sampling_frequencies = 0;
+        assert_eq!(capabilities.select_configuration(), None);
+        let mut capabilities = SbcCapabilities::all();
+        capabilities.min_bitpool = 60;
+        capabilities.max_bitpool = 64;
+        assert_eq!(capabilities.select_configuration(), None);
+    }
+
+    #[test]
+    fn aac_round_trip() {
+        let bytes = [0x80, 0x01, 0x8C, 0x84, 0xE2, 0x00];
+        let capabilities = AacCapabilities::parse(&bytes).unwrap();
+        assert_eq!(
+            capabilities,
+            AacCapabilities {
+                object_types: AAC_OBJECT_TYPE_MPEG2_LC,
+                sampling_frequencies: 0x018,
+                channels: AAC_CHANNELS_1 | AAC_CHANNELS_2,
+                vbr: true,
+                bitrate: 320000,
+            }
+        );
+        assert_eq!(capabilities.to_bytes(), bytes.to_vec());
+        assert_eq!(capabilities.sampling_frequencies_hz(), vec![44100, 48000]);
+
+        let configuration = capabilities.select_configuration().unwrap();
+        assert_eq!(configuration.object_types, AAC_OBJECT_TYPE_MPEG2_LC);
+        assert_eq!(configuration.sampling_frequencies_hz(), vec![48000]);
+        assert_eq!(configuration.channels, AAC_CHANNELS_2);
+    }
+
+    #[test]
+    fn lc3_capabilities_round_trip() {
+        let capabilities = Lc3Capabilities {
+            sampling_frequencies: 0x0084,
+            frame_durations: LC3_FRAME_DURATION_7_5 | LC3_FRAME_DURATION_10,
+            channel_counts: Some(0x01),
+            min_octets_per_frame: 26,
+            max_octets_per_frame: 120,
+            max_frames_per_sdu: Some(1),
+        };
+        let parsed = Lc3Capabilities::parse(&capabilities.to_bytes()).unwrap();
+        assert_eq!(parsed, capabilities);
+        assert_eq!(parsed.sampling_frequencies_hz(), vec![16000, 48000]);
+
+        let configuration = capabilities.select_configuration().unwrap();
+        assert_eq!(configuration.sampling_frequency, 48000);
+        assert_eq!(configuration.frame_duration_us, 10000);
+        assert_eq!(configuration.octets_per_frame, 120);
+
+        let mut capabilities = capabilities;
+        capabilities.max_octets_per_frame = 100;
+        let configuration = capabilities.select_configuration().unwrap();
+        assert_eq!(configuration.sampling_frequency, 16000);
+        assert_eq!(configuration.octets_per_frame, 40);
+    }
+
+    #[test]
+    fn lc3_select_falls_back_to_short_frames() {
+        // No 10 ms preset of 48 kHz fits 80 to 100 octets, the 7.5 ms one does.
+        let capabilities = Lc3Capabilities {
+            sampling_frequencies: 0x0080,
+            frame_durations: LC3_FRAME_DURATION_7_5 | LC3_FRAME_DURATION_10,
+            channel_counts: None,
+            min_octets_per_frame: 80,
+            max_octets_per_frame: 100,
+            max_frames_per_sdu: None,
+        };
+        let configuration = capabilities.select_configuration().unwrap();
+        assert_eq!(configuration.sampling_frequency, 48000);
+        assert_eq!(configuration.frame_duration_us, 7500);
+        assert_eq!(configuration.octets_per_frame, 90);
+
+        let mut capabilities = capabilities;
+        capabilities.frame_durations = LC3_FRAME_DURATION_10;
+        assert_eq!(capabilities.select_configuration(), None);
+        capabilities.frame_durations = 0;
+        assert_eq!(capabilities.select_configuration(), None);
+    }
+
+    #[test]
+    fn lc3_configuration_round_trip() {
+        let configuration = Lc3Configuration {
+            sampling_frequency: 48000,
+            frame_duration_us: 10000,
+            channel_allocation: Some(0x03),
+            octets_per_frame: 120,
+            frames_per_sdu: Some(1),
+        };
+        let bytes = configuration.to_bytes().unwrap();
+        assert_eq!(
+            bytes,
+            vec![
+                0x02, 0x01, 0x08, 0x02, 0x02, 0x01, 0x05, 0x03, 0x03, 0x00, 0x00, 0x00, 0x03, 0x04,
+                0x78, 0x00, 0x02, 0x05, 0x01,
+            ]
+        );
+        assert_eq!(Lc3Configuration::parse(&bytes).unwrap(), configuration);
+    }
+
+    #[test]
+    fn parse_malformed_codecs() {
+        assert!(SbcCapabilities::parse(&[0xFF, 0xFF, 2]).is_err());
+        assert!(AacCapabilities::parse(&[0x80, 0x01, 0x8C, 0x84, 0xE2]).is_err());
+        // Truncated LTV and one too short for its type.
+        assert!(Lc3Capabilities::parse(&[0x03, 0x01, 0x08]).is_err());
+        assert!(Lc3Capabilities::parse(&[0x02, 0x01, 0x08]).is_err());
+        assert!(Lc3Configuration::parse(&[0x02, 0x01, 0x0E]).is_err());
+        assert!(Lc3Configuration::parse(&[0x02, 0x02, 0x02]).is_err());
+        assert!(Lc3Configuration::parse(&[0x03, 0x03, 0x03, 0x00]).is_err());
+
+        let mut configuration = Lc3Configuration::parse(&[]).unwrap();
+        configuration.sampling_frequency = 12345;
+        assert!(configuration.to_bytes().is_err());
+    }
+}
@@ -0,0 +1,10 @@
+// Decoders and encoders of Bluetooth data formats, independent of the bus.
+
+pub mod appearance;
+pub mod beacon;
+pub mod bmessage;
+pub mod class_of_device;
+pub mod gatt_descriptors;
+pub mod ieee11073;
+pub mod media_codecs;
+pub mod vcard;
@@ -0,0 +1,865 @@
+use bluetooth_obex::ObexTime;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{Read, Write};
+
+static BASE64_ALPHABET: &'static [u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VCardName {
+    pub family: String,
+    pub given: String,
+    pub additional: String,
+    pub prefix: String,
+    pub suffix: String,
+}
+
+// Types are lower case, e.g. "cell", "home" or "pref".
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VCardTelephone {
+    pub number: String,
+    pub types: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VCardEmail {
+    pub address: String,
+    pub types: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VCardAddress {
+    pub po_box: String,
+    pub extended: String,
+    pub street: String,
+    pub locality: String,
+    pub region: String,
+    pub postal_code: String,
+    pub country: String,
+    pub types: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallType {
+    Missed,
+    Received,
+    Dialed,
+}
+
+// X-IRMC-CALL-DATETIME of the call history phonebooks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VCardCall {
+    pub call_type: Option<CallType>,
+    pub time: Option<ObexTime>,
+}
+
+// A contact as found in PBAP phonebook objects, vCard 2.1 and 3.0 are accepted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VCard {
+    pub version: String,
+    pub formatted_name: Option<String>,
+    pub name: Option<VCardName>,
+    pub nickname: Option<String>,
+    pub telephones: Vec<VCardTelephone>,
+    pub emails: Vec<VCardEmail>,
+    pub addresses: Vec<VCardAddress>,
+    pub organization: Option<String>,
+    pub title: Option<String>,
+    pub birthday: Option<String>,
+    pub note: Option<String>,
+    pub urls: Vec<String>,
+    pub uid: Option<String>,
+    pub photo: Option<Vec<u8>>,
+    pub call: Option<VCardCall>,
+    // Remaining properties as (name, line), the unfolded line is kept as received
+    // and written back unchanged, e.g. ("X-ANDROID-CUSTOM", "X-ANDROID-CUSTOM;...").
+    pub extra: Vec<(String, String)>,
+}
+
+impl VCard {
+    // Parses the first vCard of `text`.
+    pub fn parse(text: &str) -> Result<VCard, Box<Error>> {
+        match parse_vcards(text).into_iter().next() {
+            Some(vcard) => Ok(vcard),
+            None => Err(Box::from("No vCard found")),
+        }
+    }
+
+    // First telephone number, preferring one marked "pref".
+    pub fn preferred_telephone(&self) -> Option<&str> {
+        self.telephones
+            .iter()
+            .find(|t| t.types.iter().any(|t| t == "pref"))
+            .or_else(|| self.telephones.first())
+            .map(|t| t.number.as_str())
+    }
+
+    // Writes the card as formatted by Display, e.g. for ExchangeBusinessCards.
+    pub fn write_file(&self, path: &str) -> Result<(), Box<Error>> {
+        let mut file = File::create(path)?;
+        file.write_all(self.to_string().as_bytes())?;
+        Ok(())
+    }
+
+    // Only vCard 3.0 escapes commas and newlines in text values, `version` is the
+    // VERSION of the card so far.
+    fn add_property(&mut self, property: Property, version: &str) {
+        let version_30 = version == "3.0";
+        let types = property.types();
+        match property.name.as_str() {
+            "VERSION" => self.version = property.text(false),
+            "FN" => self.formatted_name = Some(property.text(version_30)),
+            "N" => {
+                let mut components = property.components(version_30).into_iter();
+                self.name = Some(VCardName {
+                    family: components.next().unwrap_or_default(),
+                    given: components.next().unwrap_or_default(),
+                    additional: components.next().unwrap_or_default(),
+                    prefix: components.next().unwrap_or_default(),
+                    suffix: components.next().unwrap_or_default(),
+                });
+            }
+            "NICKNAME" => self.nickname = Some(property.text(version_30)),
+            "TEL" => self.telephones.push(VCardTelephone {
+                number: property.text(version_30),
+                types: types,
+            }),
+            "EMAIL" => self.emails.push(VCardEmail {
+                address: property.text(version_30),
+                types: types,
+            }),
+            "ADR" => {
+                let mut components = property.components(version_30).into_iter();
+                self.addresses.push(VCardAddress {
+                    po_box: components.next().unwrap_or_default(),
+                    extended: components.next().unwrap_or_default(),
+                    street: components.next().unwrap_or_default(),
+                    locality: components.next().unwrap_or_default(),
+                    region: components.next().unwrap_or_default(),
+                    postal_code: components.next().unwrap_or_default(),
+                    country: components.next().unwrap_or_default(),
+                    types: types,
+                });
+            }
+            "ORG" => self.organization = Some(property.components(version_30).join(", ")),
+            "TITLE" => self.title = Some(property.text(version_30)),
+            "BDAY" => self.birthday = Some(property.text(version_30)),
+            "NOTE" => self.note = Some(property.text(version_30)),
+            "URL" => self.urls.push(property.text(version_30)),
+            "UID" => self.uid = Some(property.text(version_30)),
+            "PHOTO" => {
+                if property.is_base64() {
+                    self.photo = decode_base64(&property.value);
+                }
+            }
+            "X-IRMC-CALL-DATETIME" => {
+                let call_type = if types.iter().any(|t| t == "missed") {
+                    Some(CallType::Missed)
+                } else if types.iter().any(|t| t == "received") {
+                    Some(CallType::Received)
+                } else if types.iter().any(|t| t == "dialed") {
+                    Some(CallType::Dialed)
+                } else {
+                    None
+                };
+                self.call = Some(VCardCall {
+                    call_type: call_type,
+                    time: ObexTime::parse(&property.text(version_30)),
+                });
+            }
+            _ => self.extra.push((property.name, property.line)),
+        }
+    }
+}
+
+// Builds a VCard from structured data, written as vCard 2.1 unless version_30 is set.
+#[derive(Clone, Debug)]
+pub struct VCardBuilder {
+    vcard: VCard,
+}
+
+impl VCardBuilder {
+    pub fn new(formatted_name: &str) -> VCardBuilder {
+        VCardBuilder {
+            vcard: VCard {
+                version: String::from("2.1"),
+                formatted_name: Some(String::from(formatted_name)),
+                ..VCard::default()
+            },
+        }
+    }
+
+    pub fn version_30(mut self) -> VCardBuilder {
+        self.vcard.version = String::from("3.0");
+        self
+    }
+
+    pub fn name(mut self, family: &str, given: &str) -> VCardBuilder {
+        self.vcard.name = Some(VCardName {
+            family: String::from(family),
+            given: String::from(given),
+            ..VCardName::default()
+        });
+        self
+    }
+
+    pub fn full_name(mut self, name: VCardName) -> VCardBuilder {
+        self.vcard.name = Some(name);
+        self
+    }
+
+    pub fn nickname(mut self, nickname: &str) -> VCardBuilder {
+        self.vcard.nickname = Some(String::from(nickname));
+        self
+    }
+
+    // `types` such as "cell", "home", "work" or "pref".
+    pub fn telephone(mut self, number: &str, types: &[&str]) -> VCardBuilder {
+        self.vcard.telephones.push(VCardTelephone {
+            number: String::from(number),
+            types: types.iter().map(|t| t.to_lowercase()).collect(),
+        });
+        self
+    }
+
+    pub fn email(mut self, address: &str, types: &[&str]) -> VCardBuilder {
+        self.vcard.emails.push(VCardEmail {
+            address: String::from(address),
+            types: types.iter().map(|t| t.to_lowercase()).collect(),
+        });
+        self
+    }
+
+    pub fn address(mut self, address: VCardAddress) -> VCardBuilder {
+        self.vcard.addresses.push(address);
+        self
+    }
+
+    pub fn organization(mut self, organization: &str) -> VCardBuilder {
+        self.vcard.organization = Some(String::from(organization));
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> VCardBuilder {
+        self.vcard.title = Some(String::from(title));
+        self
+    }
+
+    // "YYYY-MM-DD".
+    pub fn birthday(mut self, birthday: &str) -> VCardBuilder {
+        self.vcard.birthday = Some(String::from(birthday));
+        self
+    }
+
+    pub fn note(mut self, note: &str) -> VCardBuilder {
+        self.vcard.note = Some(String::from(note));
+        self
+    }
+
+    pub fn url(mut self, url: &str) -> VCardBuilder {
+        self.vcard.urls.push(String::from(url));
+        self
+    }
+
+    pub fn uid(mut self, uid: &str) -> VCardBuilder {
+        self.vcard.uid = Some(String::from(uid));
+        self
+    }
+
+    // JPEG data of the contact picture.
+    pub fn photo(mut self, photo: Vec<u8>) -> VCardBuilder {
+        self.vcard.photo = Some(photo);
+        self
+    }
+
+    pub fn build(self) -> VCard {
+        self.vcard
+    }
+}
+
+// Writes the card as vCard 3.0 if `version` is "3.0" and as vCard 2.1 otherwise.
+impl fmt::Display for VCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let version_30 = self.version == "3.0";
+        write!(f, "BEGIN:VCARD\r\n")?;
+        write!(f, "VERSION:{}\r\n", if version_30 { "3.0" } else { "2.1" })?;
+        if let Some(ref name) = self.name {
+            let components = [
+                &name.family,
+                &name.given,
+                &name.additional,
+                &name.prefix,
+                &name.suffix,
+            ];
+            write_structured(f, "N", &[], &components, version_30)?;
+        }
+        if let Some(ref formatted_name) = self.formatted_name {
+            write_text(f, "FN", &[], formatted_name, version_30)?;
+        }
+        if let Some(ref nickname) = self.nickname {
+            write_text(f, "NICKNAME", &[], nickname, version_30)?;
+        }
+        for telephone in self.telephones.iter() {
+            write_text(f, "TEL", &telephone.types, &telephone.number, version_30)?;
+        }
+        for email in self.emails.iter() {
+            write_text(f, "EMAIL", &email.types, &email.address, version_30)?;
+        }
+        for address in self.addresses.iter() {
+            let components = [
+                &address.po_box,
+                &address.extended,
+                &address.street,
+                &address.locality,
+                &address.region,
+                &address.postal_code,
+                &address.country,
+            ];
+            write_structured(f, "ADR", &address.types, &components, version_30)?;
+        }
+        if let Some(ref organization) = self.organization {
+            write_text(f, "ORG", &[], organization, version_30)?;
+        }
+        if let Some(ref title) = self.title {
+            write_text(f, "TITLE", &[], title, version_30)?;
+        }
+        if let Some(ref birthday) = self.birthday {
+            write_text(f, "BDAY", &[], birthday, version_30)?;
+        }
+        if let Some(ref note) = self.note {
+            write_text(f, "NOTE", &[], note, version_30)?;
+        }
+        for url in self.urls.iter() {
+            write_text(f, "URL", &[], url, version_30)?;
+        }
+        if let Some(ref uid) = self.uid {
+            write_text(f, "UID", &[], uid, version_30)?;
+        }
+        if let Some(ref photo) = self.photo {
+            write!(
+                f,
+                "PHOTO;ENCODING={}:",
+                if version_30 { "b" } else { "BASE64" }
+            )?;
+            // Folded at 76 characters, vCard 2.1 ends the value with an empty line.
+            let encoded = encode_base64(photo);
+            for (i, chunk) in encoded.as_bytes().chunks(76).enumerate() {
+                if i > 0 {
+                    write!(f, "\r\n ")?;
+                }
+                write!(f, "{}", String::from_utf8_lossy(chunk))?;
+            }
+            write!(f, "\r\n{}", if version_30 { "" } else { "\r\n" })?;
+        }
+        if let Some(ref call) = self.call {
+            let types: Vec<String> = match call.call_type {
+                Some(CallType::Missed) => vec![String::from("missed")],
+                Some(CallType::Received) => vec![String::from("received")],
+                Some(CallType::Dialed) => vec![String::from("dialed")],
+                None => Vec::new(),
+            };
+            let time = call.time.map(|t| t.to_string()).unwrap_or_default();
+            write_text(f, "X-IRMC-CALL-DATETIME", &types, &time, version_30)?;
+        }
+        for &(_, ref line) in self.extra.iter() {
+            write!(f, "{}\r\n", line)?;
+        }
+        write!(f, "END:VCARD\r\n")
+    }
+}
+
+// All vCards of a phonebook object, cards that are not terminated are dropped.
+pub fn parse_vcards(text: &str) -> Vec<VCard> {
+    let mut vcards = Vec::new();
+    let mut current: Option<VCard> = None;
+    for line in unfold(text) {
+        let property = match Property::parse(&line) {
+            Some(property) => property,
+            None => continue,
+        };
+        match property.name.as_str() {
+            "BEGIN" if property.value.eq_ignore_ascii_case("VCARD") => {
+                current = Some(VCard::default());
+            }
+            "END" if property.value.eq_ignore_ascii_case("VCARD") => {
+                if let Some(vcard) = current.take() {
+                    vcards.push(vcard);
+                }
+            }
+            _ => {
+                if let Some(ref mut vcard) = current {
+                    let version = vcard.version.clone();
+                    vcard.add_property(property, &version);
+                }
+            }
+        }
+    }
+    vcards
+}
+
+// Reads a phonebook object downloaded by PhonebookAccess1, e.g. with pull_all.
+pub fn read_file(path: &str) -> Result<Vec<VCard>, Box<Error>> {
+    let mut file = File::open(path)?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(parse_vcards(&String::from_utf8_lossy(&bytes)))
+}
+
+struct Property {
+    name: String,
+    // (name, value) with upper case names, bare 2.1 parameters have the name "TYPE".
+    params: Vec<(String, String)>,
+    value: String,
+    line: String,
+}
+
+impl Property {
+    fn parse(line: &str) -> Option<Property> {
+        let colon = line.find(':')?;
+        let (head, value) = (&line[..colon], &line[colon + 1..]);
+        let mut parts = head.split(';');
+        let name = parts.next()?.trim().to_uppercase();
+        // Drop a group prefix such as "item1.".
+        let name = match name.rfind('.') {
+            Some(dot) => name[dot + 1..].to_string(),
+            None => name,
+        };
+        let mut params = Vec::new();
+        for param in parts {
+            match param.find('=') {
+                Some(eq) => params.push((
+                    param[..eq].trim().to_uppercase(),
+                    param[eq + 1..].trim().trim_matches('"').to_string(),
+                )),
+                None => params.push((String::from("TYPE"), param.trim().to_string())),
+            }
+        }
+        Some(Property {
+            name: name,
+            params: params,
+            value: value.to_string(),
+            line: line.to_string(),
+        })
+    }
+
+    fn param_is(&self, name: &str, value: &str) -> bool {
+        self.params
+            .iter()
+            .any(|&(ref n, ref v)| (n == name || n == "TYPE") && v.eq_ignore_ascii_case(value))
+    }
+
+    fn is_quoted_printable(&self) -> bool {
+        self.param_is("ENCODING", "QUOTED-PRINTABLE")
+    }
+
+    fn is_base64(&self) -> bool {
+        self.param_is("ENCODING", "BASE64") || self.param_is("ENCODING", "b")
+    }
+
+    fn types(&self) -> Vec<String> {
+        let mut types = Vec::new();
+        for &(ref name, ref value) in self.params.iter() {
+            if name != "TYPE" {
+                continue;
+            }
+            for t in value.split(',') {
+                let t = t.trim().to_lowercase();
+                if !t.is_empty() && t != "quoted-printable" && t != "base64" {
+                    types.push(t);
+                }
+            }
+        }
+        types
+    }
+
+    fn decoded(&self) -> String {
+        if self.is_quoted_printable() {
+            String::from_utf8_lossy(&decode_quoted_printable(&self.value)).into_owned()
+        } else {
+            self.value.clone()
+        }
+    }
+
+    fn text(&self, version_30: bool) -> String {
+        let value = self.decoded();
+        if version_30 {
+            unescape(&value)
+        } else {
+            value
+        }
+    }
+
+    // Values of structured properties such as N and ADR, vCard 2.1 only escapes
+    // the semicolons between them.
+    fn components(&self, version_30: bool) -> Vec<String> {
+        let unescape_component = |component: &str| {
+            if version_30 {
+                unescape(component)
+            } else {
+                component.replace("\\;", ";")
+            }
+        };
+        let value = self.decoded();
+        let mut components = Vec::new();
+        let mut current = String::new();
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    current.push(c);
+                    if let Some(next) = chars.next() {
+                        current.push(next);
+                    }
+                }
+                ';' => components.push(unescape_component(&current.split_off(0))),
+                _ => current.push(c),
+            }
+        }
+        components.push(unescape_component(&current));
+        components
+    }
+}
+
+// Joins folded lines and the soft line breaks of quoted-printable values.
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut quoted_printable_continues = false;
+    for raw in text.split('\n') {
+        let raw = raw.trim_end_matches('\r');
+        if quoted_printable_continues {
+            let last = lines.last_mut().unwrap();
+            last.pop();
+            last.push_str(raw.trim_start());
+        } else if (raw.starts_with(' ') || raw.starts_with('\t')) && !lines.is_empty() {
+            lines.last_mut().unwrap().push_str(&raw[1..]);
+        } else if !raw.is_empty() {
+            lines.push(raw.to_string());
+        }
+        quoted_printable_continues = match lines.last() {
+            Some(line) => line.ends_with('=') && line.to_uppercase().contains("QUOTED-PRINTABLE"),
+            None => false,
+        };
+    }
+    lines
+}
+
+// Property parameters, vCard 2.1 lists bare types and sends non-ASCII text
+// quoted-printable.
+fn write_head(
+    f: &mut fmt::Formatter,
+    name: &str,
+    types: &[String],
+    value: &str,
+    version_30: bool,
+) -> Result<bool, fmt::Error> {
+    write!(f, "{}", name)?;
+    if version_30 {
+        if !types.is_empty() {
+            write!(f, ";TYPE={}", types.join(",").to_uppercase())?;
+        }
+        return Ok(false);
+    }
+    for t in types.iter() {
+        write!(f, ";{}", t.to_uppercase())?;
+    }
+    let quoted_printable = value.bytes().any(|b| b >= 0x80 || b == b'\n' || b == b'\r');
+    if quoted_printable {
+        write!(f, ";CHARSET=UTF-8;ENCODING=QUOTED-PRINTABLE")?;
+    }
+    Ok(quoted_printable)
+}
+
+fn write_value(f: &mut fmt::Formatter, value: &str, quoted_printable: bool) -> fmt::Result {
+    if quoted_printable {
+        write!(f, ":{}\r\n", encode_quoted_printable(value))
+    } else {
+        write!(f, ":{}\r\n", value)
+    }
+}
+
+fn write_text(
+    f: &mut fmt::Formatter,
+    name: &str,
+    types: &[String],
+    value: &str,
+    version_30: bool,
+) -> fmt::Result {
+    let quoted_printable = write_head(f, name, types, value, version_30)?;
+    if version_30 {
+        write_value(f, &escape(value, true), false)
+    } else {
+        write_value(f, value, quoted_printable)
+    }
+}
+
+fn write_structured(
+    f: &mut fmt::Formatter,
+    name: &str,
+    types: &[String],
+    components: &[&String],
+    version_30: bool,
+) -> fmt::Result {
+    let value = components
+        .iter()
+        .map(|c| escape(c, version_30))
+        .collect::<Vec<String>>()
+        .join(";");
+    let quoted_printable = write_head(f, name, types, &value, version_30)?;
+    write_value(f, &value, quoted_printable)
+}
+
+// vCard 3.0 escapes backslashes, commas, semicolons and newlines, vCard 2.1 only
+// the semicolons separating components.
+fn escape(value: &str, version_30: bool) -> String {
+    let mut result = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\n' if version_30 => result.push_str("\\n"),
+            '\\' | ',' if version_30 => {
+                result.push('\\');
+                result.push(c);
+            }
+            ';' => result.push_str("\\;"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => result.push('\n'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+fn decode_quoted_printable(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'=' && i + 2 < bytes.len() {
+            let hex = String::from_utf8_lossy(&bytes[i + 1..i + 3]);
+            if let Ok(byte) = u8::from_str_radix(&hex, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    decoded
+}
+
+fn encode_quoted_printable(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for &b in value.as_bytes() {
+        if b == b' ' || (b >= 0x21 && b <= 0x7e && b != b'=') {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("={:02X}", b));
+        }
+    }
+    encoded
+}
+
+fn encode_base64(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let buffer = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (buffer >> (18 - 6 * i)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn decode_base64(value: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(value.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &c in value.as_bytes() {
+        if c == b'=' {
+            break;
+        }
+        if (c as char).is_whitespace() {
+            continue;
+        }
+        let index = BASE64_ALPHABET.iter().position(|&a| a == c)? as u32;
+        buffer = buffer << 6 | index;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact() -> VCardBuilder {
+        VCardBuilder::new("Zoë Smith; Jr.")
+            .name("Smith", "Zoë")
+            .telephone("+123456789", &["cell", "pref"])
+            .telephone("555-0100", &["home"])
+            .email("zoe@example.com", &["work"])
+            .address(VCardAddress {
+                street: String::from("1 Main St, Apt 2"),
+                locality: String::from("Springfield"),
+                country: String::from("USA"),
+                types: vec![String::from("home")],
+                ..VCardAddress::default()
+            })
+            .organization("ACME")
+            .note("First line\nsecond line")
+            .url("https://example.com")
+            .uid("1234")
+            .photo((0..200).map(|b| b as u8).collect())
+    }
+
+    #[test]
+    fn round_trip_21() {
+        let vcard = contact().build();
+        let text = vcard.to_string();
+        assert!(text.starts_with("BEGIN:VCARD\r\nVERSION:2.1\r\n"));
+        assert!(text.contains("TEL;CELL;PREF:+123456789\r\n"));
+        assert_eq!(VCard::parse(&text).unwrap(), vcard);
+    }
+
+    #[test]
+    fn round_trip_30() {
+        let vcard = contact().version_30().build();
+        let text = vcard.to_string();
+        assert!(text.contains("VERSION:3.0\r\n"));
+        assert!(text.contains("NOTE:First line\\nsecond line\r\n"));
+        assert!(text.contains("ADR;TYPE=HOME:;;1 Main St\\, Apt 2;Springfield;;;USA\r\n"));
+        assert_eq!(VCard::parse(&text).unwrap(), vcard);
+    }
+
+    #[test]
+    fn parse_phonebook() {
+        let text = "BEGIN:VCARD\r\n\
+                    VERSION:2.1\r\n\
+                    N;CHARSET=UTF-8;ENCODING=QUOTED-PRINTABLE:M=C3=BCller;J=\r\n\
+                    =C3=BCrgen\r\n\
+                    TEL;CELL:+49 170 1234567\r\n\
+                    X-IRMC-CALL-DATETIME;MISSED:20200131T235958\r\n\
+                    END:VCARD\r\n\
+                    BEGIN:VCARD\r\n\
+                    VERSION:3.0\r\n\
+                    FN:Long\r\n  Name\r\n\
+                    item1.EMAIL;TYPE=INTERNET,pref:a@example.com\r\n\
+                    X-CUSTOM:value\r\n\
+                    END:VCARD\r\n";
+        let vcards = parse_vcards(text);
+        assert_eq!(vcards.len(), 2);
+
+        let name = vcards[0].name.as_ref().unwrap();
+        assert_eq!(name.family, "Müller");
+        assert_eq!(name.given, "Jürgen");
+        assert_eq!(vcards[0].preferred_telephone(), Some("+49 170 1234567"));
+        let call = vcards[0].call.unwrap();
+        assert_eq!(call.call_type, Some(CallType::Missed));
+        assert_eq!(call.time, ObexTime::parse("20200131T235958"));
+
+        assert_eq!(vcards[1].formatted_name, Some(String::from("Long Name")));
+        assert_eq!(vcards[1].emails[0].types, vec!["internet", "pref"]);
+        assert_eq!(
+            vcards[1].extra,
+            vec![(String::from("X-CUSTOM"), String::from("X-CUSTOM:value"))]
+        );
+    }
+
+    #[test]
+    fn unescape_only_30() {
+        let text = "BEGIN:VCARD\r\n\
+                    VERSION:2.1\r\n\
+                    N:O\\;Brien;Pat\r\n\
+                    NOTE:C:\\new\\dir, ok\r\n\
+                    END:VCARD\r\n\
+                    BEGIN:VCARD\r\n\
+                    VERSION:3.0\r\n\
+                    N:O\\;Brien;Pat\r\n\
+                    NOTE:C:\\\\new\\ndir\\, ok\r\n\
+                    END:VCARD\r\n";
+        let vcards = parse_vcards(text);
+        for vcard in vcards.iter() {
+            let name = vcard.name.as_ref().unwrap();
+            assert_eq!(name.family, "O;Brien");
+            assert_eq!(name.given, "Pat");
+        }
+        assert_eq!(vcards[0].note, Some(String::from("C:\\new\\dir, ok")));
+        assert_eq!(vcards[1].note, Some(String::from("C:\\new\ndir, ok")));
+    }
+
+    #[test]
+    fn extra_properties_kept_as_received() {
+        let line = "item2.X-ANDROID-CUSTOM;CHARSET=UTF-8:vnd.android/nickname;Bob\\, Jr.;1";
+        let text = format!(
+            "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Bob\r\n{}\r\nEND:VCARD\r\n",
+            line
+        );
+        let vcard = VCard::parse(&text).unwrap();
+        assert_eq!(
+            vcard.extra,
+            vec![(String::from("X-ANDROID-CUSTOM"), String::from(line))]
+        );
+        assert!(vcard.to_string().contains(&format!("\r\n{}\r\n", line)));
+        assert_eq!(VCard::parse(&vcard.to_string()).unwrap(), vcard);
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        assert_eq!(encode_base64(b"Man"), "TWFu");
+        assert_eq!(encode_base64(b"Ma"), "TWE=");
+        assert_eq!(encode_base64(b"M"), "TQ==");
+        assert_eq!(decode_base64("TWFu\r\n TWE="), Some(b"ManMa".to_vec()));
+        assert_eq!(decode_base64("TQ*="), None);
+    }
+
+    #[test]
+    fn parse_malformed_vcards() {
+        assert!(VCard::parse("").is_err());
+        assert!(VCard::parse("FN:No card\r\n").is_err());
+        // Unterminated cards and stray ends are dropped.
+        assert!(parse_vcards("BEGIN:VCARD\r\nFN:Open\r\n").is_empty());
+        assert!(parse_vcards("END:VCARD\r\n").is_empty());
+
+        let vcard = VCard::parse(
+            "BEGIN:VCARD\r\n\
+             no colon\r\n\
+             \u{e9}:value\r\n\
+             PHOTO;ENCODING=BASE64:not*base64\r\n\
+             NOTE;ENCODING=QUOTED-PRINTABLE:=4\r\n\
+             TEL;CELL:=ZZ\u{e9}=\r\n\
+             END:VCARD\r\n",
+        ).unwrap();
+        assert_eq!(vcard.photo, None);
+        assert_eq!(vcard.note, Some(String::from("=4")));
+        assert_eq!(vcard.telephones[0].number, "=ZZ\u{e9}=");
+    }
+}
@@ -2,22 +2,54 @@ extern crate dbus;
 extern crate hex;
 
 pub use bluetooth_adapter::BluetoothAdapter;
+pub use bluetooth_battery::BluetoothBattery;
 pub use bluetooth_device::BluetoothDevice;
 pub use bluetooth_discovery_session::BluetoothDiscoverySession;
 pub use bluetooth_event::BluetoothEvent;
+pub use bluetooth_event_dispatcher::BluetoothEventDispatcher;
 pub use bluetooth_gatt_characteristic::BluetoothGATTCharacteristic;
+pub use bluetooth_gatt_database::GattDatabase;
 pub use bluetooth_gatt_descriptor::BluetoothGATTDescriptor;
 pub use bluetooth_gatt_service::BluetoothGATTService;
+pub use bluetooth_media_player::BluetoothMediaPlayer;
+pub use bluetooth_media_transport::BluetoothMediaTransport;
 pub use bluetooth_obex::BluetoothOBEXSession;
 pub use bluetooth_session::BluetoothSession;
 
 pub mod bluetooth_adapter;
+pub mod bluetooth_battery;
+pub mod bluetooth_battery_provider;
+pub mod bluetooth_beacon;
 pub mod bluetooth_device;
 pub mod bluetooth_discovery_session;
 pub mod bluetooth_event;
+pub mod bluetooth_event_dispatcher;
 pub mod bluetooth_gatt_characteristic;
+pub mod bluetooth_gatt_database;
 pub mod bluetooth_gatt_descriptor;
+pub mod bluetooth_gatt_flags;
 pub mod bluetooth_gatt_service;
+pub mod bluetooth_input;
+pub mod bluetooth_media_control;
+pub mod bluetooth_media_endpoint;
+pub mod bluetooth_media_folder;
+pub mod bluetooth_media_item;
+pub mod bluetooth_media_player;
+pub mod bluetooth_media_transport;
+pub mod bluetooth_network;
+pub mod bluetooth_network_server;
+#[macro_use]
 pub mod bluetooth_obex;
+pub mod bluetooth_obex_agent;
+pub mod bluetooth_obex_client;
+pub mod bluetooth_obex_file_transfer;
+pub mod bluetooth_obex_image;
+pub mod bluetooth_obex_message;
+pub mod bluetooth_obex_object_push;
+pub mod bluetooth_obex_phonebook;
+pub mod bluetooth_obex_sync;
+pub mod bluetooth_profile;
 pub mod bluetooth_session;
+pub mod formats;
+pub mod profiles;
 mod bluetooth_utils;
@@ -0,0 +1,43 @@
+use bluetooth_device::BluetoothDevice;
+use bluetooth_gatt_characteristic::BluetoothGATTCharacteristic;
+use profiles::{check_length, MeasurementStream};
+use std::error::Error;
+
+pub static BATTERY_SERVICE_UUID: &'static str = "0000180f-0000-1000-8000-00805f9b34fb";
+pub static BATTERY_LEVEL_UUID: &'static str = "00002a19-0000-1000-8000-00805f9b34fb";
+
+pub struct BatteryService<'a> {
+    level: BluetoothGATTCharacteristic<'a>,
+}
+
+impl<'a> BatteryService<'a> {
+    pub fn new(device: &BluetoothDevice<'a>) -> Result<BatteryService<'a>, Box<Error>> {
+        let level = try!(device.get_characteristic(BATTERY_SERVICE_UUID, BATTERY_LEVEL_UUID));
+        Ok(BatteryService { level: level })
+    }
+
+    // Percentage, 0-100.
+    pub fn get_level(&self) -> Result<u8, Box<Error>> {
+        parse_level(&try!(self.level.read_value(None)))
+    }
+
+    pub fn start_notify(&self) -> Result<(), Box<Error>> {
+        self.level.start_notify()
+    }
+
+    pub fn stop_notify(&self) -> Result<(), Box<Error>> {
+        self.level.stop_notify()
+    }
+
+    pub fn levels(&self, timeout_ms: u32) -> MeasurementStream<'a, u8> {
+        MeasurementStream::new(self.level.notifications(timeout_ms), parse_level)
+    }
+}
+
+pub fn parse_level(value: &[u8]) -> Result<u8, Box<Error>> {
+    try!(check_length("Battery Level", value, 1));
+    if value[0] > 100 {
+        return Err(Box::from(format!("Invalid battery level {}", value[0])));
+    }
+    Ok(value[0])
+}
@@ -0,0 +1,259 @@
+use bluetooth_device::BluetoothDevice;
+use bluetooth_gatt_characteristic::BluetoothGATTCharacteristic;
+use profiles::{
+    check_length, read_sfloat, read_u16, DateTime, MeasurementStream, ServiceCharacteristics,
+};
+use std::error::Error;
+
+pub static BLOOD_PRESSURE_SERVICE_UUID: &'static str = "00001810-0000-1000-8000-00805f9b34fb";
+pub static BLOOD_PRESSURE_MEASUREMENT_UUID: &'static str = "00002a35-0000-1000-8000-00805f9b34fb";
+pub static INTERMEDIATE_CUFF_PRESSURE_UUID: &'static str = "00002a36-0000-1000-8000-00805f9b34fb";
+pub static BLOOD_PRESSURE_FEATURE_UUID: &'static str = "00002a49-0000-1000-8000-00805f9b34fb";
+
+const FLAG_KPA: u8 = 0x01;
+const FLAG_TIMESTAMP: u8 = 0x02;
+const FLAG_PULSE_RATE: u8 = 0x04;
+const FLAG_USER_ID: u8 = 0x08;
+const FLAG_MEASUREMENT_STATUS: u8 = 0x10;
+
+// Measurement Status bits.
+pub const STATUS_BODY_MOVEMENT: u16 = 0x0001;
+pub const STATUS_CUFF_TOO_LOOSE: u16 = 0x0002;
+pub const STATUS_IRREGULAR_PULSE: u16 = 0x0004;
+pub const STATUS_PULSE_RATE_RANGE_MASK: u16 = 0x0018;
+pub const STATUS_IMPROPER_POSITION: u16 = 0x0020;
+
+// Blood Pressure Feature bits.
+pub const FEATURE_BODY_MOVEMENT_DETECTION: u16 = 0x0001;
+pub const FEATURE_CUFF_FIT_DETECTION: u16 = 0x0002;
+pub const FEATURE_IRREGULAR_PULSE_DETECTION: u16 = 0x0004;
+pub const FEATURE_PULSE_RATE_RANGE_DETECTION: u16 = 0x0008;
+pub const FEATURE_MEASUREMENT_POSITION_DETECTION: u16 = 0x0010;
+pub const FEATURE_MULTIPLE_BOND: u16 = 0x0020;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PressureUnit {
+    MillimetersOfMercury,
+    Kilopascal,
+}
+
+// Blood Pressure Measurement and Intermediate Cuff Pressure share this layout,
+// for the latter only `systolic` carries the current cuff pressure.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BloodPressureMeasurement {
+    pub systolic: f64,
+    pub diastolic: f64,
+    pub mean_arterial_pressure: f64,
+    pub unit: PressureUnit,
+    pub timestamp: Option<DateTime>,
+    // Beats per minute.
+    pub pulse_rate: Option<f64>,
+    pub user_id: Option<u8>,
+    // Combination of the STATUS_* bits.
+    pub measurement_status: Option<u16>,
+}
+
+impl BloodPressureMeasurement {
+    pub fn parse(value: &[u8]) -> Result<BloodPressureMeasurement, Box<Error>> {
+        try!(check_length("Blood Pressure Measurement", value, 7));
+        let flags = value[0];
+        let mut offset = 7;
+
+        let timestamp = if flags & FLAG_TIMESTAMP != 0 {
+            try!(check_length(
+                "Blood Pressure Measurement",
+                value,
+                offset + 7
+            ));
+            offset += 7;
+            Some(try!(DateTime::parse(&value[offset - 7..])))
+        } else {
+            None
+        };
+
+        let pulse_rate = if flags & FLAG_PULSE_RATE != 0 {
+            try!(check_length(
+                "Blood Pressure Measurement",
+                value,
+                offset + 2
+            ));
+            offset += 2;
+            Some(read_sfloat(value, offset - 2))
+        } else {
+            None
+        };
+
+        let user_id = if flags & FLAG_USER_ID != 0 {
+            try!(check_length(
+                "Blood Pressure Measurement",
+                value,
+                offset + 1
+            ));
+            offset += 1;
+            Some(value[offset - 1])
+        } else {
+            None
+        };
+
+        let measurement_status = if flags & FLAG_MEASUREMENT_STATUS != 0 {
+            try!(check_length(
+                "Blood Pressure Measurement",
+                value,
+                offset + 2
+            ));
+            Some(read_u16(value, offset))
+        } else {
+            None
+        };
+
+        Ok(BloodPressureMeasurement {
+            systolic: read_sfloat(value, 1),
+            diastolic: read_sfloat(value, 3),
+            mean_arterial_pressure: read_sfloat(value, 5),
+            unit: if flags & FLAG_KPA != 0 {
+                PressureUnit::Kilopascal
+            } else {
+                PressureUnit::MillimetersOfMercury
+            },
+            timestamp: timestamp,
+            pulse_rate: pulse_rate,
+            user_id: user_id,
+            measurement_status: measurement_status,
+        })
+    }
+}
+
+pub struct BloodPressureService<'a> {
+    measurement: BluetoothGATTCharacteristic<'a>,
+    intermediate_cuff_pressure: Option<BluetoothGATTCharacteristic<'a>>,
+    feature: BluetoothGATTCharacteristic<'a>,
+}
+
+impl<'a> BloodPressureService<'a> {
+    pub fn new(device: &BluetoothDevice<'a>) -> Result<BloodPressureService<'a>, Box<Error>> {
+        let characteristics = try!(ServiceCharacteristics::fetch(
+            device,
+            BLOOD_PRESSURE_SERVICE_UUID
+        ));
+        Ok(BloodPressureService {
+            measurement: try!(characteristics.require(
+                BLOOD_PRESSURE_MEASUREMENT_UUID,
+                "Blood Pressure Measurement"
+            )),
+            intermediate_cuff_pressure: characteristics.find(INTERMEDIATE_CUFF_PRESSURE_UUID),
+            feature: try!(
+                characteristics.require(BLOOD_PRESSURE_FEATURE_UUID, "Blood Pressure Feature")
+            ),
+        })
+    }
+
+    // Combination of the FEATURE_* bits.
+    pub fn get_feature(&self) -> Result<u16, Box<Error>> {
+        let value = try!(self.feature.read_value(None));
+        try!(check_length("Blood Pressure Feature", &value, 2));
+        Ok(read_u16(&value, 0))
+    }
+
+    pub fn start_indicate(&self) -> Result<(), Box<Error>> {
+        self.measurement.start_notify()
+    }
+
+    pub fn stop_indicate(&self) -> Result<(), Box<Error>> {
+        self.measurement.stop_notify()
+    }
+
+    pub fn measurements(&self, timeout_ms: u32) -> MeasurementStream<'a, BloodPressureMeasurement> {
+        MeasurementStream::new(
+            self.measurement.notifications(timeout_ms),
+            BloodPressureMeasurement::parse,
+        )
+    }
+
+    pub fn start_cuff_pressure_notify(&self) -> Result<(), Box<Error>> {
+        match self.intermediate_cuff_pressure {
+            Some(ref c) => c.start_notify(),
+            None => Err(Box::from("Intermediate Cuff Pressure not supported")),
+        }
+    }
+
+    pub fn stop_cuff_pressure_notify(&self) -> Result<(), Box<Error>> {
+        match self.intermediate_cuff_pressure {
+            Some(ref c) => c.stop_notify(),
+            None => Err(Box::from("Intermediate Cuff Pressure not supported")),
+        }
+    }
+
+    pub fn cuff_pressures(
+        &self,
+        timeout_ms: u32,
+    ) -> Result<MeasurementStream<'a, BloodPressureMeasurement>, Box<Error>> {
+        match self.intermediate_cuff_pressure {
+            Some(ref c) => Ok(MeasurementStream::new(
+                c.notifications(timeout_ms),
+                BloodPressureMeasurement::parse,
+            )),
+            None => Err(Box::from("Intermediate Cuff Pressure not supported")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BloodPressureMeasurement, PressureUnit, STATUS_IRREGULAR_PULSE};
+    use profiles::DateTime;
+
+    #[test]
+    fn parse_all_fields() {
+        // 120/80 mmHg, MAP 93, 2019-01-02 03:04:05, 72 bpm, user 1, irregular pulse.
+        let value = [
+            0x1E, 0x78, 0x00, 0x50, 0x00, 0x5D, 0x00, 0xE3, 0x07, 0x01, 0x02, 0x03, 0x04, 0x05,
+            0x48, 0x00, 0x01, 0x04, 0x00,
+        ];
+        assert_eq!(
+            BloodPressureMeasurement::parse(&value).unwrap(),
+            BloodPressureMeasurement {
+                systolic: 120.0,
+                diastolic: 80.0,
+                mean_arterial_pressure: 93.0,
+                unit: PressureUnit::MillimetersOfMercury,
+                timestamp: Some(DateTime {
+                    year: 2019,
+                    month: 1,
+                    day: 2,
+                    hours: 3,
+                    minutes: 4,
+                    seconds: 5,
+                }),
+                pulse_rate: Some(72.0),
+                user_id: Some(1),
+                measurement_status: Some(STATUS_IRREGULAR_PULSE),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_kilopascal_without_optional_fields() {
+        // 16.0/10.7 kPa, MAP 12.5 as SFLOATs with exponent -1.
+        let value = [0x01, 0xA0, 0xF0, 0x6B, 0xF0, 0x7D, 0xF0];
+        let measurement = BloodPressureMeasurement::parse(&value).unwrap();
+        assert_eq!(measurement.unit, PressureUnit::Kilopascal);
+        assert!((measurement.systolic - 16.0).abs() < 1e-9);
+        assert!((measurement.diastolic - 10.7).abs() < 1e-9);
+        assert!((measurement.mean_arterial_pressure - 12.5).abs() < 1e-9);
+        assert_eq!(measurement.timestamp, None);
+        assert_eq!(measurement.pulse_rate, None);
+        assert_eq!(measurement.user_id, None);
+        assert_eq!(measurement.measurement_status, None);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert!(BloodPressureMeasurement::parse(&[0x00, 0x78, 0x00, 0x50, 0x00, 0x5D]).is_err());
+        // Timestamp flagged but missing.
+        let value = [0x02, 0x78, 0x00, 0x50, 0x00, 0x5D, 0x00, 0xE3, 0x07];
+        assert!(BloodPressureMeasurement::parse(&value).is_err());
+        // Measurement status flagged but only one byte present.
+        let value = [0x10, 0x78, 0x00, 0x50, 0x00, 0x5D, 0x00, 0x04];
+        assert!(BloodPressureMeasurement::parse(&value).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+use bluetooth_device::BluetoothDevice;
+use bluetooth_gatt_characteristic::BluetoothGATTCharacteristic;
+use profiles::{check_length, DateTime, MeasurementStream, ServiceCharacteristics};
+use std::error::Error;
+
+pub static CURRENT_TIME_SERVICE_UUID: &'static str = "00001805-0000-1000-8000-00805f9b34fb";
+pub static CURRENT_TIME_UUID: &'static str = "00002a2b-0000-1000-8000-00805f9b34fb";
+pub static LOCAL_TIME_INFORMATION_UUID: &'static str = "00002a0f-0000-1000-8000-00805f9b34fb";
+pub static REFERENCE_TIME_INFORMATION_UUID: &'static str = "00002a14-0000-1000-8000-00805f9b34fb";
+
+pub const ADJUST_REASON_MANUAL: u8 = 0x01;
+pub const ADJUST_REASON_EXTERNAL_REFERENCE: u8 = 0x02;
+pub const ADJUST_REASON_TIME_ZONE: u8 = 0x04;
+pub const ADJUST_REASON_DST: u8 = 0x08;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct CurrentTime {
+    pub date_time: DateTime,
+    // 1 = Monday ... 7 = Sunday, 0 = unknown.
+    pub day_of_week: u8,
+    // 1/256 second units.
+    pub fractions256: u8,
+    // Combination of the ADJUST_REASON_* bits.
+    pub adjust_reason: u8,
+}
+
+impl CurrentTime {
+    pub fn parse(value: &[u8]) -> Result<CurrentTime, Box<Error>> {
+        try!(check_length("Current Time", value, 10));
+        Ok(CurrentTime {
+            date_time: try!(DateTime::parse(value)),
+            day_of_week: value[7],
+            fractions256: value[8],
+            adjust_reason: value[9],
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.date_time.to_bytes();
+        bytes.push(self.day_of_week);
+        bytes.push(self.fractions256);
+        bytes.push(self.adjust_reason);
+        bytes
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocalTimeInformation {
+    // Offset from UTC in 15 minute steps, -128 if unknown.
+    pub time_zone: i8,
+    // 0 standard, 2 half hour, 4 daylight, 8 double daylight, 255 unknown.
+    pub dst_offset: u8,
+}
+
+impl LocalTimeInformation {
+    pub fn parse(value: &[u8]) -> Result<LocalTimeInformation, Box<Error>> {
+        try!(check_length("Local Time Information", value, 2));
+        Ok(LocalTimeInformation {
+            time_zone: value[0] as i8,
+            dst_offset: value[1],
+        })
+    }
+
+    pub fn utc_offset_minutes(&self) -> Option<i32> {
+        if self.time_zone == -128 {
+            return None;
+        }
+        let dst = match self.dst_offset {
+            0 => 0,
+            2 => 30,
+            4 => 60,
+            8 => 120,
+            _ => return None,
+        };
+        Some(self.time_zone as i32 * 15 + dst)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeSource {
+    Unknown,
+    NetworkTimeProtocol,
+    Gps,
+    RadioTimeSignal,
+    Manual,
+    AtomicClock,
+    CellularNetwork,
+    Reserved(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReferenceTimeInformation {
+    pub source: TimeSource,
+    // 1/8 second steps, 254 = more than 31.625 s, 255 = unknown.
+    pub accuracy: u8,
+    pub days_since_update: u8,
+    pub hours_since_update: u8,
+}
+
+impl ReferenceTimeInformation {
+    pub fn parse(value: &[u8]) -> Result<ReferenceTimeInformation, Box<Error>> {
+        try!(check_length("Reference Time Information", value, 4));
+        Ok(ReferenceTimeInformation {
+            source: match value[0] {
+                0 => TimeSource::Unknown,
+                1 => TimeSource::NetworkTimeProtocol,
+                2 => TimeSource::Gps,
+                3 => TimeSource::RadioTimeSignal,
+                4 => TimeSource::Manual,
+                5 => TimeSource::AtomicClock,
+                6 => TimeSource::CellularNetwork,
+                other => TimeSource::Reserved(other),
+            },
+            accuracy: value[1],
+            days_since_update: value[2],
+            hours_since_update: value[3],
+        })
+    }
+}
+
+pub struct CurrentTimeService<'a> {
+    current_time: BluetoothGATTCharacteristic<'a>,
+    local_time_information: Option<BluetoothGATTCharacteristic<'a>>,
+    reference_time_information: Option<BluetoothGATTCharacteristic<'a>>,
+}
+
+impl<'a> CurrentTimeService<'a> {
+    pub fn new(device: &BluetoothDevice<'a>) -> Result<CurrentTimeService<'a>, Box<Error>> {
+        let characteristics = try!(ServiceCharacteristics::fetch(
+            device,
+            CURRENT_TIME_SERVICE_UUID
+        ));
+        Ok(CurrentTimeService {
+            current_time: try!(characteristics.require(CURRENT_TIME_UUID, "Current Time")),
+            local_time_information: characteristics.find(LOCAL_TIME_INFORMATION_UUID),
+            reference_time_information: characteristics.find(REFERENCE_TIME_INFORMATION_UUID),
+        })
+    }
+
+    pub fn get_current_time(&self) -> Result<CurrentTime, Box<Error>> {
+        CurrentTime::parse(&try!(self.current_time.read_value(None)))
+    }
+
+    // Writing is optional in the service, devices without it reply with an error.
+    pub fn set_current_time(&self, current_time: &CurrentTime) -> Result<(), Box<Error>> {
+        self.current_time.write_value(current_time.to_bytes(), None)
+    }
+
+    pub fn start_notify(&self) -> Result<(), Box<Error>> {
+        self.current_time.start_notify()
+    }
+
+    pub fn stop_notify(&self) -> Result<(), Box<Error>> {
+        self.current_time.stop_notify()
+    }
+
+    pub fn time_updates(&self, timeout_ms: u32) -> MeasurementStream<'a, CurrentTime> {
+        MeasurementStream::new(
+            self.current_time.notifications(timeout_ms),
+            CurrentTime::parse,
+        )
+    }
+
+    pub fn get_local_time_information(&self) -> Result<LocalTimeInformation, Box<Error>> {
+        match self.local_time_information {
+            Some(ref c) => LocalTimeInformation::parse(&try!(c.read_value(None))),
+            None => Err(Box::from("Local Time Information not supported")),
+        }
+    }
+
+    pub fn get_reference_time_information(&self) -> Result<ReferenceTimeInformation, Box<Error>> {
+        match self.reference_time_information {
+            Some(ref c) => ReferenceTimeInformation::parse(&try!(c.read_value(None))),
+            None => Err(Box::from("Reference Time Information not supported")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CurrentTime, LocalTimeInformation, ReferenceTimeInformation, TimeSource,
+        ADJUST_REASON_MANUAL, ADJUST_REASON_TIME_ZONE,
+    };
+    use profiles::DateTime;
+
+    #[test]
+    fn parse_current_time() {
+        // 2019-12-31 23:59:30, Tuesday, 128/256 s, manual time zone change.
+        let value = [0xE3, 0x07, 0x0C, 0x1F, 0x17, 0x3B, 0x1E, 0x02, 0x80, 0x05];
+        let current_time = CurrentTime::parse(&value).unwrap();
+        assert_eq!(
+            current_time,
+            CurrentTime {
+                date_time: DateTime {
+                    year: 2019,
+                    month: 12,
+                    day: 31,
+                    hours: 23,
+                    minutes: 59,
+                    seconds: 30,
+                },
+                day_of_week: 2,
+                fractions256: 128,
+                adjust_reason: ADJUST_REASON_MANUAL | ADJUST_REASON_TIME_ZONE,
+            }
+        );
+        assert_eq!(current_time.to_bytes(), value.to_vec());
+    }
+
+    #[test]
+    fn parse_current_time_truncated() {
+        assert!(
+            CurrentTime::parse(&[0xE3, 0x07, 0x0C, 0x1F, 0x17, 0x3B, 0x1E, 0x02, 0x80]).is_err()
+        );
+    }
+
+    #[test]
+    fn parse_local_time_information() {
+        // UTC+1 with daylight saving time.
+        let local = LocalTimeInformation::parse(&[0x04, 0x04]).unwrap();
+        assert_eq!(local.utc_offset_minutes(), Some(120));
+        // UTC-3:30, standard time.
+        let local = LocalTimeInformation::parse(&[0xF2, 0x00]).unwrap();
+        assert_eq!(local.utc_offset_minutes(), Some(-210));
+        let local = LocalTimeInformation::parse(&[0x80, 0x00]).unwrap();
+        assert_eq!(local.utc_offset_minutes(), None);
+        assert!(LocalTimeInformation::parse(&[0x04]).is_err());
+    }
+
+    #[test]
+    fn parse_reference_time_information() {
+        assert_eq!(
+            ReferenceTimeInformation::parse(&[0x02, 0x08, 0x01, 0x05]).unwrap(),
+            ReferenceTimeInformation {
+                source: TimeSource::Gps,
+                accuracy: 8,
+                days_since_update: 1,
+                hours_since_update: 5,
+            }
+        );
+        assert_eq!(
+            ReferenceTimeInformation::parse(&[0x09, 0xFF, 0x00, 0x00])
+                .unwrap()
+                .source,
+            TimeSource::Reserved(9)
+        );
+        assert!(ReferenceTimeInformation::parse(&[0x02, 0x08, 0x01]).is_err());
+    }
+}
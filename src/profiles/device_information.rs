@@ -0,0 +1,171 @@
+use bluetooth_device::BluetoothDevice;
+use profiles::{check_length, read_u16, ServiceCharacteristics};
+use std::error::Error;
+
+pub static DEVICE_INFORMATION_SERVICE_UUID: &'static str = "0000180a-0000-1000-8000-00805f9b34fb";
+pub static SYSTEM_ID_UUID: &'static str = "00002a23-0000-1000-8000-00805f9b34fb";
+pub static MODEL_NUMBER_UUID: &'static str = "00002a24-0000-1000-8000-00805f9b34fb";
+pub static SERIAL_NUMBER_UUID: &'static str = "00002a25-0000-1000-8000-00805f9b34fb";
+pub static FIRMWARE_REVISION_UUID: &'static str = "00002a26-0000-1000-8000-00805f9b34fb";
+pub static HARDWARE_REVISION_UUID: &'static str = "00002a27-0000-1000-8000-00805f9b34fb";
+pub static SOFTWARE_REVISION_UUID: &'static str = "00002a28-0000-1000-8000-00805f9b34fb";
+pub static MANUFACTURER_NAME_UUID: &'static str = "00002a29-0000-1000-8000-00805f9b34fb";
+pub static PNP_ID_UUID: &'static str = "00002a50-0000-1000-8000-00805f9b34fb";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VendorIdSource {
+    Bluetooth,
+    Usb,
+    Unknown(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PnpId {
+    pub vendor_id_source: VendorIdSource,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub product_version: u16,
+}
+
+impl PnpId {
+    pub fn parse(value: &[u8]) -> Result<PnpId, Box<Error>> {
+        try!(check_length("PnP ID", value, 7));
+        Ok(PnpId {
+            vendor_id_source: match value[0] {
+                0x01 => VendorIdSource::Bluetooth,
+                0x02 => VendorIdSource::Usb,
+                other => VendorIdSource::Unknown(other),
+            },
+            vendor_id: read_u16(value, 1),
+            product_id: read_u16(value, 3),
+            product_version: read_u16(value, 5),
+        })
+    }
+}
+
+// Every field is optional in the service, missing characteristics are None.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeviceInformation {
+    pub manufacturer_name: Option<String>,
+    pub model_number: Option<String>,
+    pub serial_number: Option<String>,
+    pub hardware_revision: Option<String>,
+    pub firmware_revision: Option<String>,
+    pub software_revision: Option<String>,
+    pub system_id: Option<Vec<u8>>,
+    pub pnp_id: Option<PnpId>,
+}
+
+pub struct DeviceInformationService<'a> {
+    characteristics: ServiceCharacteristics<'a>,
+}
+
+impl<'a> DeviceInformationService<'a> {
+    pub fn new(device: &BluetoothDevice<'a>) -> Result<DeviceInformationService<'a>, Box<Error>> {
+        let characteristics = try!(ServiceCharacteristics::fetch(
+            device,
+            DEVICE_INFORMATION_SERVICE_UUID
+        ));
+        Ok(DeviceInformationService {
+            characteristics: characteristics,
+        })
+    }
+
+    fn read(&self, uuid: &str) -> Result<Option<Vec<u8>>, Box<Error>> {
+        match self.characteristics.find(uuid) {
+            Some(characteristic) => Ok(Some(try!(characteristic.read_value(None)))),
+            None => Ok(None),
+        }
+    }
+
+    fn read_string(&self, uuid: &str) -> Result<Option<String>, Box<Error>> {
+        Ok(try!(self.read(uuid)).map(|v| String::from_utf8_lossy(&v).into_owned()))
+    }
+
+    pub fn get_manufacturer_name(&self) -> Result<Option<String>, Box<Error>> {
+        self.read_string(MANUFACTURER_NAME_UUID)
+    }
+
+    pub fn get_model_number(&self) -> Result<Option<String>, Box<Error>> {
+        self.read_string(MODEL_NUMBER_UUID)
+    }
+
+    pub fn get_serial_number(&self) -> Result<Option<String>, Box<Error>> {
+        self.read_string(SERIAL_NUMBER_UUID)
+    }
+
+    pub fn get_hardware_revision(&self) -> Result<Option<String>, Box<Error>> {
+        self.read_string(HARDWARE_REVISION_UUID)
+    }
+
+    pub fn get_firmware_revision(&self) -> Result<Option<String>, Box<Error>> {
+        self.read_string(FIRMWARE_REVISION_UUID)
+    }
+
+    pub fn get_software_revision(&self) -> Result<Option<String>, Box<Error>> {
+        self.read_string(SOFTWARE_REVISION_UUID)
+    }
+
+    pub fn get_system_id(&self) -> Result<Option<Vec<u8>>, Box<Error>> {
+        self.read(SYSTEM_ID_UUID)
+    }
+
+    pub fn get_pnp_id(&self) -> Result<Option<PnpId>, Box<Error>> {
+        match try!(self.read(PNP_ID_UUID)) {
+            Some(value) => Ok(Some(try!(PnpId::parse(&value)))),
+            None => Ok(None),
+        }
+    }
+
+    pub fn read_all(&self) -> Result<DeviceInformation, Box<Error>> {
+        Ok(DeviceInformation {
+            manufacturer_name: try!(self.get_manufacturer_name()),
+            model_number: try!(self.get_model_number()),
+            serial_number: try!(self.get_serial_number()),
+            hardware_revision: try!(self.get_hardware_revision()),
+            firmware_revision: try!(self.get_firmware_revision()),
+            software_revision: try!(self.get_software_revision()),
+            system_id: try!(self.get_system_id()),
+            pnp_id: try!(self.get_pnp_id()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PnpId, VendorIdSource};
+
+    #[test]
+    fn parse_pnp_id() {
+        // Bluetooth SIG assigned vendor 0x000F, product 0x1234, version 1.2.3.
+        let value = [0x01, 0x0F, 0x00, 0x34, 0x12, 0x23, 0x01];
+        assert_eq!(
+            PnpId::parse(&value).unwrap(),
+            PnpId {
+                vendor_id_source: VendorIdSource::Bluetooth,
+                vendor_id: 0x000F,
+                product_id: 0x1234,
+                product_version: 0x0123,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_pnp_id_vendor_sources() {
+        let value = [0x02, 0x6B, 0x1D, 0x01, 0x00, 0x00, 0x01];
+        assert_eq!(
+            PnpId::parse(&value).unwrap().vendor_id_source,
+            VendorIdSource::Usb
+        );
+        let value = [0x07, 0x6B, 0x1D, 0x01, 0x00, 0x00, 0x01];
+        assert_eq!(
+            PnpId::parse(&value).unwrap().vendor_id_source,
+            VendorIdSource::Unknown(7)
+        );
+    }
+
+    #[test]
+    fn parse_pnp_id_truncated() {
+        assert!(PnpId::parse(&[0x01, 0x0F, 0x00, 0x34, 0x12, 0x23]).is_err());
+    }
+}
@@ -0,0 +1,289 @@
+use bluetooth_device::BluetoothDevice;
+use bluetooth_gatt_characteristic::BluetoothGATTCharacteristic;
+use formats::gatt_descriptors::{self, EnvironmentalSensingMeasurement};
+use profiles::{
+    check_length, read_i16, read_u16, read_u32, MeasurementStream, ServiceCharacteristics,
+};
+use std::error::Error;
+
+pub static ENVIRONMENTAL_SENSING_SERVICE_UUID: &'static str =
+    "0000181a-0000-1000-8000-00805f9b34fb";
+pub static ELEVATION_UUID: &'static str = "00002a6c-0000-1000-8000-00805f9b34fb";
+pub static PRESSURE_UUID: &'static str = "00002a6d-0000-1000-8000-00805f9b34fb";
+pub static TEMPERATURE_UUID: &'static str = "00002a6e-0000-1000-8000-00805f9b34fb";
+pub static HUMIDITY_UUID: &'static str = "00002a6f-0000-1000-8000-00805f9b34fb";
+pub static TRUE_WIND_SPEED_UUID: &'static str = "00002a70-0000-1000-8000-00805f9b34fb";
+pub static TRUE_WIND_DIRECTION_UUID: &'static str = "00002a71-0000-1000-8000-00805f9b34fb";
+pub static UV_INDEX_UUID: &'static str = "00002a76-0000-1000-8000-00805f9b34fb";
+pub static IRRADIANCE_UUID: &'static str = "00002a77-0000-1000-8000-00805f9b34fb";
+pub static DEW_POINT_UUID: &'static str = "00002a7b-0000-1000-8000-00805f9b34fb";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EnvironmentalQuantity {
+    Elevation,
+    Pressure,
+    Temperature,
+    Humidity,
+    TrueWindSpeed,
+    TrueWindDirection,
+    UvIndex,
+    Irradiance,
+    DewPoint,
+}
+
+pub static ENVIRONMENTAL_QUANTITIES: [EnvironmentalQuantity; 9] = [
+    EnvironmentalQuantity::Elevation,
+    EnvironmentalQuantity::Pressure,
+    EnvironmentalQuantity::Temperature,
+    EnvironmentalQuantity::Humidity,
+    EnvironmentalQuantity::TrueWindSpeed,
+    EnvironmentalQuantity::TrueWindDirection,
+    EnvironmentalQuantity::UvIndex,
+    EnvironmentalQuantity::Irradiance,
+    EnvironmentalQuantity::DewPoint,
+];
+
+impl EnvironmentalQuantity {
+    pub fn uuid(&self) -> &'static str {
+        match *self {
+            EnvironmentalQuantity::Elevation => ELEVATION_UUID,
+            EnvironmentalQuantity::Pressure => PRESSURE_UUID,
+            EnvironmentalQuantity::Temperature => TEMPERATURE_UUID,
+            EnvironmentalQuantity::Humidity => HUMIDITY_UUID,
+            EnvironmentalQuantity::TrueWindSpeed => TRUE_WIND_SPEED_UUID,
+            EnvironmentalQuantity::TrueWindDirection => TRUE_WIND_DIRECTION_UUID,
+            EnvironmentalQuantity::UvIndex => UV_INDEX_UUID,
+            EnvironmentalQuantity::Irradiance => IRRADIANCE_UUID,
+            EnvironmentalQuantity::DewPoint => DEW_POINT_UUID,
+        }
+    }
+
+    // Unit of the decoded value.
+    pub fn unit_symbol(&self) -> &'static str {
+        match *self {
+            EnvironmentalQuantity::Elevation => "m",
+            EnvironmentalQuantity::Pressure => "Pa",
+            EnvironmentalQuantity::Temperature | EnvironmentalQuantity::DewPoint => "°C",
+            EnvironmentalQuantity::Humidity => "%",
+            EnvironmentalQuantity::TrueWindSpeed => "m/s",
+            EnvironmentalQuantity::TrueWindDirection => "°",
+            EnvironmentalQuantity::UvIndex => "",
+            EnvironmentalQuantity::Irradiance => "W/m²",
+        }
+    }
+
+    pub fn decode(&self, value: &[u8]) -> Result<f64, Box<Error>> {
+        (self.parser())(value)
+    }
+
+    fn parser(&self) -> fn(&[u8]) -> Result<f64, Box<Error>> {
+        match *self {
+            EnvironmentalQuantity::Elevation => parse_elevation,
+            EnvironmentalQuantity::Pressure => parse_pressure,
+            EnvironmentalQuantity::Temperature => parse_temperature,
+            EnvironmentalQuantity::Humidity => parse_humidity,
+            EnvironmentalQuantity::TrueWindSpeed => parse_true_wind_speed,
+            EnvironmentalQuantity::TrueWindDirection => parse_true_wind_direction,
+            EnvironmentalQuantity::UvIndex => parse_uv_index,
+            EnvironmentalQuantity::Irradiance => parse_irradiance,
+            EnvironmentalQuantity::DewPoint => parse_dew_point,
+        }
+    }
+}
+
+// sint24, 0.01 m
+pub fn parse_elevation(value: &[u8]) -> Result<f64, Box<Error>> {
+    try!(check_length("Elevation", value, 3));
+    let raw = value[0] as u32 | (value[1] as u32) << 8 | (value[2] as u32) << 16;
+    Ok((((raw << 8) as i32) >> 8) as f64 * 0.01)
+}
+
+// uint32, 0.1 Pa
+pub fn parse_pressure(value: &[u8]) -> Result<f64, Box<Error>> {
+    try!(check_length("Pressure", value, 4));
+    Ok(read_u32(value, 0) as f64 * 0.1)
+}
+
+// sint16, 0.01 °C
+pub fn parse_temperature(value: &[u8]) -> Result<f64, Box<Error>> {
+    try!(check_length("Temperature", value, 2));
+    Ok(read_i16(value, 0) as f64 * 0.01)
+}
+
+// uint16, 0.01 %
+pub fn parse_humidity(value: &[u8]) -> Result<f64, Box<Error>> {
+    try!(check_length("Humidity", value, 2));
+    Ok(read_u16(value, 0) as f64 * 0.01)
+}
+
+// uint16, 0.01 m/s
+pub fn parse_true_wind_speed(value: &[u8]) -> Result<f64, Box<Error>> {
+    try!(check_length("True Wind Speed", value, 2));
+    Ok(read_u16(value, 0) as f64 * 0.01)
+}
+
+// uint16, 0.01 degrees
+pub fn parse_true_wind_direction(value: &[u8]) -> Result<f64, Box<Error>> {
+    try!(check_length("True Wind Direction", value, 2));
+    Ok(read_u16(value, 0) as f64 * 0.01)
+}
+
+// uint8
+pub fn parse_uv_index(value: &[u8]) -> Result<f64, Box<Error>> {
+    try!(check_length("UV Index", value, 1));
+    Ok(value[0] as f64)
+}
+
+// uint16, 0.1 W/m²
+pub fn parse_irradiance(value: &[u8]) -> Result<f64, Box<Error>> {
+    try!(check_length("Irradiance", value, 2));
+    Ok(read_u16(value, 0) as f64 * 0.1)
+}
+
+// sint8, 1 °C
+pub fn parse_dew_point(value: &[u8]) -> Result<f64, Box<Error>> {
+    try!(check_length("Dew Point", value, 1));
+    Ok(value[0] as i8 as f64)
+}
+
+// Only the first characteristic of each quantity is used when a sensor exposes
+// several instances of it.
+pub struct EnvironmentalSensingService<'a> {
+    characteristics: Vec<(EnvironmentalQuantity, BluetoothGATTCharacteristic<'a>)>,
+}
+
+impl<'a> EnvironmentalSensingService<'a> {
+    pub fn new(
+        device: &BluetoothDevice<'a>,
+    ) -> Result<EnvironmentalSensingService<'a>, Box<Error>> {
+        let service = try!(ServiceCharacteristics::fetch(
+            device,
+            ENVIRONMENTAL_SENSING_SERVICE_UUID
+        ));
+        let mut characteristics = Vec::new();
+        for quantity in ENVIRONMENTAL_QUANTITIES.iter() {
+            if let Some(c) = service.find(quantity.uuid()) {
+                characteristics.push((*quantity, c));
+            }
+        }
+        Ok(EnvironmentalSensingService {
+            characteristics: characteristics,
+        })
+    }
+
+    pub fn get_quantities(&self) -> Vec<EnvironmentalQuantity> {
+        self.characteristics.iter().map(|&(q, _)| q).collect()
+    }
+
+    pub fn read(&self, quantity: EnvironmentalQuantity) -> Result<f64, Box<Error>> {
+        let characteristic = try!(self.get(quantity));
+        quantity.decode(&try!(characteristic.read_value(None)))
+    }
+
+    pub fn get_temperature(&self) -> Result<f64, Box<Error>> {
+        self.read(EnvironmentalQuantity::Temperature)
+    }
+
+    pub fn get_humidity(&self) -> Result<f64, Box<Error>> {
+        self.read(EnvironmentalQuantity::Humidity)
+    }
+
+    pub fn get_pressure(&self) -> Result<f64, Box<Error>> {
+        self.read(EnvironmentalQuantity::Pressure)
+    }
+
+    // Sampling function, interval and application from the ES Measurement descriptor.
+    pub fn get_measurement_info(
+        &self,
+        quantity: EnvironmentalQuantity,
+    ) -> Result<Option<EnvironmentalSensingMeasurement>, Box<Error>> {
+        let characteristic = try!(self.get(quantity));
+        let uuid = gatt_descriptors::ENVIRONMENTAL_SENSING_MEASUREMENT_UUID;
+        match try!(characteristic.find_descriptor(uuid)) {
+            Some(descriptor) => {
+                let value = try!(descriptor.read_value(None));
+                Ok(Some(try!(EnvironmentalSensingMeasurement::parse(&value))))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn start_notify(&self, quantity: EnvironmentalQuantity) -> Result<(), Box<Error>> {
+        try!(self.get(quantity)).start_notify()
+    }
+
+    pub fn stop_notify(&self, quantity: EnvironmentalQuantity) -> Result<(), Box<Error>> {
+        try!(self.get(quantity)).stop_notify()
+    }
+
+    pub fn updates(
+        &self,
+        quantity: EnvironmentalQuantity,
+        timeout_ms: u32,
+    ) -> Result<MeasurementStream<'a, f64>, Box<Error>> {
+        let characteristic = try!(self.get(quantity));
+        Ok(MeasurementStream::new(
+            characteristic.notifications(timeout_ms),
+            quantity.parser(),
+        ))
+    }
+
+    fn get(
+        &self,
+        quantity: EnvironmentalQuantity,
+    ) -> Result<&BluetoothGATTCharacteristic<'a>, Box<Error>> {
+        match self.characteristics.iter().find(|&&(q, _)| q == quantity) {
+            Some(&(_, ref c)) => Ok(c),
+            None => Err(Box::from(format!("{:?} not supported", quantity))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: f64, expected: f64) {
+        assert!((value - expected).abs() < 1e-9, "{} != {}", value, expected);
+    }
+
+    #[test]
+    fn parse_signed_values() {
+        assert_close(parse_elevation(&[0x6A, 0xFF, 0xFF]).unwrap(), -1.5);
+        assert_close(parse_elevation(&[0xA0, 0x86, 0x01]).unwrap(), 1000.0);
+        assert_close(parse_temperature(&[0xF3, 0xFD]).unwrap(), -5.25);
+        assert_close(parse_dew_point(&[0xFD]).unwrap(), -3.0);
+    }
+
+    #[test]
+    fn parse_unsigned_values() {
+        assert_close(parse_pressure(&[0x02, 0x76, 0x0F, 0x00]).unwrap(), 101325.0);
+        assert_close(parse_humidity(&[0xC6, 0x11]).unwrap(), 45.5);
+        assert_close(parse_true_wind_speed(&[0xF4, 0x01]).unwrap(), 5.0);
+        assert_close(parse_true_wind_direction(&[0x28, 0x23]).unwrap(), 90.0);
+        assert_close(parse_uv_index(&[0x07]).unwrap(), 7.0);
+        assert_close(parse_irradiance(&[0xE8, 0x03]).unwrap(), 100.0);
+    }
+
+    #[test]
+    fn decode_by_quantity() {
+        assert_close(
+            EnvironmentalQuantity::Humidity
+                .decode(&[0xC6, 0x11])
+                .unwrap(),
+            45.5,
+        );
+        assert_eq!(EnvironmentalQuantity::Pressure.unit_symbol(), "Pa");
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert!(parse_elevation(&[0x6A, 0xFF]).is_err());
+        assert!(parse_pressure(&[0x02, 0x76, 0x0F]).is_err());
+        assert!(parse_temperature(&[0xF3]).is_err());
+        assert!(parse_uv_index(&[]).is_err());
+        for quantity in ENVIRONMENTAL_QUANTITIES.iter() {
+            assert!(quantity.decode(&[]).is_err());
+        }
+    }
+}
@@ -0,0 +1,395 @@
+use bluetooth_device::BluetoothDevice;
+use bluetooth_gatt_characteristic::{self, BluetoothGATTCharacteristic};
+use profiles::{
+    check_length, read_i16, read_sfloat, read_u16, DateTime, MeasurementStream,
+    ServiceCharacteristics,
+};
+use std::error::Error;
+use std::fmt;
+
+pub static GLUCOSE_SERVICE_UUID: &'static str = "00001808-0000-1000-8000-00805f9b34fb";
+pub static GLUCOSE_MEASUREMENT_UUID: &'static str = "00002a18-0000-1000-8000-00805f9b34fb";
+pub static GLUCOSE_FEATURE_UUID: &'static str = "00002a51-0000-1000-8000-00805f9b34fb";
+pub static RECORD_ACCESS_CONTROL_POINT_UUID: &'static str = "00002a52-0000-1000-8000-00805f9b34fb";
+
+const FLAG_TIME_OFFSET: u8 = 0x01;
+const FLAG_CONCENTRATION: u8 = 0x02;
+const FLAG_MOL_PER_LITER: u8 = 0x04;
+const FLAG_SENSOR_STATUS: u8 = 0x08;
+const FLAG_CONTEXT_FOLLOWS: u8 = 0x10;
+
+// Record Access Control Point op codes and operators.
+const RACP_REPORT_STORED_RECORDS: u8 = 0x01;
+const RACP_DELETE_STORED_RECORDS: u8 = 0x02;
+const RACP_ABORT: u8 = 0x03;
+const RACP_REPORT_NUMBER_OF_RECORDS: u8 = 0x04;
+const RACP_NUMBER_OF_RECORDS_RESPONSE: u8 = 0x05;
+const RACP_RESPONSE_CODE: u8 = 0x06;
+const RACP_SUCCESS: u8 = 0x01;
+const RACP_OPERATOR_NULL: u8 = 0x00;
+const RACP_OPERATOR_ALL: u8 = 0x01;
+const RACP_OPERATOR_GREATER_OR_EQUAL: u8 = 0x03;
+const RACP_FILTER_SEQUENCE_NUMBER: u8 = 0x01;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConcentrationUnit {
+    KilogramPerLiter,
+    MolePerLiter,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlucoseConcentration {
+    pub value: f64,
+    pub unit: ConcentrationUnit,
+    // Fluid type, 1 = capillary whole blood ... 10 = control solution.
+    pub sample_type: u8,
+    // 1 = finger, 2 = alternate site test, 3 = earlobe, 4 = control solution,
+    // 15 = not available.
+    pub sample_location: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlucoseMeasurement {
+    pub sequence_number: u16,
+    pub base_time: DateTime,
+    // Minutes to add to `base_time` to get the user facing time.
+    pub time_offset: Option<i16>,
+    pub concentration: Option<GlucoseConcentration>,
+    pub sensor_status: Option<u16>,
+    // A Glucose Measurement Context notification with the same sequence number follows.
+    pub context_follows: bool,
+}
+
+impl GlucoseMeasurement {
+    pub fn parse(value: &[u8]) -> Result<GlucoseMeasurement, Box<Error>> {
+        try!(check_length("Glucose Measurement", value, 10));
+        let flags = value[0];
+        let mut offset = 10;
+
+        let time_offset = if flags & FLAG_TIME_OFFSET != 0 {
+            try!(check_length("Glucose Measurement", value, offset + 2));
+            offset += 2;
+            Some(read_i16(value, offset - 2))
+        } else {
+            None
+        };
+
+        let concentration = if flags & FLAG_CONCENTRATION != 0 {
+            try!(check_length("Glucose Measurement", value, offset + 3));
+            offset += 3;
+            Some(GlucoseConcentration {
+                value: read_sfloat(value, offset - 3),
+                unit: if flags & FLAG_MOL_PER_LITER != 0 {
+                    ConcentrationUnit::MolePerLiter
+                } else {
+                    ConcentrationUnit::KilogramPerLiter
+                },
+                sample_type: value[offset - 1] & 0x0F,
+                sample_location: value[offset - 1] >> 4,
+            })
+        } else {
+            None
+        };
+
+        let sensor_status = if flags & FLAG_SENSOR_STATUS != 0 {
+            try!(check_length("Glucose Measurement", value, offset + 2));
+            Some(read_u16(value, offset))
+        } else {
+            None
+        };
+
+        Ok(GlucoseMeasurement {
+            sequence_number: read_u16(value, 1),
+            base_time: try!(DateTime::parse(&value[3..])),
+            time_offset: time_offset,
+            concentration: concentration,
+            sensor_status: sensor_status,
+            context_follows: flags & FLAG_CONTEXT_FOLLOWS != 0,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordAccessResponse {
+    NumberOfRecords(u16),
+    // Request op code and response code, 0x01 is success and 0x06 no records found.
+    ResponseCode(u8, u8),
+}
+
+impl RecordAccessResponse {
+    pub fn parse(value: &[u8]) -> Result<RecordAccessResponse, Box<Error>> {
+        try!(check_length("Record Access Control Point", value, 4));
+        match value[0] {
+            RACP_NUMBER_OF_RECORDS_RESPONSE => {
+                Ok(RecordAccessResponse::NumberOfRecords(read_u16(value, 2)))
+            }
+            RACP_RESPONSE_CODE => Ok(RecordAccessResponse::ResponseCode(value[2], value[3])),
+            other => Err(Box::from(format!(
+                "Unexpected Record Access Control Point op code {}",
+                other
+            ))),
+        }
+    }
+}
+
+// A Record Access Control Point request answered with a response code other than
+// success, e.g. 0x06 when no records were found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecordAccessError {
+    pub response_code: u8,
+}
+
+impl fmt::Display for RecordAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self.response_code {
+            0x02 => "op code not supported",
+            0x03 => "invalid operator",
+            0x04 => "operator not supported",
+            0x05 => "invalid operand",
+            0x06 => "no records found",
+            0x07 => "abort unsuccessful",
+            0x08 => "procedure not completed",
+            0x09 => "operand not supported",
+            _ => "unknown response code",
+        };
+        write!(
+            f,
+            "Record Access Control Point request failed: {} (0x{:02x})",
+            reason, self.response_code
+        )
+    }
+}
+
+impl Error for RecordAccessError {}
+
+// A value notified while reading the stored records.
+enum RecordNotification {
+    Measurement(Vec<u8>),
+    RecordAccess(Vec<u8>),
+}
+
+pub struct GlucoseService<'a> {
+    measurement: BluetoothGATTCharacteristic<'a>,
+    feature: BluetoothGATTCharacteristic<'a>,
+    record_access: BluetoothGATTCharacteristic<'a>,
+}
+
+impl<'a> GlucoseService<'a> {
+    pub fn new(device: &BluetoothDevice<'a>) -> Result<GlucoseService<'a>, Box<Error>> {
+        let characteristics = try!(ServiceCharacteristics::fetch(device, GLUCOSE_SERVICE_UUID));
+        Ok(GlucoseService {
+            measurement: try!(
+                characteristics.require(GLUCOSE_MEASUREMENT_UUID, "Glucose Measurement")
+            ),
+            feature: try!(characteristics.require(GLUCOSE_FEATURE_UUID, "Glucose Feature")),
+            record_access: try!(characteristics.require(
+                RECORD_ACCESS_CONTROL_POINT_UUID,
+                "Record Access Control Point"
+            )),
+        })
+    }
+
+    pub fn get_feature(&self) -> Result<u16, Box<Error>> {
+        let value = try!(self.feature.read_value(None));
+        try!(check_length("Glucose Feature", &value, 2));
+        Ok(read_u16(&value, 0))
+    }
+
+    // Stored records are only sent in response to a Record Access Control Point
+    // request, which requires both measurement notifications and control point
+    // indications to be enabled.
+    pub fn start_notify(&self) -> Result<(), Box<Error>> {
+        try!(self.measurement.start_notify());
+        self.record_access.start_notify()
+    }
+
+    pub fn stop_notify(&self) -> Result<(), Box<Error>> {
+        try!(self.record_access.stop_notify());
+        self.measurement.stop_notify()
+    }
+
+    pub fn request_all_records(&self) -> Result<(), Box<Error>> {
+        self.write_record_access(vec![RACP_REPORT_STORED_RECORDS, RACP_OPERATOR_ALL])
+    }
+
+    pub fn request_records_since(&self, sequence_number: u16) -> Result<(), Box<Error>> {
+        self.write_record_access(vec![
+            RACP_REPORT_STORED_RECORDS,
+            RACP_OPERATOR_GREATER_OR_EQUAL,
+            RACP_FILTER_SEQUENCE_NUMBER,
+            sequence_number as u8,
+            (sequence_number >> 8) as u8,
+        ])
+    }
+
+    pub fn request_number_of_records(&self) -> Result<(), Box<Error>> {
+        self.write_record_access(vec![RACP_REPORT_NUMBER_OF_RECORDS, RACP_OPERATOR_ALL])
+    }
+
+    pub fn delete_all_records(&self) -> Result<(), Box<Error>> {
+        self.write_record_access(vec![RACP_DELETE_STORED_RECORDS, RACP_OPERATOR_ALL])
+    }
+
+    pub fn abort(&self) -> Result<(), Box<Error>> {
+        self.write_record_access(vec![RACP_ABORT, RACP_OPERATOR_NULL])
+    }
+
+    pub fn measurements(&self, timeout_ms: u32) -> MeasurementStream<'a, GlucoseMeasurement> {
+        MeasurementStream::new(
+            self.measurement.notifications(timeout_ms),
+            GlucoseMeasurement::parse,
+        )
+    }
+
+    pub fn record_access_responses(
+        &self,
+        timeout_ms: u32,
+    ) -> MeasurementStream<'a, RecordAccessResponse> {
+        MeasurementStream::new(
+            self.record_access.notifications(timeout_ms),
+            RecordAccessResponse::parse,
+        )
+    }
+
+    // Requests all stored records and collects them until the Record Access Control
+    // Point reports the end of the transfer. Fails with a RecordAccessError when the
+    // request isn't successful, and once nothing arrives for `timeout_ms`.
+    // Notifications must be enabled with `start_notify`.
+    pub fn read_all_records(&self, timeout_ms: u32) -> Result<Vec<GlucoseMeasurement>, Box<Error>> {
+        try!(self.request_all_records());
+        let session = self.measurement.get_session();
+        let measurement_path = self.measurement.get_id();
+        let record_access_path = self.record_access.get_id();
+        let mut records = Vec::new();
+        loop {
+            let notification = session.wait_for_message(timeout_ms, |msg| {
+                bluetooth_gatt_characteristic::notified_value(msg, &measurement_path)
+                    .map(RecordNotification::Measurement)
+                    .or_else(|| {
+                        bluetooth_gatt_characteristic::notified_value(msg, &record_access_path)
+                            .map(RecordNotification::RecordAccess)
+                    })
+            });
+            match notification {
+                Some(RecordNotification::Measurement(value)) => {
+                    if let Ok(measurement) = GlucoseMeasurement::parse(&value) {
+                        records.push(measurement);
+                    }
+                }
+                Some(RecordNotification::RecordAccess(value)) => {
+                    match RecordAccessResponse::parse(&value) {
+                        Ok(RecordAccessResponse::ResponseCode(
+                            RACP_REPORT_STORED_RECORDS,
+                            RACP_SUCCESS,
+                        )) => return Ok(records),
+                        Ok(RecordAccessResponse::ResponseCode(
+                            RACP_REPORT_STORED_RECORDS,
+                            code,
+                        )) => {
+                            return Err(Box::new(RecordAccessError {
+                                response_code: code,
+                            }))
+                        }
+                        _ => {}
+                    }
+                }
+                None => {
+                    return Err(Box::from(
+                        "Timed out waiting for the Record Access Control Point response",
+                    ))
+                }
+            }
+        }
+    }
+
+    fn write_record_access(&self, value: Vec<u8>) -> Result<(), Box<Error>> {
+        self.record_access.write_value(value, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_measurement_with_all_fields() {
+        // Record 1 at 2019-01-02 03:04:05 shown an hour earlier, 95 mg/dL of capillary
+        // whole blood from a finger, device battery low.
+        let value = [
+            0x1B, 0x01, 0x00, 0xE3, 0x07, 0x01, 0x02, 0x03, 0x04, 0x05, 0xC4, 0xFF, 0x5F, 0xB0,
+            0x11, 0x01, 0x00,
+        ];
+        let measurement = GlucoseMeasurement::parse(&value).unwrap();
+        assert_eq!(measurement.sequence_number, 1);
+        assert_eq!(
+            measurement.base_time,
+            DateTime {
+                year: 2019,
+                month: 1,
+                day: 2,
+                hours: 3,
+                minutes: 4,
+                seconds: 5,
+            }
+        );
+        assert_eq!(measurement.time_offset, Some(-60));
+        let concentration = measurement.concentration.unwrap();
+        assert!((concentration.value - 0.00095).abs() < 1e-12);
+        assert_eq!(concentration.unit, ConcentrationUnit::KilogramPerLiter);
+        assert_eq!(concentration.sample_type, 1);
+        assert_eq!(concentration.sample_location, 1);
+        assert_eq!(measurement.sensor_status, Some(0x0001));
+        assert!(measurement.context_follows);
+    }
+
+    #[test]
+    fn parse_measurement_in_mol_per_liter() {
+        // 5.3 mmol/L as SFLOAT 53e-4 mol/L.
+        let value = [
+            0x06, 0x02, 0x00, 0xE3, 0x07, 0x01, 0x02, 0x03, 0x04, 0x05, 0x35, 0xC0, 0x11,
+        ];
+        let measurement = GlucoseMeasurement::parse(&value).unwrap();
+        assert_eq!(measurement.time_offset, None);
+        let concentration = measurement.concentration.unwrap();
+        assert!((concentration.value - 0.0053).abs() < 1e-12);
+        assert_eq!(concentration.unit, ConcentrationUnit::MolePerLiter);
+        assert_eq!(measurement.sensor_status, None);
+        assert!(!measurement.context_follows);
+    }
+
+    #[test]
+    fn parse_measurement_truncated() {
+        let value = [0x00, 0x01, 0x00, 0xE3, 0x07, 0x01, 0x02, 0x03, 0x04];
+        assert!(GlucoseMeasurement::parse(&value).is_err());
+        // Concentration flagged but cut short.
+        let value = [
+            0x02, 0x01, 0x00, 0xE3, 0x07, 0x01, 0x02, 0x03, 0x04, 0x05, 0x5F, 0xB0,
+        ];
+        assert!(GlucoseMeasurement::parse(&value).is_err());
+    }
+
+    #[test]
+    fn parse_record_access_response() {
+        assert_eq!(
+            RecordAccessResponse::parse(&[0x06, 0x00, 0x01, 0x01]).unwrap(),
+            RecordAccessResponse::ResponseCode(RACP_REPORT_STORED_RECORDS, RACP_SUCCESS)
+        );
+        assert_eq!(
+            RecordAccessResponse::parse(&[0x05, 0x00, 0x0A, 0x00]).unwrap(),
+            RecordAccessResponse::NumberOfRecords(10)
+        );
+        assert!(RecordAccessResponse::parse(&[0x01, 0x01, 0x00, 0x00]).is_err());
+        assert!(RecordAccessResponse::parse(&[0x06, 0x00, 0x01]).is_err());
+    }
+
+    #[test]
+    fn describe_record_access_error() {
+        let err = RecordAccessError {
+            response_code: 0x06,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Record Access Control Point request failed: no records found (0x06)"
+        );
+    }
+}
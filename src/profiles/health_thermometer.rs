@@ -0,0 +1,260 @@
+use bluetooth_device::BluetoothDevice;
+use bluetooth_gatt_characteristic::BluetoothGATTCharacteristic;
+use profiles::{
+    check_length, read_float, read_u16, DateTime, MeasurementStream, ServiceCharacteristics,
+};
+use std::error::Error;
+
+pub static HEALTH_THERMOMETER_SERVICE_UUID: &'static str = "00001809-0000-1000-8000-00805f9b34fb";
+pub static TEMPERATURE_MEASUREMENT_UUID: &'static str = "00002a1c-0000-1000-8000-00805f9b34fb";
+pub static TEMPERATURE_TYPE_UUID: &'static str = "00002a1d-0000-1000-8000-00805f9b34fb";
+pub static INTERMEDIATE_TEMPERATURE_UUID: &'static str = "00002a1e-0000-1000-8000-00805f9b34fb";
+pub static MEASUREMENT_INTERVAL_UUID: &'static str = "00002a21-0000-1000-8000-00805f9b34fb";
+
+const FLAG_FAHRENHEIT: u8 = 0x01;
+const FLAG_TIMESTAMP: u8 = 0x02;
+const FLAG_TEMPERATURE_TYPE: u8 = 0x04;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TemperatureType {
+    Armpit,
+    Body,
+    Ear,
+    Finger,
+    GastroIntestinalTract,
+    Mouth,
+    Rectum,
+    Toe,
+    Tympanum,
+    Unknown(u8),
+}
+
+impl TemperatureType {
+    pub fn from_u8(value: u8) -> TemperatureType {
+        match value {
+            1 => TemperatureType::Armpit,
+            2 => TemperatureType::Body,
+            3 => TemperatureType::Ear,
+            4 => TemperatureType::Finger,
+            5 => TemperatureType::GastroIntestinalTract,
+            6 => TemperatureType::Mouth,
+            7 => TemperatureType::Rectum,
+            8 => TemperatureType::Toe,
+            9 => TemperatureType::Tympanum,
+            other => TemperatureType::Unknown(other),
+        }
+    }
+
+    pub fn parse(value: &[u8]) -> Result<TemperatureType, Box<Error>> {
+        try!(check_length("Temperature Type", value, 1));
+        Ok(TemperatureType::from_u8(value[0]))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TemperatureMeasurement {
+    pub temperature: f64,
+    pub unit: TemperatureUnit,
+    pub timestamp: Option<DateTime>,
+    pub temperature_type: Option<TemperatureType>,
+}
+
+impl TemperatureMeasurement {
+    pub fn parse(value: &[u8]) -> Result<TemperatureMeasurement, Box<Error>> {
+        try!(check_length("Temperature Measurement", value, 5));
+        let flags = value[0];
+        let mut offset = 5;
+
+        let timestamp = if flags & FLAG_TIMESTAMP != 0 {
+            try!(check_length("Temperature Measurement", value, offset + 7));
+            offset += 7;
+            Some(try!(DateTime::parse(&value[offset - 7..])))
+        } else {
+            None
+        };
+
+        let temperature_type = if flags & FLAG_TEMPERATURE_TYPE != 0 {
+            try!(check_length("Temperature Measurement", value, offset + 1));
+            Some(TemperatureType::from_u8(value[offset]))
+        } else {
+            None
+        };
+
+        Ok(TemperatureMeasurement {
+            temperature: read_float(value, 1),
+            unit: if flags & FLAG_FAHRENHEIT != 0 {
+                TemperatureUnit::Fahrenheit
+            } else {
+                TemperatureUnit::Celsius
+            },
+            timestamp: timestamp,
+            temperature_type: temperature_type,
+        })
+    }
+
+    pub fn celsius(&self) -> f64 {
+        match self.unit {
+            TemperatureUnit::Celsius => self.temperature,
+            TemperatureUnit::Fahrenheit => (self.temperature - 32.0) * 5.0 / 9.0,
+        }
+    }
+}
+
+pub struct HealthThermometerService<'a> {
+    measurement: BluetoothGATTCharacteristic<'a>,
+    temperature_type: Option<BluetoothGATTCharacteristic<'a>>,
+    intermediate_temperature: Option<BluetoothGATTCharacteristic<'a>>,
+    measurement_interval: Option<BluetoothGATTCharacteristic<'a>>,
+}
+
+impl<'a> HealthThermometerService<'a> {
+    pub fn new(device: &BluetoothDevice<'a>) -> Result<HealthThermometerService<'a>, Box<Error>> {
+        let characteristics = try!(ServiceCharacteristics::fetch(
+            device,
+            HEALTH_THERMOMETER_SERVICE_UUID
+        ));
+        Ok(HealthThermometerService {
+            measurement: try!(
+                characteristics.require(TEMPERATURE_MEASUREMENT_UUID, "Temperature Measurement")
+            ),
+            temperature_type: characteristics.find(TEMPERATURE_TYPE_UUID),
+            intermediate_temperature: characteristics.find(INTERMEDIATE_TEMPERATURE_UUID),
+            measurement_interval: characteristics.find(MEASUREMENT_INTERVAL_UUID),
+        })
+    }
+
+    // Temperature Measurement is indicated, stored measurements are delivered
+    // once indications are enabled.
+    pub fn start_indicate(&self) -> Result<(), Box<Error>> {
+        self.measurement.start_notify()
+    }
+
+    pub fn stop_indicate(&self) -> Result<(), Box<Error>> {
+        self.measurement.stop_notify()
+    }
+
+    pub fn measurements(&self, timeout_ms: u32) -> MeasurementStream<'a, TemperatureMeasurement> {
+        MeasurementStream::new(
+            self.measurement.notifications(timeout_ms),
+            TemperatureMeasurement::parse,
+        )
+    }
+
+    pub fn start_intermediate_notify(&self) -> Result<(), Box<Error>> {
+        match self.intermediate_temperature {
+            Some(ref c) => c.start_notify(),
+            None => Err(Box::from("Intermediate Temperature not supported")),
+        }
+    }
+
+    pub fn stop_intermediate_notify(&self) -> Result<(), Box<Error>> {
+        match self.intermediate_temperature {
+            Some(ref c) => c.stop_notify(),
+            None => Err(Box::from("Intermediate Temperature not supported")),
+        }
+    }
+
+    pub fn intermediate_measurements(
+        &self,
+        timeout_ms: u32,
+    ) -> Result<MeasurementStream<'a, TemperatureMeasurement>, Box<Error>> {
+        match self.intermediate_temperature {
+            Some(ref c) => Ok(MeasurementStream::new(
+                c.notifications(timeout_ms),
+                TemperatureMeasurement::parse,
+            )),
+            None => Err(Box::from("Intermediate Temperature not supported")),
+        }
+    }
+
+    pub fn get_temperature_type(&self) -> Result<TemperatureType, Box<Error>> {
+        match self.temperature_type {
+            Some(ref c) => TemperatureType::parse(&try!(c.read_value(None))),
+            None => Err(Box::from("Temperature Type not supported")),
+        }
+    }
+
+    // Seconds between measurements, 0 means no periodic measurements.
+    pub fn get_measurement_interval(&self) -> Result<u16, Box<Error>> {
+        match self.measurement_interval {
+            Some(ref c) => {
+                let value = try!(c.read_value(None));
+                try!(check_length("Measurement Interval", &value, 2));
+                Ok(read_u16(&value, 0))
+            }
+            None => Err(Box::from("Measurement Interval not supported")),
+        }
+    }
+
+    pub fn set_measurement_interval(&self, seconds: u16) -> Result<(), Box<Error>> {
+        match self.measurement_interval {
+            Some(ref c) => c.write_value(vec![seconds as u8, (seconds >> 8) as u8], None),
+            None => Err(Box::from("Measurement Interval not supported")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TemperatureMeasurement, TemperatureType, TemperatureUnit};
+    use profiles::DateTime;
+
+    #[test]
+    fn parse_celsius_with_timestamp_and_type() {
+        // 36.5 °C as FLOAT 365e-1, 2019-01-02 03:04:05, measured in the ear.
+        let value = [
+            0x06, 0x6D, 0x01, 0x00, 0xFF, 0xE3, 0x07, 0x01, 0x02, 0x03, 0x04, 0x05, 0x03,
+        ];
+        let measurement = TemperatureMeasurement::parse(&value).unwrap();
+        assert!((measurement.temperature - 36.5).abs() < 1e-9);
+        assert_eq!(measurement.unit, TemperatureUnit::Celsius);
+        assert_eq!(
+            measurement.timestamp,
+            Some(DateTime {
+                year: 2019,
+                month: 1,
+                day: 2,
+                hours: 3,
+                minutes: 4,
+                seconds: 5,
+            })
+        );
+        assert_eq!(measurement.temperature_type, Some(TemperatureType::Ear));
+    }
+
+    #[test]
+    fn parse_fahrenheit() {
+        // 98.6 °F as FLOAT 986e-1.
+        let measurement = TemperatureMeasurement::parse(&[0x01, 0xDA, 0x03, 0x00, 0xFF]).unwrap();
+        assert_eq!(measurement.unit, TemperatureUnit::Fahrenheit);
+        assert!((measurement.celsius() - 37.0).abs() < 1e-9);
+        assert_eq!(measurement.timestamp, None);
+        assert_eq!(measurement.temperature_type, None);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert!(TemperatureMeasurement::parse(&[0x00, 0x6D, 0x01, 0x00]).is_err());
+        assert!(TemperatureMeasurement::parse(&[0x04, 0x6D, 0x01, 0x00, 0xFF]).is_err());
+        assert!(TemperatureMeasurement::parse(&[0x02, 0x6D, 0x01, 0x00, 0xFF, 0xE3]).is_err());
+    }
+
+    #[test]
+    fn parse_temperature_type() {
+        assert_eq!(
+            TemperatureType::parse(&[0x01]).unwrap(),
+            TemperatureType::Armpit
+        );
+        assert_eq!(
+            TemperatureType::parse(&[0x0A]).unwrap(),
+            TemperatureType::Unknown(10)
+        );
+        assert!(TemperatureType::parse(&[]).is_err());
+    }
+}
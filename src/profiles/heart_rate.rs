@@ -0,0 +1,228 @@
+use bluetooth_device::BluetoothDevice;
+use bluetooth_gatt_characteristic::BluetoothGATTCharacteristic;
+use profiles::{check_length, read_u16, MeasurementStream, ServiceCharacteristics};
+use std::error::Error;
+
+pub static HEART_RATE_SERVICE_UUID: &'static str = "0000180d-0000-1000-8000-00805f9b34fb";
+pub static HEART_RATE_MEASUREMENT_UUID: &'static str = "00002a37-0000-1000-8000-00805f9b34fb";
+pub static BODY_SENSOR_LOCATION_UUID: &'static str = "00002a38-0000-1000-8000-00805f9b34fb";
+pub static HEART_RATE_CONTROL_POINT_UUID: &'static str = "00002a39-0000-1000-8000-00805f9b34fb";
+
+const FLAG_VALUE_FORMAT_U16: u8 = 0x01;
+const FLAG_SENSOR_CONTACT_DETECTED: u8 = 0x02;
+const FLAG_SENSOR_CONTACT_SUPPORTED: u8 = 0x04;
+const FLAG_ENERGY_EXPENDED: u8 = 0x08;
+const FLAG_RR_INTERVALS: u8 = 0x10;
+
+const CONTROL_POINT_RESET_ENERGY_EXPENDED: u8 = 0x01;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeartRateMeasurement {
+    // Beats per minute.
+    pub heart_rate: u16,
+    // None if the sensor does not support contact detection.
+    pub sensor_contact: Option<bool>,
+    // Kilojoules since the last reset.
+    pub energy_expended: Option<u16>,
+    // Raw RR-intervals in 1/1024 second units, oldest first.
+    pub rr_intervals: Vec<u16>,
+}
+
+impl HeartRateMeasurement {
+    pub fn parse(value: &[u8]) -> Result<HeartRateMeasurement, Box<Error>> {
+        try!(check_length("Heart Rate Measurement", value, 2));
+        let flags = value[0];
+        let mut offset = 1;
+
+        let heart_rate = if flags & FLAG_VALUE_FORMAT_U16 != 0 {
+            try!(check_length("Heart Rate Measurement", value, offset + 2));
+            offset += 2;
+            read_u16(value, offset - 2)
+        } else {
+            offset += 1;
+            value[offset - 1] as u16
+        };
+
+        let sensor_contact = if flags & FLAG_SENSOR_CONTACT_SUPPORTED != 0 {
+            Some(flags & FLAG_SENSOR_CONTACT_DETECTED != 0)
+        } else {
+            None
+        };
+
+        let energy_expended = if flags & FLAG_ENERGY_EXPENDED != 0 {
+            try!(check_length("Heart Rate Measurement", value, offset + 2));
+            offset += 2;
+            Some(read_u16(value, offset - 2))
+        } else {
+            None
+        };
+
+        let mut rr_intervals = Vec::new();
+        if flags & FLAG_RR_INTERVALS != 0 {
+            while offset + 2 <= value.len() {
+                rr_intervals.push(read_u16(value, offset));
+                offset += 2;
+            }
+        }
+
+        Ok(HeartRateMeasurement {
+            heart_rate: heart_rate,
+            sensor_contact: sensor_contact,
+            energy_expended: energy_expended,
+            rr_intervals: rr_intervals,
+        })
+    }
+
+    pub fn rr_intervals_ms(&self) -> Vec<f64> {
+        self.rr_intervals
+            .iter()
+            .map(|rr| *rr as f64 * 1000.0 / 1024.0)
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BodySensorLocation {
+    Other,
+    Chest,
+    Wrist,
+    Finger,
+    Hand,
+    EarLobe,
+    Foot,
+    Unknown(u8),
+}
+
+impl BodySensorLocation {
+    pub fn parse(value: &[u8]) -> Result<BodySensorLocation, Box<Error>> {
+        try!(check_length("Body Sensor Location", value, 1));
+        Ok(match value[0] {
+            0 => BodySensorLocation::Other,
+            1 => BodySensorLocation::Chest,
+            2 => BodySensorLocation::Wrist,
+            3 => BodySensorLocation::Finger,
+            4 => BodySensorLocation::Hand,
+            5 => BodySensorLocation::EarLobe,
+            6 => BodySensorLocation::Foot,
+            other => BodySensorLocation::Unknown(other),
+        })
+    }
+}
+
+pub struct HeartRateService<'a> {
+    measurement: BluetoothGATTCharacteristic<'a>,
+    body_sensor_location: Option<BluetoothGATTCharacteristic<'a>>,
+    control_point: Option<BluetoothGATTCharacteristic<'a>>,
+}
+
+impl<'a> HeartRateService<'a> {
+    pub fn new(device: &BluetoothDevice<'a>) -> Result<HeartRateService<'a>, Box<Error>> {
+        let characteristics = try!(ServiceCharacteristics::fetch(
+            device,
+            HEART_RATE_SERVICE_UUID
+        ));
+        Ok(HeartRateService {
+            measurement: try!(
+                characteristics.require(HEART_RATE_MEASUREMENT_UUID, "Heart Rate Measurement")
+            ),
+            body_sensor_location: characteristics.find(BODY_SENSOR_LOCATION_UUID),
+            control_point: characteristics.find(HEART_RATE_CONTROL_POINT_UUID),
+        })
+    }
+
+    pub fn start_notify(&self) -> Result<(), Box<Error>> {
+        self.measurement.start_notify()
+    }
+
+    pub fn stop_notify(&self) -> Result<(), Box<Error>> {
+        self.measurement.stop_notify()
+    }
+
+    pub fn measurements(&self, timeout_ms: u32) -> MeasurementStream<'a, HeartRateMeasurement> {
+        MeasurementStream::new(
+            self.measurement.notifications(timeout_ms),
+            HeartRateMeasurement::parse,
+        )
+    }
+
+    pub fn get_body_sensor_location(&self) -> Result<BodySensorLocation, Box<Error>> {
+        match self.body_sensor_location {
+            Some(ref c) => BodySensorLocation::parse(&try!(c.read_value(None))),
+            None => Err(Box::from("Body Sensor Location not supported")),
+        }
+    }
+
+    pub fn reset_energy_expended(&self) -> Result<(), Box<Error>> {
+        match self.control_point {
+            Some(ref c) => c.write_value(vec![CONTROL_POINT_RESET_ENERGY_EXPENDED], None),
+            None => Err(Box::from("Heart Rate Control Point not supported")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BodySensorLocation, HeartRateMeasurement};
+
+    #[test]
+    fn parse_u8_heart_rate_without_contact_support() {
+        assert_eq!(
+            HeartRateMeasurement::parse(&[0x00, 0x48]).unwrap(),
+            HeartRateMeasurement {
+                heart_rate: 72,
+                sensor_contact: None,
+                energy_expended: None,
+                rr_intervals: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_all_fields() {
+        // u16 heart rate, contact detected, energy expended and two RR-intervals.
+        let value = [0x1F, 0x2C, 0x01, 0x10, 0x27, 0x00, 0x04, 0x00, 0x02];
+        let measurement = HeartRateMeasurement::parse(&value).unwrap();
+        assert_eq!(
+            measurement,
+            HeartRateMeasurement {
+                heart_rate: 300,
+                sensor_contact: Some(true),
+                energy_expended: Some(10000),
+                rr_intervals: vec![1024, 512],
+            }
+        );
+        assert_eq!(measurement.rr_intervals_ms(), vec![1000.0, 500.0]);
+    }
+
+    #[test]
+    fn parse_contact_not_detected() {
+        let measurement = HeartRateMeasurement::parse(&[0x04, 0x3C]).unwrap();
+        assert_eq!(measurement.sensor_contact, Some(false));
+    }
+
+    #[test]
+    fn parse_ignores_trailing_rr_byte() {
+        let measurement = HeartRateMeasurement::parse(&[0x10, 0x3C, 0x00, 0x04, 0x01]).unwrap();
+        assert_eq!(measurement.rr_intervals, vec![1024]);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert!(HeartRateMeasurement::parse(&[0x00]).is_err());
+        assert!(HeartRateMeasurement::parse(&[0x01, 0x2C]).is_err());
+        assert!(HeartRateMeasurement::parse(&[0x08, 0x3C, 0x10]).is_err());
+    }
+
+    #[test]
+    fn parse_body_sensor_location() {
+        assert_eq!(
+            BodySensorLocation::parse(&[0x01]).unwrap(),
+            BodySensorLocation::Chest
+        );
+        assert_eq!(
+            BodySensorLocation::parse(&[0x07]).unwrap(),
+            BodySensorLocation::Unknown(7)
+        );
+        assert!(BodySensorLocation::parse(&[]).is_err());
+    }
+}
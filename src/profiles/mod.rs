@@ -0,0 +1,145 @@
+use bluetooth_device::BluetoothDevice;
+use bluetooth_gatt_characteristic::{BluetoothGATTCharacteristic, BluetoothGATTNotifications};
+use bluetooth_utils::{self, check_length, read_u16};
+use formats::ieee11073;
+use std::error::Error;
+
+pub mod battery;
+pub mod blood_pressure;
+pub mod current_time;
+pub mod device_information;
+pub mod environmental_sensing;
+pub mod glucose;
+pub mod health_thermometer;
+pub mod heart_rate;
+pub mod weight_scale;
+
+pub use self::battery::BatteryService;
+pub use self::blood_pressure::BloodPressureService;
+pub use self::current_time::CurrentTimeService;
+pub use self::device_information::DeviceInformationService;
+pub use self::environmental_sensing::EnvironmentalSensingService;
+pub use self::glucose::GlucoseService;
+pub use self::health_thermometer::HealthThermometerService;
+pub use self::heart_rate::HeartRateService;
+pub use self::weight_scale::WeightScaleService;
+
+// Decodes each notified value of a characteristic with `parse`, values that fail
+// to parse are skipped.
+pub struct MeasurementStream<'a, T> {
+    notifications: BluetoothGATTNotifications<'a>,
+    parse: fn(&[u8]) -> Result<T, Box<Error>>,
+}
+
+impl<'a, T> MeasurementStream<'a, T> {
+    pub fn new(
+        notifications: BluetoothGATTNotifications<'a>,
+        parse: fn(&[u8]) -> Result<T, Box<Error>>,
+    ) -> MeasurementStream<'a, T> {
+        MeasurementStream {
+            notifications: notifications,
+            parse: parse,
+        }
+    }
+}
+
+impl<'a, T> Iterator for MeasurementStream<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some(value) = self.notifications.next() {
+            if let Ok(measurement) = (self.parse)(&value) {
+                return Some(measurement);
+            }
+        }
+        None
+    }
+}
+
+// Date Time characteristic (0x2A08) layout, shared by several services.
+// Zero fields mean "not known" per the specification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct DateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+}
+
+impl DateTime {
+    pub fn parse(value: &[u8]) -> Result<DateTime, Box<Error>> {
+        try!(check_length("Date Time", value, 7));
+        Ok(DateTime {
+            year: read_u16(value, 0),
+            month: value[2],
+            day: value[3],
+            hours: value[4],
+            minutes: value[5],
+            seconds: value[6],
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        vec![
+            self.year as u8,
+            (self.year >> 8) as u8,
+            self.month,
+            self.day,
+            self.hours,
+            self.minutes,
+            self.seconds,
+        ]
+    }
+}
+
+// The characteristics of a service, looked up once when the profile is created.
+struct ServiceCharacteristics<'a> {
+    characteristics: Vec<(String, BluetoothGATTCharacteristic<'a>)>,
+}
+
+impl<'a> ServiceCharacteristics<'a> {
+    fn fetch(
+        device: &BluetoothDevice<'a>,
+        service_uuid: &str,
+    ) -> Result<ServiceCharacteristics<'a>, Box<Error>> {
+        Ok(ServiceCharacteristics {
+            characteristics: try!(device.get_service_characteristics(service_uuid)),
+        })
+    }
+
+    fn find(&self, uuid: &str) -> Option<BluetoothGATTCharacteristic<'a>> {
+        self.characteristics
+            .iter()
+            .find(|&&(ref u, _)| bluetooth_utils::uuid_matches(u, uuid))
+            .map(|&(_, ref characteristic)| characteristic.clone())
+    }
+
+    fn require(
+        &self,
+        uuid: &str,
+        name: &str,
+    ) -> Result<BluetoothGATTCharacteristic<'a>, Box<Error>> {
+        match self.find(uuid) {
+            Some(characteristic) => Ok(characteristic),
+            None => Err(Box::from(format!("{} characteristic not found", name))),
+        }
+    }
+}
+
+fn read_i16(value: &[u8], offset: usize) -> i16 {
+    read_u16(value, offset) as i16
+}
+
+fn read_u32(value: &[u8], offset: usize) -> u32 {
+    read_u16(value, offset) as u32 | (read_u16(value, offset + 2) as u32) << 16
+}
+
+fn read_sfloat(value: &[u8], offset: usize) -> f64 {
+    ieee11073::sfloat_to_f64(read_u16(value, offset))
+}
+
+fn read_float(value: &[u8], offset: usize) -> f64 {
+    ieee11073::float_to_f64(read_u32(value, offset))
+}
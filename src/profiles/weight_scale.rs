@@ -0,0 +1,242 @@
+use bluetooth_device::BluetoothDevice;
+use bluetooth_gatt_characteristic::BluetoothGATTCharacteristic;
+use profiles::{
+    check_length, read_u16, read_u32, DateTime, MeasurementStream, ServiceCharacteristics,
+};
+use std::error::Error;
+
+pub static WEIGHT_SCALE_SERVICE_UUID: &'static str = "0000181d-0000-1000-8000-00805f9b34fb";
+pub static WEIGHT_MEASUREMENT_UUID: &'static str = "00002a9d-0000-1000-8000-00805f9b34fb";
+pub static WEIGHT_SCALE_FEATURE_UUID: &'static str = "00002a9e-0000-1000-8000-00805f9b34fb";
+
+const FLAG_IMPERIAL: u8 = 0x01;
+const FLAG_TIMESTAMP: u8 = 0x02;
+const FLAG_USER_ID: u8 = 0x04;
+const FLAG_BMI_AND_HEIGHT: u8 = 0x08;
+
+// User ID value for a measurement that could not be assigned to a user.
+pub const UNKNOWN_USER: u8 = 0xFF;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeightUnit {
+    // Kilograms and meters.
+    Metric,
+    // Pounds and inches.
+    Imperial,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WeightMeasurement {
+    // NaN when the measurement was unsuccessful.
+    pub weight: f64,
+    pub unit: WeightUnit,
+    pub timestamp: Option<DateTime>,
+    pub user_id: Option<u8>,
+    pub bmi: Option<f64>,
+    pub height: Option<f64>,
+}
+
+impl WeightMeasurement {
+    pub fn parse(value: &[u8]) -> Result<WeightMeasurement, Box<Error>> {
+        try!(check_length("Weight Measurement", value, 3));
+        let flags = value[0];
+        let imperial = flags & FLAG_IMPERIAL != 0;
+        let mut offset = 3;
+
+        let raw_weight = read_u16(value, 1);
+        let weight = if raw_weight == 0xFFFF {
+            ::std::f64::NAN
+        } else if imperial {
+            raw_weight as f64 * 0.01
+        } else {
+            raw_weight as f64 * 0.005
+        };
+
+        let timestamp = if flags & FLAG_TIMESTAMP != 0 {
+            try!(check_length("Weight Measurement", value, offset + 7));
+            offset += 7;
+            Some(try!(DateTime::parse(&value[offset - 7..])))
+        } else {
+            None
+        };
+
+        let user_id = if flags & FLAG_USER_ID != 0 {
+            try!(check_length("Weight Measurement", value, offset + 1));
+            offset += 1;
+            Some(value[offset - 1])
+        } else {
+            None
+        };
+
+        let (bmi, height) = if flags & FLAG_BMI_AND_HEIGHT != 0 {
+            try!(check_length("Weight Measurement", value, offset + 4));
+            let height = read_u16(value, offset + 2) as f64;
+            (
+                Some(read_u16(value, offset) as f64 * 0.1),
+                Some(if imperial {
+                    height * 0.1
+                } else {
+                    height * 0.001
+                }),
+            )
+        } else {
+            (None, None)
+        };
+
+        Ok(WeightMeasurement {
+            weight: weight,
+            unit: if imperial {
+                WeightUnit::Imperial
+            } else {
+                WeightUnit::Metric
+            },
+            timestamp: timestamp,
+            user_id: user_id,
+            bmi: bmi,
+            height: height,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WeightScaleFeature {
+    pub timestamp_supported: bool,
+    pub multiple_users_supported: bool,
+    pub bmi_supported: bool,
+    // Raw resolution fields, 0 means not specified.
+    pub weight_resolution: u8,
+    pub height_resolution: u8,
+}
+
+impl WeightScaleFeature {
+    pub fn parse(value: &[u8]) -> Result<WeightScaleFeature, Box<Error>> {
+        try!(check_length("Weight Scale Feature", value, 4));
+        let bits = read_u32(value, 0);
+        Ok(WeightScaleFeature {
+            timestamp_supported: bits & 0x01 != 0,
+            multiple_users_supported: bits & 0x02 != 0,
+            bmi_supported: bits & 0x04 != 0,
+            weight_resolution: ((bits >> 3) & 0x0F) as u8,
+            height_resolution: ((bits >> 7) & 0x07) as u8,
+        })
+    }
+}
+
+pub struct WeightScaleService<'a> {
+    measurement: BluetoothGATTCharacteristic<'a>,
+    feature: BluetoothGATTCharacteristic<'a>,
+}
+
+impl<'a> WeightScaleService<'a> {
+    pub fn new(device: &BluetoothDevice<'a>) -> Result<WeightScaleService<'a>, Box<Error>> {
+        let characteristics = try!(ServiceCharacteristics::fetch(
+            device,
+            WEIGHT_SCALE_SERVICE_UUID
+        ));
+        Ok(WeightScaleService {
+            measurement: try!(
+                characteristics.require(WEIGHT_MEASUREMENT_UUID, "Weight Measurement")
+            ),
+            feature: try!(
+                characteristics.require(WEIGHT_SCALE_FEATURE_UUID, "Weight Scale Feature")
+            ),
+        })
+    }
+
+    pub fn get_feature(&self) -> Result<WeightScaleFeature, Box<Error>> {
+        WeightScaleFeature::parse(&try!(self.feature.read_value(None)))
+    }
+
+    pub fn start_indicate(&self) -> Result<(), Box<Error>> {
+        self.measurement.start_notify()
+    }
+
+    pub fn stop_indicate(&self) -> Result<(), Box<Error>> {
+        self.measurement.stop_notify()
+    }
+
+    pub fn measurements(&self, timeout_ms: u32) -> MeasurementStream<'a, WeightMeasurement> {
+        MeasurementStream::new(
+            self.measurement.notifications(timeout_ms),
+            WeightMeasurement::parse,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WeightMeasurement, WeightScaleFeature, WeightUnit};
+    use profiles::DateTime;
+
+    fn assert_close(value: Option<f64>, expected: f64) {
+        let value = value.unwrap();
+        assert!((value - expected).abs() < 1e-9, "{} != {}", value, expected);
+    }
+
+    #[test]
+    fn parse_metric_with_all_fields() {
+        // 70 kg, 2019-01-02 03:04:05, user 3, BMI 22.9, 1.75 m.
+        let value = [
+            0x0E, 0xB0, 0x36, 0xE3, 0x07, 0x01, 0x02, 0x03, 0x04, 0x05, 0x03, 0xE5, 0x00, 0xD6,
+            0x06,
+        ];
+        let measurement = WeightMeasurement::parse(&value).unwrap();
+        assert_close(Some(measurement.weight), 70.0);
+        assert_eq!(measurement.unit, WeightUnit::Metric);
+        assert_eq!(
+            measurement.timestamp,
+            Some(DateTime {
+                year: 2019,
+                month: 1,
+                day: 2,
+                hours: 3,
+                minutes: 4,
+                seconds: 5,
+            })
+        );
+        assert_eq!(measurement.user_id, Some(3));
+        assert_close(measurement.bmi, 22.9);
+        assert_close(measurement.height, 1.75);
+    }
+
+    #[test]
+    fn parse_imperial() {
+        // 154.32 lb, BMI 22.9, 68.9 in.
+        let value = [0x09, 0x48, 0x3C, 0xE5, 0x00, 0xB1, 0x02];
+        let measurement = WeightMeasurement::parse(&value).unwrap();
+        assert_close(Some(measurement.weight), 154.32);
+        assert_eq!(measurement.unit, WeightUnit::Imperial);
+        assert_eq!(measurement.timestamp, None);
+        assert_eq!(measurement.user_id, None);
+        assert_close(measurement.height, 68.9);
+    }
+
+    #[test]
+    fn parse_unsuccessful_measurement() {
+        let measurement = WeightMeasurement::parse(&[0x00, 0xFF, 0xFF]).unwrap();
+        assert!(measurement.weight.is_nan());
+    }
+
+    #[test]
+    fn parse_truncated() {
+        assert!(WeightMeasurement::parse(&[0x00, 0xB0]).is_err());
+        assert!(WeightMeasurement::parse(&[0x04, 0xB0, 0x36]).is_err());
+        assert!(WeightMeasurement::parse(&[0x08, 0xB0, 0x36, 0xE5, 0x00, 0xD6]).is_err());
+    }
+
+    #[test]
+    fn parse_feature() {
+        // All features, weight resolution 4 (0.05 kg), height resolution 1 (0.01 m).
+        assert_eq!(
+            WeightScaleFeature::parse(&[0xA7, 0x00, 0x00, 0x00]).unwrap(),
+            WeightScaleFeature {
+                timestamp_supported: true,
+                multiple_users_supported: true,
+                bmi_supported: true,
+                weight_resolution: 4,
+                height_resolution: 1,
+            }
+        );
+        assert!(WeightScaleFeature::parse(&[0xA7, 0x00, 0x00]).is_err());
+    }
+}